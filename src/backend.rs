@@ -2,17 +2,24 @@ use manga_tui::exists;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs::{create_dir, create_dir_all, File};
+use std::fs::{create_dir, create_dir_all, remove_file, File};
 use std::path::{Path, PathBuf};
 use strum::Display;
 
 use self::error_log::ERROR_LOGS_FILE;
 
+pub mod blocklist;
+pub mod cache;
+pub mod clipboard;
+pub mod color_depth;
 pub mod database;
 pub mod download;
 pub mod error_log;
+pub mod export;
 pub mod fetch;
 pub mod filter;
+pub mod library;
+pub mod session;
 pub mod tui;
 
 #[derive(Display)]
@@ -23,6 +30,8 @@ pub enum AppDirectories {
     ErrorLogs,
     #[strum(to_string = "history")]
     History,
+    #[strum(to_string = "config")]
+    Config,
 }
 
 pub static APP_DATA_DIR: Lazy<Option<PathBuf>> = Lazy::new(|| {
@@ -54,6 +63,10 @@ pub fn build_data_dir() -> Result<(), std::io::Error> {
                 create_dir(dir.join(AppDirectories::History.to_string()))?;
             }
 
+            if !exists!(&dir.join(AppDirectories::Config.to_string())) {
+                create_dir(dir.join(AppDirectories::Config.to_string()))?;
+            }
+
             if !exists!(&dir
                 .join(AppDirectories::ErrorLogs.to_string())
                 .join(ERROR_LOGS_FILE))
@@ -70,6 +83,38 @@ pub fn build_data_dir() -> Result<(), std::io::Error> {
     }
 }
 
+/// Whether a file can be created on the data directory and on each of the directories the app
+/// writes to, they may exist but be read only, for example on a read only file system
+pub fn is_data_dir_writable() -> bool {
+    let Some(dir) = APP_DATA_DIR.as_ref() else {
+        return false;
+    };
+
+    let directories = [
+        AppDirectories::MangaDownloads,
+        AppDirectories::ErrorLogs,
+        AppDirectories::History,
+        AppDirectories::Config,
+    ];
+
+    is_writable(dir)
+        && directories
+            .iter()
+            .all(|directory| is_writable(&dir.join(directory.to_string())))
+}
+
+fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(".manga-tui-write-check");
+
+    match File::create(&probe) {
+        Ok(_) => {
+            remove_file(&probe).ok();
+            true
+        }
+        Err(_) => false,
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchMangaResponse {
@@ -93,6 +138,9 @@ pub struct Data {
 #[serde(rename_all = "camelCase")]
 pub struct Attributes {
     pub title: Title,
+    /// Titles in other languages, each one maps the code of its language to the title
+    #[serde(default)]
+    pub alt_titles: Vec<HashMap<String, String>>,
     pub description: Option<Description>,
     pub status: String,
     pub tags: Vec<Tag>,
@@ -101,6 +149,14 @@ pub struct Attributes {
     pub created_at: String,
     pub publication_demographic: Option<String>,
     pub available_translated_languages: Vec<Option<String>>,
+    /// Ids of the manga on other sites, the key is the site, for example `al` for anilist
+    #[serde(default)]
+    pub links: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub year: Option<u32>,
+    /// ISO code of the language the manga was published in
+    #[serde(default)]
+    pub original_language: Option<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -114,14 +170,36 @@ pub struct Title {
     pub ko: Option<String>,
     #[serde(rename = "zh-ro")]
     pub zh_ro: Option<String>,
-    #[serde(rename = "zh-ro")]
+    #[serde(rename = "ko-ro")]
     pub ko_ro: Option<String>,
 }
 
+impl Title {
+    pub fn in_language(&self, code: &str) -> Option<&String> {
+        match code {
+            "en" => self.en.as_ref(),
+            "ja" => self.ja.as_ref(),
+            "ja-ro" => self.ja_ro.as_ref(),
+            "jp" => self.jp.as_ref(),
+            "zh" => self.zh.as_ref(),
+            "ko" => self.ko.as_ref(),
+            "zh-ro" => self.zh_ro.as_ref(),
+            "ko-ro" => self.ko_ro.as_ref(),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Description {
-    pub en: Option<String>,
+pub struct Description(pub HashMap<String, String>);
+
+impl Description {
+    pub fn in_language(&self, code: &str) -> Option<&String> {
+        self.0
+            .get(code)
+            .filter(|description| !description.is_empty())
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -152,6 +230,9 @@ pub struct Tag {
 #[serde(rename_all = "camelCase")]
 pub struct TagAtributtes {
     pub name: Name,
+    /// Tags without a group take the `default` color of `tag_colors`
+    #[serde(default)]
+    pub group: String,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -330,3 +411,33 @@ pub mod authors {
         pub version: i64,
     }
 }
+
+pub mod covers {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CoversResponse {
+        pub result: String,
+        pub response: String,
+        pub data: Vec<Data>,
+        pub total: u32,
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Data {
+        pub id: String,
+        #[serde(rename = "type")]
+        pub type_field: String,
+        pub attributes: Attributes,
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Attributes {
+        pub volume: Option<String>,
+        pub file_name: String,
+        pub locale: Option<String>,
+    }
+}
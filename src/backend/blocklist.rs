@@ -0,0 +1,84 @@
+use once_cell::sync::Lazy;
+use std::sync::{RwLock, RwLockReadGuard};
+
+use super::database::{add_to_blocklist, get_blocklist, remove_from_blocklist};
+use super::Data;
+
+pub use super::database::{BlockedItem, BlockedKind};
+
+/// In memory copy of the blocklist stored in the database, it's checked every time mangas are
+/// fetched so the database is only queried when the app starts
+pub static BLOCKLIST: Lazy<RwLock<Blocklist>> = Lazy::new(|| RwLock::new(Blocklist::default()));
+
+/// Mangas and tags the user never wants to see, mangas with a blocked tag are excluded from the
+/// search query and any manga which still makes it into the results is removed after fetching
+#[derive(Debug, Default, Clone)]
+pub struct Blocklist {
+    pub items: Vec<BlockedItem>,
+}
+
+impl Blocklist {
+    pub fn get() -> RwLockReadGuard<'static, Blocklist> {
+        BLOCKLIST.read().unwrap()
+    }
+
+    pub fn load() -> rusqlite::Result<()> {
+        let items = get_blocklist()?;
+        BLOCKLIST.write().unwrap().items = items;
+        Ok(())
+    }
+
+    pub fn block(item: BlockedItem) -> rusqlite::Result<()> {
+        add_to_blocklist(&item)?;
+
+        let mut blocklist = BLOCKLIST.write().unwrap();
+        blocklist
+            .items
+            .retain(|blocked| !(blocked.id == item.id && blocked.kind == item.kind));
+        blocklist.items.insert(0, item);
+
+        Ok(())
+    }
+
+    pub fn unblock(id: &str, kind: BlockedKind) -> rusqlite::Result<()> {
+        remove_from_blocklist(id, kind)?;
+
+        BLOCKLIST
+            .write()
+            .unwrap()
+            .items
+            .retain(|blocked| !(blocked.id == id && blocked.kind == kind));
+
+        Ok(())
+    }
+
+    fn is_blocked(&self, id: &str, kind: BlockedKind) -> bool {
+        self.items
+            .iter()
+            .any(|blocked| blocked.kind == kind && blocked.id == id)
+    }
+
+    pub fn blocked_tag_ids(&self) -> impl Iterator<Item = &str> {
+        self.items
+            .iter()
+            .filter(|blocked| blocked.kind == BlockedKind::Tag)
+            .map(|blocked| blocked.id.as_str())
+    }
+
+    pub fn is_hidden(&self, manga: &Data) -> bool {
+        self.is_blocked(&manga.id, BlockedKind::Manga)
+            || manga
+                .attributes
+                .tags
+                .iter()
+                .any(|tag| self.is_blocked(&tag.id, BlockedKind::Tag))
+    }
+
+    /// Remove the mangas which are blocked or have a blocked tag
+    pub fn filter_mangas(&self, mangas: Vec<Data>) -> Vec<Data> {
+        mangas
+            .into_iter()
+            .filter(|manga| !self.is_hidden(manga))
+            .collect()
+    }
+}
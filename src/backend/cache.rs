@@ -0,0 +1,160 @@
+use bytes::Bytes;
+use manga_tui::exists;
+use once_cell::sync::Lazy;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::config::MangaTuiConfig;
+use crate::global::is_persistence_disabled;
+
+/// Where the pages of the chapters that are read are stored so going back and forth between pages
+/// or reading a chapter again does not fetch them again
+pub static PAGES_CACHE_DIR: Lazy<Option<PathBuf>> = Lazy::new(|| {
+    directories::ProjectDirs::from("", "", "manga-tui").map(|dirs| dirs.cache_dir().join("pages"))
+});
+
+/// Where the pages of a chapter are written to open them in an external image viewer, it's removed
+/// when the app is closed
+pub static EXTERNAL_VIEWER_DIR: Lazy<PathBuf> =
+    Lazy::new(|| std::env::temp_dir().join("manga-tui-viewer"));
+
+/// Bytes taken by the cached pages, `None` until the cache directory is scanned for the first time.
+/// Pages written again are counted twice, which only makes the next eviction scan a bit earlier
+static CACHE_SIZE: Mutex<Option<u64>> = Mutex::new(None);
+
+fn cache_budget_in_bytes() -> u64 {
+    MangaTuiConfig::get().reader_cache_size_mb * 1024 * 1024
+}
+
+fn page_file_name(chapter_id: &str, page_type: &str, index: usize) -> String {
+    format!("{chapter_id}_{page_type}_{index}")
+}
+
+/// Returns the page if it was cached, the file's modification time is updated so it's the last
+/// one to be evicted
+pub fn get_cached_page(chapter_id: &str, page_type: &str, index: usize) -> Option<Bytes> {
+    if cache_budget_in_bytes() == 0 {
+        return None;
+    }
+
+    let page = PAGES_CACHE_DIR
+        .as_ref()?
+        .join(page_file_name(chapter_id, page_type, index));
+
+    let bytes = fs::read(&page).ok()?;
+
+    if let Ok(file) = File::options().append(true).open(&page) {
+        file.set_modified(SystemTime::now()).ok();
+    }
+
+    Some(Bytes::from(bytes))
+}
+
+pub fn cache_page(
+    chapter_id: &str,
+    page_type: &str,
+    index: usize,
+    bytes: &[u8],
+) -> Result<(), std::io::Error> {
+    let budget = cache_budget_in_bytes();
+
+    if budget == 0 || is_persistence_disabled() {
+        return Ok(());
+    }
+
+    let cache_dir = PAGES_CACHE_DIR
+        .as_ref()
+        .ok_or(std::io::Error::other("cache dir could not be found"))?;
+
+    if !exists!(cache_dir) {
+        fs::create_dir_all(cache_dir)?;
+    }
+
+    let mut page = File::create(cache_dir.join(page_file_name(chapter_id, page_type, index)))?;
+    page.write_all(bytes)?;
+
+    let mut cache_size = CACHE_SIZE.lock().unwrap();
+
+    let size = match *cache_size {
+        Some(size) => size + bytes.len() as u64,
+        None => directory_size(cache_dir)?,
+    };
+
+    // The directory is only scanned again once the pages written go over the budget
+    *cache_size = Some(if size > budget {
+        evict_least_recently_used(cache_dir, budget)?
+    } else {
+        size
+    });
+
+    Ok(())
+}
+
+fn directory_size(dir: &Path) -> Result<u64, std::io::Error> {
+    Ok(fs::read_dir(dir)?
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum())
+}
+
+/// Remove the pages which were used the longest time ago until the cache fits in the budget and
+/// return the size left. Pages which can't be removed are skipped, the ones already removed (by
+/// another instance of the app for example) are counted as removed
+fn evict_least_recently_used(cache_dir: &Path, budget: u64) -> Result<u64, std::io::Error> {
+    let mut pages: Vec<(PathBuf, u64, SystemTime)> = fs::read_dir(cache_dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((
+                entry.path(),
+                metadata.len(),
+                metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            ))
+        })
+        .collect();
+
+    let mut total_size: u64 = pages.iter().map(|(_, size, _)| size).sum();
+
+    if total_size <= budget {
+        return Ok(total_size);
+    }
+
+    pages.sort_by_key(|(_, _, modified)| *modified);
+
+    for (path, size, _) in pages {
+        if total_size <= budget {
+            break;
+        }
+
+        match fs::remove_file(path) {
+            Ok(()) => total_size = total_size.saturating_sub(size),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                total_size = total_size.saturating_sub(size)
+            }
+            Err(_) => {}
+        }
+    }
+
+    Ok(total_size)
+}
+
+pub fn clear_external_viewer_dir() -> Result<(), std::io::Error> {
+    if exists!(&*EXTERNAL_VIEWER_DIR) {
+        fs::remove_dir_all(&*EXTERNAL_VIEWER_DIR)
+    } else {
+        Ok(())
+    }
+}
+
+pub fn clear_cache() -> Result<(), std::io::Error> {
+    *CACHE_SIZE.lock().unwrap() = None;
+
+    match PAGES_CACHE_DIR.as_ref() {
+        Some(cache_dir) if exists!(cache_dir) => fs::remove_dir_all(cache_dir),
+        _ => Ok(()),
+    }
+}
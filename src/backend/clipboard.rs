@@ -0,0 +1,58 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use super::APP_DATA_DIR;
+
+/// Written to the data directory when there is no clipboard, for example over ssh or on a tty
+pub static SHARED_TEXT_FILE: &str = "manga-tui-shared.txt";
+
+/// Programs which set the clipboard, they are tried in order until one of them works
+const CLIPBOARD_COMMANDS: [(&str, &[&str]); 5] = [
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("pbcopy", &[]),
+    ("clip.exe", &[]),
+];
+
+/// Where the text ended up
+pub enum CopiedTo {
+    Clipboard,
+    File(PathBuf),
+}
+
+fn copy_with(program: &str, args: &[&str], text: &str) -> std::io::Result<bool> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    Ok(child.wait()?.success())
+}
+
+/// Copy the text to the clipboard, if none of the clipboard programs is available it's written to
+/// `SHARED_TEXT_FILE` instead. It waits for the program, so it's better called from a blocking
+/// task
+pub fn copy_to_clipboard(text: &str) -> std::io::Result<CopiedTo> {
+    for (program, args) in CLIPBOARD_COMMANDS {
+        if let Ok(true) = copy_with(program, args, text) {
+            return Ok(CopiedTo::Clipboard);
+        }
+    }
+
+    let file = APP_DATA_DIR
+        .as_ref()
+        .ok_or_else(|| std::io::Error::other("data dir could not be found"))?
+        .join(SHARED_TEXT_FILE);
+
+    std::fs::write(&file, text)?;
+
+    Ok(CopiedTo::File(file))
+}
@@ -0,0 +1,265 @@
+use image::imageops::{dither, ColorMap};
+use image::{DynamicImage, Rgb};
+use once_cell::sync::Lazy;
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::config::MangaTuiConfig;
+
+/// How many colors the terminal can display
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ColorDepth {
+    #[strum(to_string = "16 colors")]
+    #[serde(rename = "16")]
+    Ansi16,
+    #[strum(to_string = "256 colors")]
+    #[serde(rename = "256")]
+    Ansi256,
+    #[strum(to_string = "truecolor")]
+    #[serde(rename = "truecolor")]
+    TrueColor,
+}
+
+/// The color depth set on the config file, or the detected one if there is none
+pub static COLOR_DEPTH: Lazy<ColorDepth> = Lazy::new(|| {
+    MangaTuiConfig::get().color_depth.unwrap_or_else(|| {
+        color_depth_from_env(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    })
+});
+
+/// Terminals with truecolor support usually set `COLORTERM`, otherwise `TERM` tells whether the
+/// terminal has 256 colors, anything else is assumed to have the basic 16 colors
+pub fn color_depth_from_env(colorterm: Option<&str>, term: Option<&str>) -> ColorDepth {
+    let colorterm = colorterm.unwrap_or_default().to_lowercase();
+    let term = term.unwrap_or_default().to_lowercase();
+
+    if colorterm == "truecolor" || colorterm == "24bit" || term.contains("direct") {
+        ColorDepth::TrueColor
+    } else if term.contains("256") {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+/// The colors of the xterm palette, the first 16 are the basic ones and the rest are a 6x6x6 cube
+/// followed by 24 grays
+struct TerminalPalette {
+    colors: Vec<[u8; 3]>,
+}
+
+const ANSI_16: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [128, 0, 0],
+    [0, 128, 0],
+    [128, 128, 0],
+    [0, 0, 128],
+    [128, 0, 128],
+    [0, 128, 128],
+    [192, 192, 192],
+    [128, 128, 128],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [0, 0, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+];
+
+/// The colors of `ANSI_16` as named colors, terminals with 16 colors may not understand indexed ones
+const ANSI_16_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// The palette of `COLOR_DEPTH`, built once since every frame is reduced to it
+static PALETTE: Lazy<TerminalPalette> = Lazy::new(|| TerminalPalette::new(*COLOR_DEPTH));
+
+/// Used to know which color an indexed one is when the terminal only has 16
+static XTERM_PALETTE: Lazy<TerminalPalette> =
+    Lazy::new(|| TerminalPalette::new(ColorDepth::Ansi256));
+
+impl TerminalPalette {
+    fn new(color_depth: ColorDepth) -> Self {
+        let mut colors = ANSI_16.to_vec();
+
+        if color_depth == ColorDepth::Ansi256 {
+            let levels = [0, 95, 135, 175, 215, 255];
+            for r in levels {
+                for g in levels {
+                    for b in levels {
+                        colors.push([r, g, b]);
+                    }
+                }
+            }
+            colors.extend((0..24).map(|gray| {
+                let level = 8 + gray * 10;
+                [level, level, level]
+            }));
+        }
+
+        Self { colors }
+    }
+}
+
+impl ColorMap for TerminalPalette {
+    type Color = Rgb<u8>;
+
+    fn index_of(&self, color: &Rgb<u8>) -> usize {
+        let distance = |palette_color: &[u8; 3]| -> u32 {
+            palette_color
+                .iter()
+                .zip(color.0)
+                .map(|(a, b)| (*a as i32 - b as i32).pow(2) as u32)
+                .sum()
+        };
+
+        self.colors
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, palette_color)| distance(palette_color))
+            .map(|(index, _)| index)
+            .unwrap_or_default()
+    }
+
+    fn lookup(&self, index: usize) -> Option<Rgb<u8>> {
+        self.colors.get(index).map(|color| Rgb(*color))
+    }
+
+    fn has_lookup(&self) -> bool {
+        true
+    }
+
+    fn map_color(&self, color: &mut Rgb<u8>) {
+        *color = Rgb(self.colors[self.index_of(color)]);
+    }
+}
+
+/// Reduce the colors of the image to the ones the terminal can display, the image is dithered so
+/// it still gives an impression of the colors which are lost
+pub fn reduce_colors(image: DynamicImage, color_depth: ColorDepth) -> DynamicImage {
+    if color_depth == ColorDepth::TrueColor {
+        return image;
+    }
+
+    let mut image = image.into_rgb8();
+    dither(&mut image, &TerminalPalette::new(color_depth));
+
+    DynamicImage::ImageRgb8(image)
+}
+
+/// The closest color of the palette, the ones without a value (like `Reset`) are kept
+fn terminal_color(color: Color, palette: &TerminalPalette) -> Color {
+    let has_16_colors = palette.colors.len() == ANSI_16.len();
+
+    let rgb = match color {
+        Color::Rgb(r, g, b) => Rgb([r, g, b]),
+        Color::Indexed(index) if has_16_colors && index as usize >= ANSI_16.len() => {
+            match XTERM_PALETTE.lookup(index as usize) {
+                Some(rgb) => rgb,
+                None => return color,
+            }
+        }
+        _ => return color,
+    };
+
+    let index = palette.index_of(&rgb);
+
+    if has_16_colors {
+        ANSI_16_COLORS[index]
+    } else {
+        Color::Indexed(index as u8)
+    }
+}
+
+/// Swap the colors of the frame for the closest ones the terminal has when it has no truecolor
+/// support, otherwise truecolor escapes are written anyway, for example by the block art
+pub fn reduce_buffer_colors(buf: &mut Buffer) {
+    if *COLOR_DEPTH == ColorDepth::TrueColor {
+        return;
+    }
+
+    for cell in buf.content.iter_mut() {
+        cell.fg = terminal_color(cell.fg, &PALETTE);
+        cell.bg = terminal_color(cell.bg, &PALETTE);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn color_depth_is_detected_from_the_environment() {
+        assert_eq!(
+            ColorDepth::TrueColor,
+            color_depth_from_env(Some("truecolor"), Some("xterm-256color"))
+        );
+        assert_eq!(
+            ColorDepth::TrueColor,
+            color_depth_from_env(None, Some("xterm-direct"))
+        );
+        assert_eq!(
+            ColorDepth::Ansi256,
+            color_depth_from_env(None, Some("screen-256color"))
+        );
+        assert_eq!(
+            ColorDepth::Ansi16,
+            color_depth_from_env(None, Some("linux"))
+        );
+        assert_eq!(ColorDepth::Ansi16, color_depth_from_env(None, None));
+    }
+
+    #[test]
+    fn colors_are_reduced_to_the_palette() {
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(4, 4, Rgb([250, 10, 5])));
+
+        let reduced = reduce_colors(image, ColorDepth::Ansi16).into_rgb8();
+
+        assert!(reduced.pixels().all(|pixel| ANSI_16.contains(&pixel.0)));
+    }
+
+    #[test]
+    fn colors_of_the_frame_are_swapped_for_the_ones_of_the_terminal() {
+        let ansi_16 = TerminalPalette::new(ColorDepth::Ansi16);
+        let ansi_256 = TerminalPalette::new(ColorDepth::Ansi256);
+
+        assert_eq!(
+            Color::LightRed,
+            terminal_color(Color::Rgb(250, 10, 5), &ansi_16)
+        );
+        assert_eq!(
+            Color::LightRed,
+            terminal_color(Color::Indexed(196), &ansi_16)
+        );
+        assert_eq!(
+            Color::Indexed(9),
+            terminal_color(Color::Rgb(250, 10, 5), &ansi_256)
+        );
+        assert_eq!(
+            Color::Indexed(208),
+            terminal_color(Color::Indexed(208), &ansi_256)
+        );
+        assert_eq!(Color::Reset, terminal_color(Color::Reset, &ansi_16));
+    }
+}
@@ -1,4 +1,5 @@
 use super::{AppDirectories, APP_DATA_DIR};
+use crate::global::is_persistence_disabled;
 use chrono::Utc;
 use manga_tui::build_check_exists_function;
 use once_cell::sync::Lazy;
@@ -8,14 +9,16 @@ use strum::Display;
 
 // Todo! document database schema
 
+/// Kept in memory when the data directory can't be written to, see `PERSISTENCE_DISABLED`
 pub static DBCONN: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| {
-    let conn = Connection::open(
-        APP_DATA_DIR
-            .as_ref()
-            .unwrap()
-            .join(AppDirectories::History.to_string())
-            .join("manga-tui-history.db"),
-    );
+    let conn = match APP_DATA_DIR.as_ref().filter(|_| !is_persistence_disabled()) {
+        Some(data_dir) => Connection::open(
+            data_dir
+                .join(AppDirectories::History.to_string())
+                .join("manga-tui-history.db"),
+        ),
+        None => Connection::open_in_memory(),
+    };
 
     if conn.is_err() {
         return Mutex::new(None);
@@ -92,6 +95,94 @@ pub static DBCONN: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| {
     )
     .unwrap();
 
+    conn.execute(
+        "CREATE TABLE if not exists downloaded_chapters (
+                chapter_id TEXT PRIMARY KEY,
+                manga_id TEXT NOT NULL,
+                manga_title TEXT NOT NULL,
+                number TEXT NOT NULL,
+                title TEXT NOT NULL,
+                lang TEXT NOT NULL,
+                path TEXT NOT NULL,
+                downloaded_at DATETIME DEFAULT (datetime('now'))
+             )",
+        (),
+    )
+    .unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists blocklist (
+                id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                created_at DATETIME DEFAULT (datetime('now')),
+                PRIMARY KEY (id, kind)
+             )",
+        (),
+    )
+    .unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists manga_tags (
+                manga_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                PRIMARY KEY (manga_id, name)
+             )",
+        (),
+    )
+    .unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists reading_activity (
+                chapter_id TEXT PRIMARY KEY,
+                manga_id TEXT NOT NULL,
+                read_at DATETIME DEFAULT (datetime('now'))
+             )",
+        (),
+    )
+    .unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists reading_directions (
+                manga_id TEXT PRIMARY KEY,
+                direction TEXT NOT NULL
+             )",
+        (),
+    )
+    .unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists completed_mangas (
+                manga_id TEXT PRIMARY KEY,
+                completed_at DATETIME DEFAULT (datetime('now'))
+             )",
+        (),
+    )
+    .unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists page_bookmarks (
+                chapter_id TEXT NOT NULL,
+                page INTEGER NOT NULL,
+                manga_id TEXT NOT NULL,
+                chapter_title TEXT NOT NULL,
+                chapter_number TEXT NOT NULL,
+                created_at DATETIME DEFAULT (datetime('now')),
+                PRIMARY KEY (chapter_id, page)
+             )",
+        (),
+    )
+    .unwrap();
+
+    conn.execute(
+        "CREATE TABLE if not exists pinned_mangas (
+                manga_id TEXT PRIMARY KEY,
+                pinned_at DATETIME DEFAULT (datetime('now'))
+             )",
+        (),
+    )
+    .unwrap();
+
     let already_has_data: i32 = conn
         .query_row("SELECT COUNT(*) from history_types", [], |row| row.get(0))
         .unwrap();
@@ -110,6 +201,25 @@ pub static DBCONN: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| {
         .unwrap();
     }
 
+    let has_reading_activity: i32 = conn
+        .query_row("SELECT COUNT(*) from reading_activity", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+
+    // The chapters read before the reading stats existed have no activity, so they are dated with
+    // the last time their manga was read which is the closest the history has
+    if has_reading_activity == 0 {
+        conn.execute(
+            "INSERT OR IGNORE INTO reading_activity(chapter_id, manga_id, read_at)
+                SELECT chapters.id, chapters.manga_id, mangas.last_read FROM chapters
+                INNER JOIN mangas ON mangas.id = chapters.manga_id
+                WHERE chapters.is_read = 1",
+            (),
+        )
+        .unwrap();
+    }
+
     Mutex::new(Some(conn))
 });
 
@@ -131,6 +241,15 @@ fn manga_is_reading(id: &str, conn: &Connection) -> rusqlite::Result<bool> {
     Ok(exists)
 }
 
+/// Whether the manga has chapters read, before its first chapter is saved it's not on the reading
+/// history
+pub fn is_manga_in_reading_history(id: &str) -> rusqlite::Result<bool> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    manga_is_reading(id, conn)
+}
+
 pub struct MangaInsert<'a> {
     pub id: &'a str,
     pub title: &'a str,
@@ -203,6 +322,29 @@ pub struct MangaReadingHistorySave<'a> {
     pub img_url: Option<&'a str>,
     pub chapter_id: &'a str,
     pub chapter_title: &'a str,
+    /// Names of the manga's tags, kept for the reading stats
+    pub tags: &'a [String],
+}
+
+/// Keep when the chapter was read and the tags of its manga, which is what the reading stats are
+/// made of
+fn save_reading_activity(
+    manga_read: &MangaReadingHistorySave<'_>,
+    conn: &Connection,
+) -> rusqlite::Result<()> {
+    for tag in manga_read.tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO manga_tags(manga_id, name) VALUES (?1, ?2)",
+            params![manga_read.id, tag],
+        )?;
+    }
+
+    conn.execute(
+        "INSERT OR REPLACE INTO reading_activity(chapter_id, manga_id) VALUES (?1, ?2)",
+        params![manga_read.chapter_id, manga_read.id],
+    )?;
+
+    Ok(())
 }
 
 // if it's the first time the user is reading a manga then save it to mangas table and save the
@@ -217,6 +359,8 @@ pub fn save_history(manga_read: MangaReadingHistorySave<'_>) -> rusqlite::Result
         |row| row.get(0),
     )?;
 
+    save_reading_activity(&manga_read, conn)?;
+
     // Check if manga already exists in table mangas
     if check_manga_already_exists(manga_read.id, conn)? {
         insert_chapter(
@@ -307,6 +451,8 @@ pub fn get_chapters_history_status(id: &str) -> rusqlite::Result<Vec<MangaReadin
 pub struct MangaHistory {
     pub id: String,
     pub title: String,
+    /// Pinned mangas are listed before the rest on every tab of the feed
+    pub is_pinned: bool,
     // img_url: Option<String>,
 }
 
@@ -341,18 +487,20 @@ pub fn get_history(
     )?;
 
     let mut get_statement = conn.prepare(
-        "SELECT  mangas.id, mangas.title from mangas 
+        "SELECT  mangas.id, mangas.title, pinned_mangas.manga_id IS NOT NULL from mangas 
                      INNER JOIN manga_history_union ON mangas.id = manga_history_union.manga_id 
+                     LEFT JOIN pinned_mangas ON pinned_mangas.manga_id = mangas.id
                      WHERE manga_history_union.type_id = ?1
-                     ORDER BY mangas.last_read DESC
+                     ORDER BY pinned_mangas.manga_id IS NULL, mangas.last_read DESC
                      LIMIT 5 OFFSET ?2",
     )?;
 
     let mut get_statement_with_search_term = conn.prepare(
-        "SELECT  mangas.id, mangas.title from mangas 
+        "SELECT  mangas.id, mangas.title, pinned_mangas.manga_id IS NOT NULL from mangas 
                      INNER JOIN manga_history_union ON mangas.id = manga_history_union.manga_id 
+                     LEFT JOIN pinned_mangas ON pinned_mangas.manga_id = mangas.id
                      WHERE manga_history_union.type_id = ?1 AND LOWER(mangas.title) LIKE '%' || ?2 || '%'
-                     ORDER BY mangas.last_read DESC
+                     ORDER BY pinned_mangas.manga_id IS NULL, mangas.last_read DESC
                      LIMIT 5 OFFSET ?3",
     )?;
 
@@ -363,6 +511,7 @@ pub fn get_history(
             Ok(MangaHistory {
                 id: row.get(0)?,
                 title: row.get(1)?,
+                is_pinned: row.get(2)?,
                 // img_url: row.get(2)?,
             })
         })?;
@@ -391,6 +540,7 @@ pub fn get_history(
                 Ok(MangaHistory {
                     id: row.get(0)?,
                     title: row.get(1)?,
+                    is_pinned: row.get(2)?,
                     // img_url: row.get(2)?,
                 })
             },
@@ -531,3 +681,873 @@ pub fn set_chapter_downloaded(chapter: SetChapterDownloaded<'_>) -> rusqlite::Re
         Ok(())
     }
 }
+
+pub struct ChapterToMark {
+    pub id: String,
+    pub title: String,
+}
+
+/// Shared by `set_manga_chapters_read` and `set_chapters_read`. Marking chapters as read adds the
+/// manga to the reading history, the chapters which are not saved yet are only added when they are
+/// marked as read
+fn mark_chapters(
+    manga: &MangaInsert<'_>,
+    chapters: &[ChapterToMark],
+    is_read: bool,
+    conn: &Connection,
+) -> rusqlite::Result<()> {
+    if is_read {
+        if !check_manga_already_exists(manga.id, conn)? {
+            insert_manga(
+                MangaInsert {
+                    id: manga.id,
+                    title: manga.title,
+                    img_url: manga.img_url,
+                },
+                conn,
+            )?;
+        }
+
+        update_or_insert_manga_most_recent_read(manga.id, conn)?;
+    }
+
+    for chapter in chapters {
+        if check_chapter_exists(&chapter.id, conn)? {
+            conn.execute(
+                "UPDATE chapters SET is_read = ?1 WHERE id = ?2",
+                params![is_read, chapter.id],
+            )?;
+        } else if is_read {
+            insert_chapter(
+                ChapterInsert {
+                    id: &chapter.id,
+                    title: &chapter.title,
+                    manga_id: manga.id,
+                    is_read: true,
+                    is_downloaded: false,
+                },
+                conn,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Mark every chapter of a manga as read at once, the manga is added to the reading history and
+/// taken out of plan to read since there is nothing left of it to read
+pub fn set_manga_chapters_read(
+    manga: MangaInsert<'_>,
+    chapters: &[ChapterToMark],
+) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let transaction = conn.unchecked_transaction()?;
+
+    mark_chapters(&manga, chapters, true, &transaction)?;
+
+    let plan_to_read: i32 = transaction.query_row(
+        "SELECT id FROM history_types where name = ?1",
+        params![MangaHistoryType::PlanToRead.to_string()],
+        |row| row.get(0),
+    )?;
+
+    transaction.execute(
+        "DELETE FROM manga_history_union WHERE manga_id = ?1 AND type_id = ?2",
+        params![manga.id, plan_to_read],
+    )?;
+
+    transaction.execute(
+        "INSERT OR REPLACE INTO completed_mangas(manga_id) VALUES (?1)",
+        params![manga.id],
+    )?;
+
+    transaction.commit()
+}
+
+/// Keep the tags of a manga which has chapters read, used when its manga page is opened so the
+/// mangas read before the reading stats existed get their tags too
+pub fn save_manga_tags(manga_id: &str, tags: &[String]) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    for tag in tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO manga_tags(manga_id, name)
+                SELECT ?1, ?2 WHERE EXISTS
+                    (SELECT 1 FROM chapters WHERE manga_id = ?1 AND is_read = 1)",
+            params![manga_id, tag],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Count the manga as completed for the reading stats, without marking its chapters as read
+pub fn set_manga_completed(manga_id: &str) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "INSERT OR IGNORE INTO completed_mangas(manga_id) VALUES (?1)",
+        params![manga_id],
+    )?;
+
+    Ok(())
+}
+
+/// Undo `set_manga_chapters_read`, the chapters stay in the database so their download status is
+/// kept
+pub fn set_manga_chapters_unread(manga_id: &str) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "UPDATE chapters SET is_read = ?1 WHERE manga_id = ?2",
+        params![false, manga_id],
+    )?;
+
+    conn.execute(
+        "DELETE FROM completed_mangas WHERE manga_id = ?1",
+        params![manga_id],
+    )?;
+
+    Ok(())
+}
+
+/// Mark only some chapters of a manga, the ones selected on the chapter list, marking them as
+/// read adds the manga to the reading history
+pub fn set_chapters_read(
+    manga: MangaInsert<'_>,
+    chapters: &[ChapterToMark],
+    is_read: bool,
+) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let transaction = conn.unchecked_transaction()?;
+
+    mark_chapters(&manga, chapters, is_read, &transaction)?;
+
+    if !is_read {
+        transaction.execute(
+            "DELETE FROM completed_mangas WHERE manga_id = ?1",
+            params![manga.id],
+        )?;
+    }
+
+    transaction.commit()
+}
+
+pub struct ReadingListEntry {
+    pub id: String,
+    pub title: String,
+    pub is_completed: bool,
+    /// Title of the chapter read the most recently, it's empty for chapters without a title
+    pub last_chapter: Option<String>,
+}
+
+/// Every manga of the list, the most recently read first, used by `manga-tui export`
+pub fn get_reading_list(hist_type: MangaHistoryType) -> rusqlite::Result<Vec<ReadingListEntry>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare(
+        "SELECT mangas.id, mangas.title, completed_mangas.manga_id IS NOT NULL,
+                (SELECT chapters.title FROM reading_activity
+                    INNER JOIN chapters ON chapters.id = reading_activity.chapter_id
+                    WHERE reading_activity.manga_id = mangas.id
+                    ORDER BY reading_activity.read_at DESC LIMIT 1)
+                FROM mangas
+                INNER JOIN manga_history_union ON mangas.id = manga_history_union.manga_id
+                INNER JOIN history_types ON history_types.id = manga_history_union.type_id
+                LEFT JOIN completed_mangas ON completed_mangas.manga_id = mangas.id
+                WHERE history_types.name = ?1
+                ORDER BY mangas.last_read DESC",
+    )?;
+
+    let entries = statement.query_map(params![hist_type.to_string()], |row| {
+        Ok(ReadingListEntry {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            is_completed: row.get(2)?,
+            last_chapter: row.get(3)?,
+        })
+    })?;
+
+    entries.collect()
+}
+
+/// How much of the reading history is kept, the oldest mangas are taken out of it each time a
+/// chapter is read
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HistoryRetention {
+    /// Most mangas kept on the reading history
+    pub max_mangas: Option<u32>,
+    /// Mangas not read for longer than this are taken out of the reading history
+    pub max_age_days: Option<u32>,
+    /// Completed mangas are never taken out and are not counted for `max_mangas`
+    pub keep_completed: bool,
+}
+
+struct HistoryEntry {
+    id: String,
+    days_since_read: f64,
+    is_completed: bool,
+}
+
+/// `entries` must be sorted by the most recently read first, the manga being read is always kept
+fn mangas_to_prune<'a>(
+    entries: &'a [HistoryEntry],
+    current_manga_id: &str,
+    retention: HistoryRetention,
+) -> Vec<&'a str> {
+    let mut kept: u32 = 0;
+    let mut pruned = vec![];
+
+    for entry in entries {
+        if entry.id == current_manga_id {
+            kept += 1;
+            continue;
+        }
+
+        if entry.is_completed && retention.keep_completed {
+            continue;
+        }
+
+        let is_too_old = retention
+            .max_age_days
+            .is_some_and(|max_age| entry.days_since_read > max_age as f64);
+        let is_over_limit = retention.max_mangas.is_some_and(|max| kept >= max);
+
+        if is_too_old || is_over_limit {
+            pruned.push(entry.id.as_str());
+        } else {
+            kept += 1;
+        }
+    }
+
+    pruned
+}
+
+/// The manga stays in plan to read and its downloads are kept, only what was read is forgotten
+fn remove_from_reading_history(manga_id: &str, conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "DELETE FROM manga_history_union WHERE manga_id = ?1 AND type_id = (SELECT id FROM history_types WHERE name = ?2)",
+        params![manga_id, MangaHistoryType::ReadingHistory.to_string()],
+    )?;
+
+    conn.execute(
+        "UPDATE chapters SET is_read = ?1 WHERE manga_id = ?2",
+        params![false, manga_id],
+    )?;
+
+    conn.execute(
+        "DELETE FROM reading_activity WHERE manga_id = ?1",
+        params![manga_id],
+    )?;
+
+    conn.execute(
+        "DELETE FROM completed_mangas WHERE manga_id = ?1",
+        params![manga_id],
+    )?;
+
+    Ok(())
+}
+
+fn get_history_entries(conn: &Connection) -> rusqlite::Result<Vec<HistoryEntry>> {
+    let mut statement = conn.prepare(
+        "SELECT mangas.id, julianday('now') - julianday(mangas.last_read), completed_mangas.manga_id IS NOT NULL FROM mangas
+                INNER JOIN manga_history_union ON mangas.id = manga_history_union.manga_id
+                INNER JOIN history_types ON history_types.id = manga_history_union.type_id
+                LEFT JOIN completed_mangas ON completed_mangas.manga_id = mangas.id
+                WHERE history_types.name = ?1
+                ORDER BY mangas.last_read DESC",
+    )?;
+
+    let entries = statement.query_map(
+        params![MangaHistoryType::ReadingHistory.to_string()],
+        |row| {
+            Ok(HistoryEntry {
+                id: row.get(0)?,
+                days_since_read: row.get::<_, Option<f64>>(1)?.unwrap_or_default(),
+                is_completed: row.get(2)?,
+            })
+        },
+    )?;
+
+    entries.collect()
+}
+
+/// Called after a chapter is read, `current_manga_id` is the manga of that chapter
+pub fn prune_history(current_manga_id: &str, retention: HistoryRetention) -> rusqlite::Result<()> {
+    if retention.max_mangas.is_none() && retention.max_age_days.is_none() {
+        return Ok(());
+    }
+
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let entries = get_history_entries(conn)?;
+
+    let transaction = conn.unchecked_transaction()?;
+
+    for manga_id in mangas_to_prune(&entries, current_manga_id, retention) {
+        remove_from_reading_history(manga_id, &transaction)?;
+    }
+
+    transaction.commit()
+}
+
+/// Forget the whole reading history, including the stats built from it
+pub fn clear_reading_history() -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let entries = get_history_entries(conn)?;
+
+    let transaction = conn.unchecked_transaction()?;
+
+    for entry in entries {
+        remove_from_reading_history(&entry.id, &transaction)?;
+    }
+
+    transaction.commit()
+}
+
+#[derive(Debug, Default)]
+pub struct ReadingStats {
+    pub chapters_read: u32,
+    pub mangas_started: u32,
+    /// Mangas with every chapter marked as read, or which finished publishing and had their latest
+    /// chapter read
+    pub mangas_completed: u32,
+    /// Tags of the mangas with the most chapters read, along with how many chapters
+    pub top_tags: Vec<(String, u32)>,
+    /// Chapters read on each of the last days, the day is formatted as `YYYY-MM-DD`
+    pub activity: Vec<(String, u32)>,
+}
+
+impl ReadingStats {
+    pub fn is_empty(&self) -> bool {
+        self.chapters_read == 0 && self.mangas_started == 0
+    }
+}
+
+/// Everything is counted by sqlite so the history isn't loaded in memory
+pub fn get_reading_stats(top_tags: u32, activity_days: i64) -> rusqlite::Result<ReadingStats> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let chapters_read: u32 = conn.query_row(
+        "SELECT COUNT(*) FROM chapters WHERE is_read = 1",
+        [],
+        |row| row.get(0),
+    )?;
+
+    let mangas_started: u32 = conn.query_row(
+        "SELECT COUNT(*) FROM manga_history_union
+                INNER JOIN history_types ON history_types.id = manga_history_union.type_id
+                WHERE history_types.name = ?1",
+        params![MangaHistoryType::ReadingHistory.to_string()],
+        |row| row.get(0),
+    )?;
+
+    let mangas_completed: u32 =
+        conn.query_row("SELECT COUNT(*) FROM completed_mangas", [], |row| {
+            row.get(0)
+        })?;
+
+    let mut get_top_tags = conn.prepare(
+        "SELECT manga_tags.name, COUNT(*) AS total FROM manga_tags
+                INNER JOIN chapters ON chapters.manga_id = manga_tags.manga_id
+                WHERE chapters.is_read = 1
+                GROUP BY manga_tags.name
+                ORDER BY total DESC
+                LIMIT ?1",
+    )?;
+
+    let top_tags = get_top_tags
+        .query_map(params![top_tags], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<Vec<(String, u32)>>>()?;
+
+    let mut get_activity = conn.prepare(
+        "SELECT date(read_at) AS day, COUNT(*) FROM reading_activity
+                WHERE read_at >= datetime('now', 'start of day', ?1)
+                GROUP BY day",
+    )?;
+
+    let read_by_day = get_activity
+        .query_map(params![format!("-{} days", activity_days - 1)], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<(String, u32)>>>()?;
+
+    // Days without chapters read are not returned by the query
+    let today = Utc::now().date_naive();
+    let activity = (0..activity_days)
+        .rev()
+        .map(|days_ago| {
+            let day = (today - chrono::Duration::days(days_ago))
+                .format("%Y-%m-%d")
+                .to_string();
+            let chapters = read_by_day
+                .iter()
+                .find(|(read_day, _)| *read_day == day)
+                .map_or(0, |(_, chapters)| *chapters);
+            (day, chapters)
+        })
+        .collect();
+
+    Ok(ReadingStats {
+        chapters_read,
+        mangas_started,
+        mangas_completed,
+        top_tags,
+        activity,
+    })
+}
+
+pub struct LibraryChapterInsert<'a> {
+    pub chapter_id: &'a str,
+    pub manga_id: &'a str,
+    pub manga_title: &'a str,
+    pub number: &'a str,
+    pub title: &'a str,
+    pub lang: &'a str,
+    pub path: &'a str,
+}
+
+/// Add a downloaded chapter to the local library index, if it was already indexed its data is
+/// updated, which happens when a chapter is downloaded again or its folder was moved
+pub fn index_downloaded_chapter(chapter: LibraryChapterInsert<'_>) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "INSERT INTO downloaded_chapters(chapter_id, manga_id, manga_title, number, title, lang, path)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                ON CONFLICT(chapter_id) DO UPDATE SET
+                manga_title = excluded.manga_title,
+                number = excluded.number,
+                title = excluded.title,
+                lang = excluded.lang,
+                path = excluded.path",
+        params![
+            chapter.chapter_id,
+            chapter.manga_id,
+            chapter.manga_title,
+            chapter.number,
+            chapter.title,
+            chapter.lang,
+            chapter.path
+        ],
+    )?;
+
+    Ok(())
+}
+
+pub struct LibraryChapter {
+    pub chapter_id: String,
+    pub number: String,
+    pub title: String,
+    pub lang: String,
+    pub path: String,
+    pub downloaded_at: String,
+}
+
+/// Returns every chapter stored in the library index, used to reconcile it against the files on
+/// disk
+pub fn get_indexed_chapters() -> rusqlite::Result<Vec<LibraryChapter>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare(
+        "SELECT chapter_id, number, title, lang, path, downloaded_at FROM downloaded_chapters",
+    )?;
+
+    let chapters = statement.query_map([], |row| {
+        Ok(LibraryChapter {
+            chapter_id: row.get(0)?,
+            number: row.get(1)?,
+            title: row.get(2)?,
+            lang: row.get(3)?,
+            path: row.get(4)?,
+            downloaded_at: row.get(5)?,
+        })
+    })?;
+
+    Ok(chapters.flatten().collect())
+}
+
+/// The title saved in the reading history when the chapter was downloaded, it's preferred over
+/// the one recovered from the chapter's directory name
+pub fn get_chapter_title(chapter_id: &str) -> rusqlite::Result<Option<String>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let title = conn.query_row(
+        "SELECT title FROM chapters WHERE id = ?1",
+        [chapter_id],
+        |row| row.get(0),
+    );
+
+    match title {
+        Ok(title) => Ok(Some(title)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Remove a chapter from the library index, which happens when its files no longer exist on disk,
+/// the chapter is also marked as not downloaded in the reading history
+pub fn remove_from_library_index(chapter_id: &str) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "DELETE FROM downloaded_chapters WHERE chapter_id = ?1",
+        params![chapter_id],
+    )?;
+
+    conn.execute(
+        "UPDATE chapters SET is_downloaded = ?1 WHERE id = ?2",
+        params![false, chapter_id],
+    )?;
+
+    Ok(())
+}
+
+/// This is used in the `feed` page to list the mangas which have chapters downloaded, the data
+/// comes from the library index so the download directory is not scanned each time
+pub fn get_library(page: u32, search: &str) -> rusqlite::Result<MangaHistoryResponse> {
+    let offset = (page - 1) * 5;
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let search = search.trim().to_lowercase();
+
+    let total_mangas: u32 = conn.query_row(
+        "SELECT COUNT(DISTINCT manga_id) FROM downloaded_chapters
+                WHERE LOWER(manga_title) LIKE '%' || ?1 || '%'",
+        params![search],
+        |row| row.get(0),
+    )?;
+
+    let mut statement = conn.prepare(
+        "SELECT downloaded_chapters.manga_id, manga_title, pinned_mangas.manga_id IS NOT NULL
+                FROM downloaded_chapters
+                LEFT JOIN pinned_mangas ON pinned_mangas.manga_id = downloaded_chapters.manga_id
+                WHERE LOWER(manga_title) LIKE '%' || ?1 || '%'
+                GROUP BY downloaded_chapters.manga_id
+                ORDER BY pinned_mangas.manga_id IS NULL, MAX(downloaded_at) DESC
+                LIMIT 5 OFFSET ?2",
+    )?;
+
+    let iter_mangas = statement.query_map(params![search, offset], |row| {
+        Ok(MangaHistory {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            is_pinned: row.get(2)?,
+        })
+    })?;
+
+    let mut mangas: Vec<MangaHistory> = vec![];
+
+    for manga in iter_mangas {
+        mangas.push(manga?);
+    }
+
+    Ok(MangaHistoryResponse {
+        mangas,
+        page,
+        total_items: total_mangas,
+    })
+}
+
+/// Retrieve the chapters of a manga which are stored in the library index, most recent downloads
+/// first
+pub fn get_library_chapters(manga_id: &str) -> rusqlite::Result<Vec<LibraryChapter>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare(
+        "SELECT chapter_id, number, title, lang, path, downloaded_at FROM downloaded_chapters
+                WHERE manga_id = ?1
+                ORDER BY downloaded_at DESC",
+    )?;
+
+    let chapters = statement.query_map(params![manga_id], |row| {
+        Ok(LibraryChapter {
+            chapter_id: row.get(0)?,
+            number: row.get(1)?,
+            title: row.get(2)?,
+            lang: row.get(3)?,
+            path: row.get(4)?,
+            downloaded_at: row.get(5)?,
+        })
+    })?;
+
+    Ok(chapters.flatten().collect())
+}
+
+/// What a blocklist entry hides, either a single manga or every manga with a tag
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockedKind {
+    #[strum(to_string = "manga")]
+    Manga,
+    #[strum(to_string = "tag")]
+    Tag,
+}
+
+/// Returns the reading direction used the last time a chapter of the manga was read
+pub fn get_reading_direction(manga_id: &str) -> rusqlite::Result<Option<String>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let direction = conn.query_row(
+        "SELECT direction FROM reading_directions WHERE manga_id = ?1",
+        [manga_id],
+        |row| row.get(0),
+    );
+
+    match direction {
+        Ok(direction) => Ok(Some(direction)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn set_reading_direction(manga_id: &str, direction: &str) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "INSERT INTO reading_directions(manga_id, direction) VALUES (?1, ?2)
+                ON CONFLICT(manga_id) DO UPDATE SET direction = excluded.direction",
+        params![manga_id, direction],
+    )?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockedItem {
+    pub id: String,
+    pub kind: BlockedKind,
+    pub name: String,
+}
+
+pub fn add_to_blocklist(item: &BlockedItem) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "INSERT INTO blocklist(id, kind, name) VALUES (?1, ?2, ?3)
+                ON CONFLICT(id, kind) DO UPDATE SET name = excluded.name",
+        params![item.id, item.kind.to_string(), item.name],
+    )?;
+
+    Ok(())
+}
+
+pub fn remove_from_blocklist(id: &str, kind: BlockedKind) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "DELETE FROM blocklist WHERE id = ?1 AND kind = ?2",
+        params![id, kind.to_string()],
+    )?;
+
+    Ok(())
+}
+
+/// Returns the blocklist, the most recently added entries first
+pub fn get_blocklist() -> rusqlite::Result<Vec<BlockedItem>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement =
+        conn.prepare("SELECT id, kind, name FROM blocklist ORDER BY created_at DESC")?;
+
+    let items = statement.query_map([], |row| {
+        let kind: String = row.get(1)?;
+        Ok(BlockedItem {
+            id: row.get(0)?,
+            kind: if kind == BlockedKind::Tag.to_string() {
+                BlockedKind::Tag
+            } else {
+                BlockedKind::Manga
+            },
+            name: row.get(2)?,
+        })
+    })?;
+
+    Ok(items.flatten().collect())
+}
+
+pub struct PageBookmarkSave<'a> {
+    pub manga_id: &'a str,
+    pub chapter_id: &'a str,
+    pub chapter_title: &'a str,
+    pub chapter_number: &'a str,
+    pub page: usize,
+}
+
+/// Take the manga out of the plan to read list, its reading history and downloads are kept
+pub fn remove_from_plan_to_read(manga_id: &str) -> rusqlite::Result<()> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    conn.execute(
+        "DELETE FROM manga_history_union WHERE manga_id = ?1 AND type_id = (SELECT id FROM history_types WHERE name = ?2)",
+        params![manga_id, MangaHistoryType::PlanToRead.to_string()],
+    )?;
+
+    Ok(())
+}
+
+/// Pins the manga or unpins it if it was already pinned, returns whether the manga is pinned now
+pub fn toggle_pinned_manga(manga_id: &str) -> rusqlite::Result<bool> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let removed = conn.execute(
+        "DELETE FROM pinned_mangas WHERE manga_id = ?1",
+        params![manga_id],
+    )?;
+
+    if removed > 0 {
+        return Ok(false);
+    }
+
+    conn.execute(
+        "INSERT INTO pinned_mangas(manga_id) VALUES (?1)",
+        params![manga_id],
+    )?;
+
+    Ok(true)
+}
+
+/// Adds the bookmark or removes it if the page was already bookmarked, returns whether the page is
+/// bookmarked now
+pub fn toggle_page_bookmark(bookmark: PageBookmarkSave<'_>) -> rusqlite::Result<bool> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let removed = conn.execute(
+        "DELETE FROM page_bookmarks WHERE chapter_id = ?1 AND page = ?2",
+        params![bookmark.chapter_id, bookmark.page],
+    )?;
+
+    if removed > 0 {
+        return Ok(false);
+    }
+
+    conn.execute(
+        "INSERT INTO page_bookmarks(chapter_id, page, manga_id, chapter_title, chapter_number)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            bookmark.chapter_id,
+            bookmark.page,
+            bookmark.manga_id,
+            bookmark.chapter_title,
+            bookmark.chapter_number
+        ],
+    )?;
+
+    Ok(true)
+}
+
+/// The pages bookmarked in the chapter
+pub fn get_chapter_bookmarks(chapter_id: &str) -> rusqlite::Result<Vec<usize>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement =
+        conn.prepare("SELECT page FROM page_bookmarks WHERE chapter_id = ?1 ORDER BY page")?;
+
+    let pages = statement.query_map([chapter_id], |row| row.get(0))?;
+
+    Ok(pages.flatten().collect())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageBookmark {
+    pub chapter_id: String,
+    pub chapter_title: String,
+    pub chapter_number: String,
+    pub page: usize,
+}
+
+/// The bookmarks of every chapter of the manga, in the order of the chapters and their pages
+pub fn get_manga_bookmarks(manga_id: &str) -> rusqlite::Result<Vec<PageBookmark>> {
+    let binding = DBCONN.lock().unwrap();
+    let conn = binding.as_ref().unwrap();
+
+    let mut statement = conn.prepare(
+        "SELECT chapter_id, chapter_title, chapter_number, page FROM page_bookmarks
+                WHERE manga_id = ?1
+                ORDER BY CAST(chapter_number AS REAL), chapter_id, page",
+    )?;
+
+    let bookmarks = statement.query_map([manga_id], |row| {
+        Ok(PageBookmark {
+            chapter_id: row.get(0)?,
+            chapter_title: row.get(1)?,
+            chapter_number: row.get(2)?,
+            page: row.get(3)?,
+        })
+    })?;
+
+    Ok(bookmarks.flatten().collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(id: &str, days_since_read: f64, is_completed: bool) -> HistoryEntry {
+        HistoryEntry {
+            id: id.to_string(),
+            days_since_read,
+            is_completed,
+        }
+    }
+
+    #[test]
+    fn the_oldest_mangas_are_pruned_except_the_current_and_completed_ones() {
+        let entries = vec![
+            entry("recent", 1.0, false),
+            entry("completed", 5.0, true),
+            entry("old", 40.0, false),
+            entry("current", 90.0, false),
+        ];
+
+        let by_count = HistoryRetention {
+            max_mangas: Some(1),
+            max_age_days: None,
+            keep_completed: true,
+        };
+        assert_eq!(vec!["old"], mangas_to_prune(&entries, "current", by_count));
+
+        let by_age = HistoryRetention {
+            max_mangas: None,
+            max_age_days: Some(30),
+            keep_completed: false,
+        };
+        assert_eq!(vec!["old"], mangas_to_prune(&entries, "current", by_age));
+
+        let without_keeping_completed = HistoryRetention {
+            max_mangas: Some(2),
+            max_age_days: None,
+            keep_completed: false,
+        };
+        assert_eq!(
+            vec!["old", "current"],
+            mangas_to_prune(&entries, "recent", without_keeping_completed)
+        );
+    }
+}
@@ -3,9 +3,13 @@ use std::fs::{create_dir, File};
 use std::io::Write;
 use std::path::Path;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
 
-use crate::view::pages::manga::MangaPageEvents;
+use crate::global::is_persistence_disabled;
+use crate::view::pages::reader::PageType;
 
+use super::cache::get_cached_page;
+use super::database::{index_downloaded_chapter, LibraryChapterInsert};
 use super::error_log::{write_to_error_log, ErrorType};
 use super::fetch::MangadexClient;
 use super::{ChapterPagesResponse, APP_DATA_DIR};
@@ -20,15 +24,36 @@ pub struct DownloadChapter<'a> {
     pub lang: &'a str,
 }
 
-pub fn download_chapter(
+/// Implemented by the events of the pages which can download chapters, so they get notified
+/// about the download's progress
+pub trait DownloadHandler: Send + 'static {
+    fn progress(progress: f64, chapter_id: String) -> Self;
+    fn finished(chapter_id: String) -> Self;
+    fn failed(chapter_id: String) -> Self;
+}
+
+/// The page numbers are padded with zeros to the digits of the last page, so the files are sorted
+/// correctly by other programs (`02.png` before `10.png`)
+fn page_file_name(number: usize, total_pages: usize, extension: &str) -> String {
+    let digits = total_pages.to_string().len();
+    format!("{number:0digits$}.{extension}")
+}
+
+/// The pages are downloaded in the background, the handle says once they are all done whether the
+/// chapter was added to the library (at least one of its pages was written)
+pub fn download_chapter<DH: DownloadHandler>(
     chapter: DownloadChapter<'_>,
     chapter_data: ChapterPagesResponse,
-    tx: UnboundedSender<MangaPageEvents>,
-) -> Result<(), std::io::Error> {
+    tx: UnboundedSender<DH>,
+) -> Result<JoinHandle<bool>, std::io::Error> {
     // need directory with the manga's title, and its id to make it unique
     let chapter_id = chapter.id_chapter.to_string();
 
-    let dir_manga_downloads = APP_DATA_DIR.as_ref().unwrap().join("mangaDownloads");
+    let dir_manga_downloads = APP_DATA_DIR
+        .as_ref()
+        .filter(|_| !is_persistence_disabled())
+        .ok_or_else(|| std::io::Error::other("the data directory can't be written to"))?
+        .join("mangaDownloads");
 
     let dir_manga = dir_manga_downloads.join(format!(
         "{} {}",
@@ -63,29 +88,46 @@ pub fn download_chapter(
 
     let total_chapters = chapter_data.chapter.data.len();
 
-    tokio::spawn(async move {
+    let manga_id = chapter.manga_id.to_string();
+    let manga_title = chapter.manga_title.trim().to_string();
+    let number = chapter.number.to_string();
+    let title = chapter.title.trim().to_string();
+    let lang = chapter.lang.to_string();
+
+    let high_quality = PageType::HighQuality.to_string();
+
+    Ok(tokio::spawn(async move {
+        let mut pages_written = 0;
         for (index, file_name) in chapter_data.chapter.data.iter().enumerate() {
             let endpoint = format!(
                 "{}/data/{}",
                 chapter_data.base_url, chapter_data.chapter.hash
             );
 
-            let image_response = MangadexClient::global()
-                .get_chapter_page(&endpoint, file_name)
-                .await;
+            // Pages already read are in the reader's cache, so they are not downloaded again
+            let image_response =
+                match get_cached_page(&chapter_data.chapter.hash, &high_quality, index) {
+                    Some(bytes) => Ok(bytes),
+                    None => {
+                        MangadexClient::global()
+                            .get_chapter_page(&endpoint, file_name)
+                            .await
+                    }
+                };
 
             let file_name = Path::new(&file_name);
 
             match image_response {
                 Ok(bytes) => {
-                    let mut image_created = File::create(chapter_dir.join(format!(
-                        "{}.{}",
+                    let mut image_created = File::create(chapter_dir.join(page_file_name(
                         index + 1,
-                        file_name.extension().unwrap().to_str().unwrap()
+                        total_chapters,
+                        file_name.extension().unwrap().to_str().unwrap(),
                     )))
                     .unwrap();
                     image_created.write_all(&bytes).unwrap();
-                    tx.send(MangaPageEvents::SetDownloadProgress(
+                    pages_written += 1;
+                    tx.send(DH::progress(
                         (index as f64) / (total_chapters as f64),
                         chapter_id.clone(),
                     ))
@@ -94,9 +136,41 @@ pub fn download_chapter(
                 Err(e) => write_to_error_log(ErrorType::FromError(Box::new(e))),
             }
         }
-        tx.send(MangaPageEvents::ChapterFinishedDownloading(chapter_id))
-            .ok();
-    });
 
-    Ok(())
+        // A chapter without a single page on disk can't be read, so it's not added to the library
+        if pages_written == 0 {
+            tx.send(DH::failed(chapter_id)).ok();
+            return false;
+        }
+
+        let index_operation = index_downloaded_chapter(LibraryChapterInsert {
+            chapter_id: &chapter_id,
+            manga_id: &manga_id,
+            manga_title: &manga_title,
+            number: &number,
+            title: &title,
+            lang: &lang,
+            path: &chapter_dir.to_string_lossy(),
+        });
+
+        if let Err(e) = index_operation {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
+
+        tx.send(DH::finished(chapter_id)).ok();
+        true
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn page_file_names_are_padded_to_the_digits_of_the_last_page() {
+        assert_eq!("2.png", page_file_name(2, 9, "png"));
+        assert_eq!("02.png", page_file_name(2, 10, "png"));
+        assert_eq!("100.jpg", page_file_name(100, 120, "jpg"));
+        assert_eq!("007.jpg", page_file_name(7, 120, "jpg"));
+    }
 }
@@ -1,7 +1,8 @@
+use std::cell::Cell;
 use std::error::Error;
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::panic::PanicInfo;
+use std::panic::{catch_unwind, PanicHookInfo, UnwindSafe};
 use std::path::Path;
 
 use chrono::offset;
@@ -10,18 +11,26 @@ use manga_tui::exists;
 
 use super::tui::restore;
 use super::{AppDirectories, APP_DATA_DIR};
+use crate::global::is_persistence_disabled;
 
 pub static ERROR_LOGS_FILE: &str = "manga-tui-error-logs.txt";
+pub static REQUEST_LOGS_FILE: &str = "manga-tui-request-logs.txt";
 
 pub enum ErrorType<'a> {
-    FromPanic(&'a PanicInfo<'a>),
+    FromPanic(&'a PanicHookInfo<'a>),
     FromError(Box<dyn Error>),
 }
 
 pub fn write_to_error_log(e: ErrorType<'_>) {
-    let error_file_name = APP_DATA_DIR
-        .as_ref()
-        .unwrap()
+    if is_persistence_disabled() {
+        return;
+    }
+
+    let Some(data_dir) = APP_DATA_DIR.as_ref() else {
+        return;
+    };
+
+    let error_file_name = data_dir
         .join(AppDirectories::ErrorLogs.to_string())
         .join(ERROR_LOGS_FILE);
 
@@ -37,22 +46,59 @@ pub fn write_to_error_log(e: ErrorType<'_>) {
         ErrorType::FromError(boxed_err) => format!("{} | {} \n \n", now, boxed_err),
     };
 
-    let error_format_bytes = error_format.as_bytes();
+    append_to_log(&error_file_name, &error_format);
+}
+
+/// Used when `log_requests` is enabled, the requests are kept apart from the errors since there
+/// are a lot of them
+pub fn write_to_request_log(request: &str) {
+    if is_persistence_disabled() {
+        return;
+    }
+
+    let Some(data_dir) = APP_DATA_DIR.as_ref() else {
+        return;
+    };
+
+    let request_file_name = data_dir
+        .join(AppDirectories::ErrorLogs.to_string())
+        .join(REQUEST_LOGS_FILE);
+
+    append_to_log(
+        &request_file_name,
+        &format!("{} | {} \n", offset::Local::now(), request),
+    );
+}
+
+fn append_to_log(file_name: &Path, text: &str) {
+    let text_bytes = text.as_bytes();
 
-    if !exists!(&error_file_name) {
-        let mut error_logs = File::create_new(error_file_name).unwrap();
+    if !exists!(file_name) {
+        let mut logs = File::create_new(file_name).unwrap();
 
-        error_logs.write_all(error_format_bytes).unwrap();
+        logs.write_all(text_bytes).unwrap();
     } else {
-        let mut error_logs = OpenOptions::new()
-            .append(true)
-            .open(error_file_name)
-            .unwrap();
+        let mut logs = OpenOptions::new().append(true).open(file_name).unwrap();
 
-        error_logs.write_all(error_format_bytes).unwrap();
+        logs.write_all(text_bytes).unwrap();
     }
 }
 
+thread_local! {
+    /// Set while `catch_panic` runs, so the panic hook only logs the panic instead of restoring the
+    /// terminal
+    static IS_CATCHING_PANIC: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f` and return `None` if it panics, the panic is written to the error log and the app keeps
+/// running. Used around the image decoders, which have panicked on malformed images
+pub fn catch_panic<T>(f: impl FnOnce() -> T + UnwindSafe) -> Option<T> {
+    IS_CATCHING_PANIC.with(|is_catching| is_catching.set(true));
+    let result = catch_unwind(f);
+    IS_CATCHING_PANIC.with(|is_catching| is_catching.set(false));
+    result.ok()
+}
+
 pub fn init_error_hooks() -> color_eyre::Result<()> {
     let (panic, error) = HookBuilder::default().into_hooks();
     let panic = panic.into_panic_hook();
@@ -64,6 +110,10 @@ pub fn init_error_hooks() -> color_eyre::Result<()> {
     }))?;
 
     std::panic::set_hook(Box::new(move |info| {
+        if IS_CATCHING_PANIC.with(Cell::get) {
+            write_to_error_log(ErrorType::FromPanic(info));
+            return;
+        }
         let _ = restore();
         write_to_error_log(ErrorType::FromPanic(info));
         panic(info);
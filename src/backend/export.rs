@@ -0,0 +1,166 @@
+use clap::ValueEnum;
+
+use super::database::{get_reading_list, MangaHistoryType, ReadingListEntry};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Csv,
+}
+
+/// The lists of the feed which can be exported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportList {
+    ReadingHistory,
+    PlanToRead,
+}
+
+impl ExportList {
+    fn as_human_readable(self) -> &'static str {
+        match self {
+            Self::ReadingHistory => "Reading history",
+            Self::PlanToRead => "Plan to read",
+        }
+    }
+
+    fn history_type(self) -> MangaHistoryType {
+        match self {
+            Self::ReadingHistory => MangaHistoryType::ReadingHistory,
+            Self::PlanToRead => MangaHistoryType::PlanToRead,
+        }
+    }
+}
+
+fn status(list: ExportList, entry: &ReadingListEntry) -> &'static str {
+    match list {
+        ExportList::PlanToRead => "Plan to read",
+        ExportList::ReadingHistory if entry.is_completed => "Completed",
+        ExportList::ReadingHistory => "Reading",
+    }
+}
+
+fn link(entry: &ReadingListEntry) -> String {
+    format!("https://mangadex.org/title/{}", entry.id)
+}
+
+/// The text of a table cell, pipes would end the cell and new lines the row
+fn markdown_cell(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+/// Fields with commas, quotes or new lines are quoted, and the ones which spreadsheets would take
+/// as a formula are prefixed with `'`
+fn csv_field(text: &str) -> String {
+    let text = if text.starts_with(['=', '+', '-', '@']) {
+        format!("'{text}")
+    } else {
+        text.to_string()
+    };
+
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+fn to_markdown(lists: &[(ExportList, Vec<ReadingListEntry>)]) -> String {
+    let mut markdown = String::new();
+
+    for (list, entries) in lists {
+        markdown.push_str(&format!("## {}\n\n", list.as_human_readable()));
+
+        if entries.is_empty() {
+            markdown.push_str("No mangas yet\n\n");
+            continue;
+        }
+
+        markdown.push_str("| Title | Status | Last chapter | Link |\n");
+        markdown.push_str("| --- | --- | --- | --- |\n");
+
+        for entry in entries {
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                markdown_cell(&entry.title),
+                status(*list, entry),
+                markdown_cell(entry.last_chapter.as_deref().unwrap_or_default()),
+                link(entry)
+            ));
+        }
+
+        markdown.push('\n');
+    }
+
+    markdown
+}
+
+fn to_csv(lists: &[(ExportList, Vec<ReadingListEntry>)]) -> String {
+    let mut csv = String::from("list,title,status,last_chapter,link\n");
+
+    for (list, entries) in lists {
+        for entry in entries {
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                csv_field(list.as_human_readable()),
+                csv_field(&entry.title),
+                status(*list, entry),
+                csv_field(entry.last_chapter.as_deref().unwrap_or_default()),
+                link(entry)
+            ));
+        }
+    }
+
+    csv
+}
+
+/// The chosen lists of the feed written in the given format, used by `manga-tui export`
+pub fn export_reading_lists(
+    lists: &[ExportList],
+    format: ExportFormat,
+) -> rusqlite::Result<String> {
+    let mut exported = vec![];
+
+    for list in lists {
+        exported.push((*list, get_reading_list(list.history_type())?));
+    }
+
+    Ok(match format {
+        ExportFormat::Markdown => to_markdown(&exported),
+        ExportFormat::Csv => to_csv(&exported),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(title: &str, last_chapter: Option<&str>) -> ReadingListEntry {
+        ReadingListEntry {
+            id: "some-id".to_string(),
+            title: title.to_string(),
+            is_completed: false,
+            last_chapter: last_chapter.map(String::from),
+        }
+    }
+
+    #[test]
+    fn titles_are_sanitized_for_each_format() {
+        let lists = vec![(
+            ExportList::ReadingHistory,
+            vec![entry("Kaguya | Love, \"War\"", Some("=1+1"))],
+        )];
+
+        assert_eq!(
+            "## Reading history\n\n| Title | Status | Last chapter | Link |\n| --- | --- | --- | --- |\n| Kaguya \\| Love, \"War\" | Reading | =1+1 | https://mangadex.org/title/some-id |\n\n",
+            to_markdown(&lists)
+        );
+
+        assert_eq!(
+            "list,title,status,last_chapter,link\nReading history,\"Kaguya | Love, \"\"War\"\"\",Reading,'=1+1,https://mangadex.org/title/some-id\n",
+            to_csv(&lists)
+        );
+    }
+}
@@ -1,21 +1,225 @@
+use super::blocklist::Blocklist;
 use super::filter::Languages;
 use super::{ChapterPagesResponse, ChapterResponse, MangaStatisticsResponse, SearchMangaResponse};
+use crate::backend::error_log::write_to_request_log;
 use crate::backend::filter::{Filters, IntoParam};
+use crate::config::{MangaTuiConfig, SourceProfile};
+use crate::global::is_offline;
 use crate::view::pages::manga::ChapterOrder;
 use bytes::Bytes;
 use chrono::Months;
 use once_cell::sync::OnceCell;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{Notify, Semaphore, SemaphorePermit};
 
 #[derive(Clone, Debug)]
 pub struct MangadexClient {
     client: reqwest::Client,
+    /// One for each profile, shared by every request made to it so the pages which make many of
+    /// them at once (like the home page with its covers) don't go over the `max_parallel_requests`
+    /// and `rate_limit` of the source. The rate limit a source reports only slows down its own
+    /// requests, see `RequestQueue`
+    requests: Arc<Mutex<HashMap<String, Arc<RequestQueue>>>>,
 }
 
-pub static MANGADEX_CLIENT_INSTANCE: OnceCell<MangadexClient> = once_cell::sync::OnceCell::new();
+/// Covers and images are requested in the background, the data of the page being shown (like
+/// the sections of the home page or the search results) is sent before them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RequestPriority {
+    Visible,
+    Background,
+}
+
+/// The `Visible` requests wait in the order they were made, the `Background` ones only take a
+/// slot when it's free and no `Visible` request is waiting for one
+#[derive(Debug)]
+struct RequestQueue {
+    permits: Semaphore,
+    visible_waiting: AtomicUsize,
+    /// Notified when a slot is freed or a `Visible` request got one
+    changed: Notify,
+    /// Time between two requests, from the `rate_limit` of the profile
+    interval: Option<Duration>,
+    /// When the next request can be sent with `interval`
+    next_request_at: Mutex<Option<Instant>>,
+    /// Taken from the last response of the source which had the rate limit headers
+    rate_limit: Mutex<Option<RateLimit>>,
+}
+
+/// Frees the slot of the request when it's dropped, letting the `Background` requests know
+struct RequestSlot<'a> {
+    permit: Option<SemaphorePermit<'a>>,
+    queue: &'a RequestQueue,
+}
+
+impl Drop for RequestSlot<'_> {
+    fn drop(&mut self) {
+        // The permit is released before notifying so the slot is already free when they check
+        self.permit.take();
+        self.queue.changed.notify_waiters();
+    }
+}
+
+/// Counts a `Visible` request as waiting until it's dropped, so a request aborted while it waits
+/// doesn't keep the `Background` ones waiting forever
+struct VisibleWaiting<'a> {
+    queue: &'a RequestQueue,
+}
+
+impl<'a> VisibleWaiting<'a> {
+    fn new(queue: &'a RequestQueue) -> Self {
+        queue.visible_waiting.fetch_add(1, Ordering::SeqCst);
+        Self { queue }
+    }
+}
+
+impl Drop for VisibleWaiting<'_> {
+    fn drop(&mut self) {
+        self.queue.visible_waiting.fetch_sub(1, Ordering::SeqCst);
+        self.queue.changed.notify_waiters();
+    }
+}
+
+impl RequestQueue {
+    fn new(max_parallel_requests: usize, rate_limit: Option<u32>) -> Self {
+        Self {
+            permits: Semaphore::new(max_parallel_requests.max(1)),
+            visible_waiting: AtomicUsize::new(0),
+            changed: Notify::new(),
+            interval: rate_limit
+                .filter(|rate_limit| *rate_limit > 0)
+                .map(|rate_limit| Duration::from_secs(1) / rate_limit),
+            next_request_at: Mutex::new(None),
+            rate_limit: Mutex::new(None),
+        }
+    }
+
+    fn rate_limit(&self) -> Option<RateLimit> {
+        *self.rate_limit.lock().unwrap()
+    }
+
+    async fn wait_for_rate_limit(&self) {
+        let delay = self
+            .rate_limit()
+            .and_then(|rate_limit| rate_limit.delay(chrono::Utc::now().timestamp()));
+
+        if let Some(delay) = delay {
+            if request_logging_enabled() {
+                write_to_request_log(&format!(
+                    "Rate limit almost reached, waiting {}s",
+                    delay.as_secs()
+                ));
+            }
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn update_rate_limit(&self, response: &Result<reqwest::Response, reqwest::Error>) {
+        if let Some(rate_limit) = response
+            .as_ref()
+            .ok()
+            .and_then(|response| RateLimit::from_headers(response.headers()))
+        {
+            *self.rate_limit.lock().unwrap() = Some(rate_limit);
+        }
+    }
+
+    /// Waits until the `rate_limit` of the profile lets the request be sent, each request takes
+    /// the next free moment so the ones waiting are spread out
+    async fn wait_for_turn(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+
+        let delay = {
+            let mut next_request_at = self.next_request_at.lock().unwrap();
+            let now = Instant::now();
+            let send_at = next_request_at.map_or(now, |at| at.max(now));
+            *next_request_at = Some(send_at + interval);
+            send_at - now
+        };
+
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn acquire(&self, priority: RequestPriority) -> RequestSlot<'_> {
+        let permit = match priority {
+            RequestPriority::Visible => {
+                let waiting = VisibleWaiting::new(self);
+                let permit = self.permits.acquire().await;
+                drop(waiting);
+                permit.expect("the request semaphore is never closed")
+            }
+            RequestPriority::Background => loop {
+                let changed = self.changed.notified();
+                tokio::pin!(changed);
+                // Registered before checking, so a slot freed in between is not missed
+                changed.as_mut().enable();
+                if self.visible_waiting.load(Ordering::SeqCst) == 0 {
+                    if let Ok(permit) = self.permits.try_acquire() {
+                        break permit;
+                    }
+                }
+                changed.await;
+            },
+        };
+
+        RequestSlot {
+            permit: Some(permit),
+            queue: self,
+        }
+    }
+}
+
+/// When this few requests are left before the rate limit, the next ones wait until it's reset
+/// instead of being answered with a 429
+static MIN_REMAINING_REQUESTS: u32 = 2;
+
+/// Longest wait for the rate limit to be reset, in case the clock of the computer is off
+static MAX_RATE_LIMIT_DELAY: Duration = Duration::from_secs(60);
+
+/// The rate limit mangadex reports on its `X-RateLimit-*` headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: u32,
+    pub remaining: u32,
+    /// Unix timestamp, in seconds, at which the limit is reset
+    pub retry_after: i64,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<i64>().ok();
+
+        Some(Self {
+            limit: header("x-ratelimit-limit")?.try_into().ok()?,
+            remaining: header("x-ratelimit-remaining")?.try_into().ok()?,
+            retry_after: header("x-ratelimit-retry-after")?,
+        })
+    }
+
+    /// How long to wait before the next request, `None` if there are enough requests left or the
+    /// limit was already reset
+    fn delay(&self, now: i64) -> Option<Duration> {
+        if self.remaining > MIN_REMAINING_REQUESTS || self.retry_after <= now {
+            return None;
+        }
+
+        Some(Duration::from_secs((self.retry_after - now) as u64).min(MAX_RATE_LIMIT_DELAY))
+    }
+}
 
-static API_URL_BASE: &str = "https://api.mangadex.org";
-static COVER_IMG_URL_BASE: &str = "https://uploads.mangadex.org/covers";
+pub static MANGADEX_CLIENT_INSTANCE: OnceCell<MangadexClient> = once_cell::sync::OnceCell::new();
 
 pub static ITEMS_PER_PAGE_CHAPTERS: u32 = 16;
 
@@ -23,6 +227,100 @@ pub static ITEMS_PER_PAGE_LATEST_CHAPTERS: u32 = 5;
 
 pub static ITEMS_PER_PAGE_SEARCH: u32 = 10;
 
+/// Mangadex returns an error for searches whose `offset + limit` goes over it
+pub static MAX_SEARCH_OFFSET: u32 = 10_000;
+
+/// How many pages of the search results can be requested, for very broad searches it's less than
+/// the total because of `MAX_SEARCH_OFFSET`
+pub fn searchable_pages(total_result: u32) -> u32 {
+    total_result
+        .div_ceil(ITEMS_PER_PAGE_SEARCH)
+        .min(MAX_SEARCH_OFFSET / ITEMS_PER_PAGE_SEARCH)
+}
+
+/// How many mangas are matched against the search term by their description for each page of
+/// the results
+pub static ITEMS_PER_DESCRIPTION_SEARCH: u32 = 100;
+
+pub static ITEMS_PER_SUGGESTIONS: u32 = 5;
+
+static RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Setting it to anything but `0` or `false` logs the requests even if `log_requests` is disabled
+static LOG_REQUESTS_ENV: &str = "MANGA_TUI_LOG_REQUESTS";
+
+/// Headers whose value is never written to the request logs
+static SENSITIVE_HEADERS: [&str; 4] = [
+    "authorization",
+    "cookie",
+    "proxy-authorization",
+    "x-api-key",
+];
+
+fn request_logging_enabled() -> bool {
+    MangaTuiConfig::get().log_requests
+        || std::env::var(LOG_REQUESTS_ENV).is_ok_and(|value| value != "0" && value != "false")
+}
+
+/// The headers of the profile are redacted too, since they are usually keys
+fn redacted_headers(headers: &HeaderMap) -> String {
+    let profile_headers = MangaTuiConfig::get().active_profile().headers;
+
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let is_sensitive = SENSITIVE_HEADERS.contains(&name.as_str())
+                || profile_headers
+                    .keys()
+                    .any(|header| header.eq_ignore_ascii_case(name.as_str()));
+
+            let value = if is_sensitive {
+                "<redacted>"
+            } else {
+                value.to_str().unwrap_or("<binary>")
+            };
+            format!("{name}: {value}")
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// The urls are taken from the active profile on each request, so switching the profile while the
+/// app runs takes effect right away
+fn api_url() -> String {
+    MangaTuiConfig::get().active_profile().api_url
+}
+
+fn covers_url() -> String {
+    MangaTuiConfig::get().active_profile().covers_url
+}
+
+fn profile_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &MangaTuiConfig::get().active_profile().headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            headers.insert(name, value);
+        }
+    }
+    headers
+}
+
+/// The home page always uses the content ratings of the config file, the search uses the ones
+/// selected on its filters
+fn home_content_rating_param() -> String {
+    MangaTuiConfig::get().content_rating.clone().into_param()
+}
+
+fn recently_added_content_rating_param() -> String {
+    MangaTuiConfig::get()
+        .recently_added_content_rating
+        .clone()
+        .into_param()
+}
+
 impl MangadexClient {
     pub fn global() -> &'static MangadexClient {
         MANGADEX_CLIENT_INSTANCE
@@ -31,7 +329,105 @@ impl MangadexClient {
     }
 
     pub fn new(client: reqwest::Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            requests: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// The queue of the active profile
+    fn request_queue(&self) -> Arc<RequestQueue> {
+        let config = MangaTuiConfig::get();
+        self.request_queue_of(&config.active_profile(), config.max_parallel_requests)
+    }
+
+    /// It's made with the limits of the profile the first time a request is sent to it,
+    /// `max_parallel_requests` is used if the profile doesn't set its own
+    fn request_queue_of(
+        &self,
+        profile: &SourceProfile,
+        max_parallel_requests: usize,
+    ) -> Arc<RequestQueue> {
+        let mut requests = self.requests.lock().unwrap();
+        let queue = requests.entry(profile.name.clone()).or_insert_with(|| {
+            Arc::new(RequestQueue::new(
+                profile
+                    .max_parallel_requests
+                    .unwrap_or(max_parallel_requests),
+                profile.rate_limit,
+            ))
+        });
+        Arc::clone(queue)
+    }
+
+    async fn send(&self, url: impl AsRef<str>) -> Result<reqwest::Response, FetchError> {
+        self.send_with_priority(url, RequestPriority::Visible).await
+    }
+
+    async fn send_in_background(
+        &self,
+        url: impl AsRef<str>,
+    ) -> Result<reqwest::Response, FetchError> {
+        self.send_with_priority(url, RequestPriority::Background)
+            .await
+    }
+
+    /// Every request is sent from here, in offline mode it fails right away without building the
+    /// request
+    async fn send_with_priority(
+        &self,
+        url: impl AsRef<str>,
+        priority: RequestPriority,
+    ) -> Result<reqwest::Response, FetchError> {
+        if is_offline() {
+            return Err(FetchError::Offline);
+        }
+
+        let url = url.as_ref();
+
+        let request = self.client.get(url).headers(profile_headers()).build()?;
+
+        let requests = self.request_queue();
+
+        // Waiting for the rate limit doesn't hold a slot, so the requests sleeping on it don't keep
+        // the other ones from being sent
+        requests.wait_for_rate_limit().await;
+        requests.wait_for_turn().await;
+
+        // Released once the response starts arriving, reading its body doesn't hold a permit
+        let _permit = requests.acquire(priority).await;
+
+        if !request_logging_enabled() {
+            let response = self.client.execute(request).await;
+            requests.update_rate_limit(&response);
+            return Ok(response?);
+        }
+
+        let headers = redacted_headers(request.headers());
+        let started_at = Instant::now();
+        let response = self.client.execute(request).await;
+        let elapsed = started_at.elapsed().as_millis();
+        requests.update_rate_limit(&response);
+
+        let outcome = match response.as_ref() {
+            Ok(response) => response.status().to_string(),
+            Err(e) => format!("failed: {e}"),
+        };
+
+        write_to_request_log(&format!("GET {url} | {outcome} | {elapsed}ms | {headers}"));
+
+        Ok(response?)
+    }
+
+    /// Every request which lists mangas goes through here so the mangas in the blocklist are never
+    /// displayed
+    async fn search_mangas_without_blocked(
+        &self,
+        url: String,
+    ) -> Result<SearchMangaResponse, FetchError> {
+        let mut response: SearchMangaResponse = self.send(url).await?.json().await?;
+        response.data = Blocklist::get().filter_mangas(response.data);
+        Ok(response)
     }
 
     pub async fn search_mangas(
@@ -39,7 +435,7 @@ impl MangadexClient {
         search_term: &str,
         page: u32,
         filters: Filters,
-    ) -> Result<SearchMangaResponse, reqwest::Error> {
+    ) -> Result<SearchMangaResponse, FetchError> {
         let offset = (page - 1) * ITEMS_PER_PAGE_SEARCH;
 
         let search_by_title = if search_term.trim().is_empty() {
@@ -50,54 +446,107 @@ impl MangadexClient {
 
         let url = format!(
             "{}/manga?{}&includes[]=cover_art&includes[]=author&includes[]=artist&limit=10&offset={}{}&includedTagsMode=AND&excludedTagsMode=OR&hasAvailableChapters=true",
-            API_URL_BASE,
+            api_url(),
             search_by_title,
             offset,
             filters.into_param(),
         );
 
-        self.client.get(url).send().await?.json().await
+        self.search_mangas_without_blocked(url).await
+    }
+
+    /// Mangadex can only search by title, so in order to find mangas by their description a larger
+    /// batch of mangas matching the filters is fetched for each page, which then is matched
+    /// against the search term client side. Pages past `MAX_SEARCH_OFFSET` bring no mangas
+    pub async fn search_mangas_for_description_match(
+        &self,
+        page: u32,
+        filters: Filters,
+    ) -> Result<SearchMangaResponse, FetchError> {
+        let offset = (page - 1) * ITEMS_PER_DESCRIPTION_SEARCH;
+
+        if offset + ITEMS_PER_DESCRIPTION_SEARCH > MAX_SEARCH_OFFSET {
+            return Ok(SearchMangaResponse::default());
+        }
+
+        let url = format!(
+            "{}/manga?includes[]=cover_art&includes[]=author&includes[]=artist&limit={}&offset={}{}&includedTagsMode=AND&excludedTagsMode=OR&hasAvailableChapters=true",
+            api_url(),
+            ITEMS_PER_DESCRIPTION_SEARCH,
+            offset,
+            filters.into_param(),
+        );
+
+        self.search_mangas_without_blocked(url).await
+    }
+
+    /// Lightweight version of `search_mangas` used to suggest titles while the user is typing
+    pub async fn search_title_suggestions(
+        &self,
+        search_term: &str,
+        filters: Filters,
+    ) -> Result<SearchMangaResponse, FetchError> {
+        let url = format!(
+            "{}/manga?title={}&includes[]=cover_art&includes[]=author&includes[]=artist&limit={}&offset=0{}&includedTagsMode=AND&excludedTagsMode=OR&hasAvailableChapters=true",
+            api_url(),
+            search_term,
+            ITEMS_PER_SUGGESTIONS,
+            filters.into_param(),
+        );
+
+        self.search_mangas_without_blocked(url).await
     }
 
     pub async fn get_cover_for_manga(
         &self,
         id_manga: &str,
         file_name: &str,
-    ) -> Result<bytes::Bytes, reqwest::Error> {
+    ) -> Result<bytes::Bytes, FetchError> {
         let file_name = format!("{}.512.jpg", file_name);
-        self.client
-            .get(format!("{}/{}/{}", COVER_IMG_URL_BASE, id_manga, file_name))
-            .send()
+        Ok(self
+            .send_in_background(format!("{}/{}/{}", covers_url(), id_manga, file_name))
             .await?
             .bytes()
-            .await
+            .await?)
+    }
+
+    /// Every cover of the manga, there is usually one for each volume
+    pub async fn get_manga_covers(
+        &self,
+        id_manga: &str,
+    ) -> Result<super::covers::CoversResponse, FetchError> {
+        let endpoint = format!(
+            "{}/cover?manga[]={}&limit=100&order[volume]=asc",
+            api_url(),
+            id_manga
+        );
+
+        Ok(self.send(endpoint).await?.json().await?)
     }
 
     pub async fn get_cover_for_manga_lower_quality(
         &self,
         id_manga: &str,
         file_name: &str,
-    ) -> Result<bytes::Bytes, reqwest::Error> {
+    ) -> Result<bytes::Bytes, FetchError> {
         let file_name = format!("{}.256.jpg", file_name);
-        self.client
-            .get(format!("{}/{}/{}", COVER_IMG_URL_BASE, id_manga, file_name))
-            .send()
+        Ok(self
+            .send_in_background(format!("{}/{}/{}", covers_url(), id_manga, file_name))
             .await?
             .bytes()
-            .await
+            .await?)
     }
 
     pub async fn get_chapter_page(
         &self,
         endpoint: &str,
         file_name: &str,
-    ) -> Result<Bytes, reqwest::Error> {
-        self.client
-            .get(format!("{}/{}", endpoint, file_name))
-            .send()
+    ) -> Result<Bytes, FetchError> {
+        Ok(self
+            .send(format!("{}/{}", endpoint, file_name))
             .await?
             .bytes()
-            .await
+            .await?)
     }
 
     pub async fn get_manga_chapters(
@@ -106,104 +555,314 @@ impl MangadexClient {
         page: u32,
         language: Languages,
         order: ChapterOrder,
-    ) -> Result<ChapterResponse, reqwest::Error> {
+    ) -> Result<ChapterResponse, FetchError> {
         let language = language.as_iso_code();
         let page = (page - 1) * ITEMS_PER_PAGE_CHAPTERS;
 
         let order = format!("order[volume]={order}&order[chapter]={order}");
         let endpoint = format!(
             "{}/manga/{}/feed?limit={ITEMS_PER_PAGE_CHAPTERS}&offset={}&{}&translatedLanguage[]={}&includes[]=scanlation_group&includeExternalUrl=0&contentRating[]=safe&contentRating[]=suggestive&contentRating[]=erotica&contentRating[]=pornographic",
-            API_URL_BASE, id, page, order, language
+            api_url(), id, page, order, language
         );
 
-        self.client.get(endpoint).send().await?.json().await
+        Ok(self.send(endpoint).await?.json().await?)
     }
 
-    pub async fn get_chapter_pages(
-        &self,
-        id: &str,
-    ) -> Result<ChapterPagesResponse, reqwest::Error> {
-        let endpoint = format!("{}/at-home/server/{}", API_URL_BASE, id);
+    pub async fn get_chapter_pages(&self, id: &str) -> Result<ChapterPagesResponse, FetchError> {
+        let endpoint = format!("{}/at-home/server/{}", api_url(), id);
 
-        self.client.get(endpoint).send().await?.json().await
+        Ok(self.send(endpoint).await?.json().await?)
     }
 
     pub async fn get_manga_statistics(
         &self,
         id_manga: &str,
-    ) -> Result<MangaStatisticsResponse, reqwest::Error> {
-        let endpoint = format!("{}/statistics/manga/{}", API_URL_BASE, id_manga);
+    ) -> Result<MangaStatisticsResponse, FetchError> {
+        let endpoint = format!("{}/statistics/manga/{}", api_url(), id_manga);
 
-        self.client.get(endpoint).send().await?.json().await
+        Ok(self.send(endpoint).await?.json().await?)
     }
 
-    pub async fn get_popular_mangas(&self) -> Result<SearchMangaResponse, reqwest::Error> {
+    pub async fn get_popular_mangas(&self) -> Result<SearchMangaResponse, FetchError> {
         let current_date = chrono::offset::Local::now()
             .date_naive()
             .checked_sub_months(Months::new(1))
             .unwrap();
 
-        let endpoint = format!("{}/manga?includes[]=cover_art&includes[]=artist&includes[]=author&order[followedCount]=desc&contentRating[]=safe&contentRating[]=suggestive&hasAvailableChapters=true&availableTranslatedLanguage[]={}&createdAtSince={}T00:00:00", API_URL_BASE, Languages::get_preferred_lang().as_iso_code(), current_date);
+        let endpoint = format!("{}/manga?includes[]=cover_art&includes[]=artist&includes[]=author&order[followedCount]=desc{}&hasAvailableChapters=true&availableTranslatedLanguage[]={}&createdAtSince={}T00:00:00", api_url(), home_content_rating_param(), Languages::get_preferred_lang().as_iso_code(), current_date);
 
-        self.client.get(endpoint).send().await?.json().await
+        self.search_mangas_without_blocked(endpoint).await
     }
 
-    pub async fn get_recently_added(&self) -> Result<SearchMangaResponse, reqwest::Error> {
-        let endpoint = format!("{}/manga?limit=5&contentRating[]=safe&contentRating[]=suggestive&contentRating[]=erotica&order[createdAt]=desc&includes[]=cover_art&includes[]=artist&includes[]=author&hasAvailableChapters=true&availableTranslatedLanguage[]={}", API_URL_BASE, Languages::get_preferred_lang().as_iso_code());
+    pub async fn get_recently_added(&self) -> Result<SearchMangaResponse, FetchError> {
+        let endpoint = format!("{}/manga?limit=5{}&order[createdAt]=desc&includes[]=cover_art&includes[]=artist&includes[]=author&hasAvailableChapters=true&availableTranslatedLanguage[]={}", api_url(), recently_added_content_rating_param(), Languages::get_preferred_lang().as_iso_code());
 
-        self.client.get(endpoint).send().await?.json().await
+        self.search_mangas_without_blocked(endpoint).await
     }
 
     // Todo! store image in this repo since it may change in the future
-    pub async fn get_mangadex_image_support(&self) -> Result<Bytes, reqwest::Error> {
-        self.client
-            .get("https://mangadex.org/img/namicomi/support-dex-chan-1.png")
-            .send()
+    pub async fn get_mangadex_image_support(&self) -> Result<Bytes, FetchError> {
+        Ok(self
+            .send_in_background("https://mangadex.org/img/namicomi/support-dex-chan-1.png")
             .await?
             .bytes()
-            .await
+            .await?)
     }
 
     pub async fn get_one_manga(
         &self,
         manga_id: &str,
-    ) -> Result<super::feed::OneMangaResponse, reqwest::Error> {
+    ) -> Result<super::feed::OneMangaResponse, FetchError> {
         let endpoint = format!(
             "{}/manga/{}?includes[]=cover_art&includes[]=author&includes[]=artist",
-            API_URL_BASE, manga_id
+            api_url(),
+            manga_id
         );
-        self.client.get(endpoint).send().await?.json().await
+        Ok(self.send(endpoint).await?.json().await?)
     }
 
-    pub async fn get_latest_chapters(
-        &self,
-        manga_id: &str,
-    ) -> Result<ChapterResponse, reqwest::Error> {
+    pub async fn get_latest_chapters(&self, manga_id: &str) -> Result<ChapterResponse, FetchError> {
         let endpoint = format!(
             "{}/manga/{}/feed?limit={}&includes[]=scanlation_group&offset=0&contentRating[]=safe&contentRating[]=suggestive&contentRating[]=erotica&contentRating[]=pornographic&order[readableAt]=desc",
-            API_URL_BASE, manga_id, ITEMS_PER_PAGE_LATEST_CHAPTERS
+            api_url(), manga_id, ITEMS_PER_PAGE_LATEST_CHAPTERS
         );
-        self.client.get(endpoint).send().await?.json().await
+        Ok(self.send(endpoint).await?.json().await?)
     }
 
-    pub async fn get_tags(&self) -> Result<super::tags::TagsResponse, reqwest::Error> {
-        let endpoint = format!("{}/manga/tag", API_URL_BASE);
+    pub async fn get_tags(&self) -> Result<super::tags::TagsResponse, FetchError> {
+        let endpoint = format!("{}/manga/tag", api_url());
 
-        self.client.get(endpoint).send().await?.json().await
+        Ok(self.send(endpoint).await?.json().await?)
     }
 
     pub async fn get_authors(
         &self,
         name: &str,
-    ) -> Result<super::authors::AuthorsResponse, reqwest::Error> {
-        let endpoint = format!("{}/author?name={}", API_URL_BASE, name);
+    ) -> Result<super::authors::AuthorsResponse, FetchError> {
+        let endpoint = format!("{}/author?name={}", api_url(), name);
+
+        Ok(self.send(endpoint).await?.json().await?)
+    }
+
+    pub async fn check_status(&self) -> Result<StatusCode, FetchError> {
+        let endpoint = format!("{}/ping", api_url());
 
-        self.client.get(endpoint).send().await?.json().await
+        Ok(self.send(endpoint).await?.status())
     }
+}
+
+/// Why a request to mangadex failed, in offline mode requests are not even built
+#[derive(Debug)]
+pub enum FetchError {
+    Offline,
+    Request(reqwest::Error),
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Request(error)
+    }
+}
 
-    pub async fn check_status(&self) -> Result<StatusCode, reqwest::Error> {
-        let endpoint = format!("{}/ping", API_URL_BASE);
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Offline => write!(f, "requests are disabled in offline mode"),
+            Self::Request(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for FetchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Offline => None,
+            Self::Request(error) => Some(error),
+        }
+    }
+}
+
+/// Returned when every attempt of a request failed, it keeps how many attempts were made so they
+/// can be shown to the user
+#[derive(Debug)]
+pub struct RetriesExhausted {
+    pub attempts: u32,
+    pub source: FetchError,
+}
+
+impl Display for RetriesExhausted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "request failed after {} attempts: {}",
+            self.attempts, self.source
+        )
+    }
+}
+
+impl Error for RetriesExhausted {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Make the request again when it fails, up to `retries` times, waiting a bit longer after each
+/// attempt in case mangadex is rate limiting or the connection is unstable. In offline mode
+/// requests always fail so they are not retried
+pub async fn with_retries<T, F, Fut>(retries: u32, mut request: F) -> Result<T, RetriesExhausted>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+{
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match request().await {
+            Ok(response) => return Ok(response),
+            Err(source) if attempts > retries || is_offline() => {
+                return Err(RetriesExhausted { attempts, source });
+            }
+            Err(_) => tokio::time::sleep(RETRY_DELAY * attempts).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn search_pages_stop_at_the_offset_limit() {
+        assert_eq!(0, searchable_pages(0));
+        assert_eq!(3, searchable_pages(21));
+        assert_eq!(1000, searchable_pages(10_000));
+        assert_eq!(1000, searchable_pages(54_321));
+    }
+
+    #[tokio::test]
+    async fn visible_requests_are_served_before_background_ones() {
+        let queue = Arc::new(RequestQueue::new(1, None));
+        let served = Arc::new(Mutex::new(vec![]));
+
+        let permit = queue.acquire(RequestPriority::Visible).await;
+
+        let background = tokio::spawn({
+            let (queue, served) = (Arc::clone(&queue), Arc::clone(&served));
+            async move {
+                let _permit = queue.acquire(RequestPriority::Background).await;
+                served.lock().unwrap().push(RequestPriority::Background);
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let visible = tokio::spawn({
+            let (queue, served) = (Arc::clone(&queue), Arc::clone(&served));
+            async move {
+                let _permit = queue.acquire(RequestPriority::Visible).await;
+                served.lock().unwrap().push(RequestPriority::Visible);
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        drop(permit);
+        background.await.unwrap();
+        visible.await.unwrap();
+
+        assert_eq!(
+            vec![RequestPriority::Visible, RequestPriority::Background],
+            *served.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn aborted_visible_requests_stop_waiting() {
+        let queue = Arc::new(RequestQueue::new(1, None));
+
+        let permit = queue.acquire(RequestPriority::Visible).await;
+
+        let visible = tokio::spawn({
+            let queue = Arc::clone(&queue);
+            async move {
+                let _permit = queue.acquire(RequestPriority::Visible).await;
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        visible.abort();
+        assert!(visible.await.unwrap_err().is_cancelled());
+        drop(permit);
+
+        let background = tokio::time::timeout(
+            Duration::from_secs(1),
+            queue.acquire(RequestPriority::Background),
+        )
+        .await;
+
+        assert!(background.is_ok());
+    }
+
+    #[tokio::test]
+    async fn requests_are_spread_out_by_the_rate_limit_of_the_profile() {
+        let queue = RequestQueue::new(6, Some(20));
+        let started_at = Instant::now();
+
+        for _ in 0..3 {
+            queue.wait_for_turn().await;
+        }
+
+        assert!(started_at.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn the_rate_limit_of_a_profile_does_not_throttle_the_others() {
+        let client = MangadexClient::new(reqwest::Client::new());
+        let mangadex = SourceProfile::default();
+        let mirror = SourceProfile {
+            name: "mirror".to_string(),
+            ..SourceProfile::default()
+        };
+
+        let exhausted = RateLimit {
+            limit: 5,
+            remaining: 0,
+            retry_after: chrono::Utc::now().timestamp() + 30,
+        };
+        let mangadex_queue = client.request_queue_of(&mangadex, 6);
+        *mangadex_queue.rate_limit.lock().unwrap() = Some(exhausted);
+
+        let mirror_wait = tokio::time::timeout(
+            Duration::from_millis(100),
+            client.request_queue_of(&mirror, 6).wait_for_rate_limit(),
+        )
+        .await;
+
+        assert!(mirror_wait.is_ok());
+        assert_eq!(None, client.request_queue_of(&mirror, 6).rate_limit());
+        assert_eq!(
+            Some(exhausted),
+            client.request_queue_of(&mangadex, 6).rate_limit()
+        );
+    }
+
+    #[test]
+    fn requests_wait_when_the_rate_limit_is_almost_reached() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("40"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("1"));
+        headers.insert("x-ratelimit-retry-after", HeaderValue::from_static("1000"));
+
+        let rate_limit = RateLimit::from_headers(&headers).unwrap();
+
+        assert_eq!(Some(Duration::from_secs(10)), rate_limit.delay(990));
+        assert_eq!(None, rate_limit.delay(1000));
+        assert_eq!(Some(MAX_RATE_LIMIT_DELAY), rate_limit.delay(0));
+
+        let plenty_left = RateLimit {
+            remaining: 30,
+            ..rate_limit
+        };
+        assert_eq!(None, plenty_left.delay(990));
 
-        Ok(self.client.get(endpoint).send().await?.status())
+        assert_eq!(None, RateLimit::from_headers(&HeaderMap::new()));
     }
 }
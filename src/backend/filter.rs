@@ -1,6 +1,9 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 use strum::{Display, EnumIter, IntoEnumIterator};
 
+use super::blocklist::Blocklist;
+use crate::config::MangaTuiConfig;
 use crate::global::PREFERRED_LANGUAGE;
 use crate::view::widgets::filter_widget::state::FilterListItem;
 
@@ -8,13 +11,15 @@ pub trait IntoParam {
     fn into_param(self) -> String;
 }
 
-#[derive(Display, Clone)]
+#[derive(Display, Debug, Clone, EnumIter, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ContentRating {
     #[strum(to_string = "safe")]
     Safe,
     #[strum(to_string = "suggestive")]
     Suggestive,
     #[strum(to_string = "erotica")]
+    #[serde(rename = "erotica")]
     Erotic,
     #[strum(to_string = "pornographic")]
     Pornographic,
@@ -32,6 +37,12 @@ impl From<&str> for ContentRating {
     }
 }
 
+impl ContentRating {
+    pub fn is_explicit(&self) -> bool {
+        matches!(self, Self::Erotic | Self::Pornographic)
+    }
+}
+
 #[derive(Display, Clone, EnumIter, PartialEq, Eq, Default)]
 pub enum SortBy {
     #[strum(to_string = "Best match")]
@@ -308,10 +319,22 @@ impl Languages {
             Self::Unkown => unreachable!(),
         }
     }
-    pub fn get_preferred_lang() -> &'static Languages {
-        PREFERRED_LANGUAGE
-            .get()
-            .expect("an error ocurred when setting preferred language")
+    /// The language of `lang --set` if the app was started with it, otherwise the one of the
+    /// active profile or of the config. It's read each time so switching the profile changes it
+    /// right away
+    pub fn get_preferred_lang() -> Languages {
+        if let Some(language) = PREFERRED_LANGUAGE.get() {
+            return *language;
+        }
+
+        let config = MangaTuiConfig::get();
+        config
+            .active_profile()
+            .language
+            .or_else(|| config.language.clone())
+            .as_deref()
+            .and_then(Self::try_from_iso_code)
+            .unwrap_or_default()
     }
     pub fn as_human_readable(self) -> String {
         self.to_string()
@@ -430,12 +453,13 @@ pub struct Filters {
 impl IntoParam for Filters {
     fn into_param(self) -> String {
         format!(
-            "{}{}{}{}{}{}{}{}",
+            "{}{}{}{}{}{}{}{}{}",
             self.authors.into_param(),
             self.artists.into_param(),
             self.publication_status.into_param(),
             self.languages.into_param(),
             self.tags.into_param(),
+            excluded_tags_param(),
             self.magazine_demographic.into_param(),
             self.content_rating.into_param(),
             self.sort_by.into_param(),
@@ -443,17 +467,29 @@ impl IntoParam for Filters {
     }
 }
 
+/// The tags in the blocklist are always excluded from the search
+fn excluded_tags_param() -> String {
+    Blocklist::get()
+        .blocked_tag_ids()
+        .fold(String::new(), |mut param, id_tag| {
+            let _ = write!(param, "&excludedTags[]={}", id_tag);
+            param
+        })
+}
+
 impl Default for Filters {
     fn default() -> Self {
+        let language = Languages::get_preferred_lang();
+
         Self {
-            content_rating: vec![ContentRating::Safe, ContentRating::Suggestive],
+            content_rating: MangaTuiConfig::get().content_rating.clone(),
             publication_status: vec![],
             sort_by: SortBy::default(),
             tags: Tags(vec![]),
             magazine_demographic: vec![],
             authors: User::<Author>::default(),
             artists: User::<Artist>::default(),
-            languages: vec![*Languages::get_preferred_lang()],
+            languages: vec![language],
         }
     }
 }
@@ -470,6 +506,10 @@ impl Filters {
     pub fn set_sort_by(&mut self, sort_by: SortBy) {
         self.sort_by = sort_by;
     }
+    pub fn has_tags(&self) -> bool {
+        !self.tags.0.is_empty()
+    }
+
     pub fn set_tags(&mut self, tags: Vec<String>) {
         self.tags.0 = tags;
     }
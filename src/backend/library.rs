@@ -0,0 +1,188 @@
+use manga_tui::exists;
+use std::collections::HashSet;
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+
+use super::database::{
+    get_chapter_title, get_indexed_chapters, index_downloaded_chapter, remove_from_library_index,
+    LibraryChapterInsert,
+};
+use super::error_log::{write_to_error_log, ErrorType};
+use super::{AppDirectories, APP_DATA_DIR};
+use crate::global::is_persistence_disabled;
+
+/// A chapter found in the download directory, the data is recovered from the names of the
+/// directories created by `download_chapter`:
+/// `mangaDownloads/{manga title} {manga id}/{language}/Ch. {number} {title} {scanlator} {chapter id}`
+#[derive(Debug, PartialEq, Eq)]
+pub struct ChapterOnDisk {
+    pub chapter_id: String,
+    pub manga_id: String,
+    pub manga_title: String,
+    pub number: String,
+    pub title: String,
+    pub lang: String,
+    pub path: PathBuf,
+}
+
+fn parse_manga_dir_name(name: &str) -> Option<(&str, &str)> {
+    let (title, manga_id) = name.trim().rsplit_once(' ')?;
+    Some((title.trim(), manga_id))
+}
+
+fn parse_chapter_dir_name(name: &str) -> Option<(&str, &str, &str)> {
+    let without_prefix = name.trim().strip_prefix("Ch. ")?;
+    let (rest, chapter_id) = without_prefix.rsplit_once(' ')?;
+    // the scanlator is the last segment before the id, it's empty when the chapter has no group
+    let number_and_title = rest
+        .rsplit_once(' ')
+        .map_or(rest, |(number_and_title, _scanlator)| number_and_title);
+    let (number, title) = number_and_title
+        .split_once(' ')
+        .unwrap_or((number_and_title, ""));
+    Some((number, title.trim(), chapter_id))
+}
+
+fn has_files(dir: &Path) -> bool {
+    read_dir(dir).is_ok_and(|mut entries| entries.next().is_some())
+}
+
+fn dir_names(dir: &Path) -> Vec<(String, PathBuf)> {
+    match read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| {
+                (
+                    entry.file_name().to_string_lossy().to_string(),
+                    entry.path(),
+                )
+            })
+            .collect(),
+        Err(_) => vec![],
+    }
+}
+
+/// Walks the download directory and returns every chapter that has at least one page stored
+pub fn scan_downloads_dir(downloads_dir: &Path) -> Vec<ChapterOnDisk> {
+    let mut chapters: Vec<ChapterOnDisk> = vec![];
+
+    for (manga_dir_name, manga_dir) in dir_names(downloads_dir) {
+        let Some((manga_title, manga_id)) = parse_manga_dir_name(&manga_dir_name) else {
+            continue;
+        };
+
+        for (lang, lang_dir) in dir_names(&manga_dir) {
+            for (chapter_dir_name, chapter_dir) in dir_names(&lang_dir) {
+                let Some((number, title, chapter_id)) = parse_chapter_dir_name(&chapter_dir_name)
+                else {
+                    continue;
+                };
+
+                if !has_files(&chapter_dir) {
+                    continue;
+                }
+
+                chapters.push(ChapterOnDisk {
+                    chapter_id: chapter_id.to_string(),
+                    manga_id: manga_id.to_string(),
+                    manga_title: manga_title.to_string(),
+                    number: number.to_string(),
+                    title: title.to_string(),
+                    lang: lang.clone(),
+                    path: chapter_dir,
+                });
+            }
+        }
+    }
+
+    chapters
+}
+
+/// Brings the library index up to date with what is actually stored in the download directory:
+/// chapters found on disk which are not indexed yet are added and chapters which were deleted
+/// manually are removed from the index
+pub fn refresh_library_index() {
+    if is_persistence_disabled() {
+        return;
+    }
+
+    let Some(data_dir) = APP_DATA_DIR.as_ref() else {
+        return;
+    };
+
+    let downloads_dir = data_dir.join(AppDirectories::MangaDownloads.to_string());
+
+    let chapters_on_disk = scan_downloads_dir(&downloads_dir);
+
+    let indexed_chapters = match get_indexed_chapters() {
+        Ok(chapters) => chapters,
+        Err(e) => {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+            return;
+        }
+    };
+
+    let mut still_on_disk: HashSet<String> = HashSet::new();
+
+    for indexed in indexed_chapters {
+        let path = Path::new(&indexed.path);
+        if exists!(path) && has_files(path) {
+            still_on_disk.insert(indexed.path);
+        } else if let Err(e) = remove_from_library_index(&indexed.chapter_id) {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
+    }
+
+    for chapter in chapters_on_disk
+        .into_iter()
+        .filter(|chapter| !still_on_disk.contains(chapter.path.to_string_lossy().as_ref()))
+    {
+        let path = chapter.path.to_string_lossy();
+        // Scanlators with spaces in their name can't be told apart from the title in the
+        // directory name, so the title of the reading history is used when there is one
+        let title = match get_chapter_title(&chapter.chapter_id) {
+            Ok(Some(title)) => title,
+            Ok(None) => chapter.title.clone(),
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                chapter.title.clone()
+            }
+        };
+        let index_operation = index_downloaded_chapter(LibraryChapterInsert {
+            chapter_id: &chapter.chapter_id,
+            manga_id: &chapter.manga_id,
+            manga_title: &chapter.manga_title,
+            number: &chapter.number,
+            title: &title,
+            lang: &chapter.lang,
+            path: &path,
+        });
+
+        if let Err(e) = index_operation {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn chapter_dir_names_are_parsed_without_the_scanlator() {
+        assert_eq!(
+            Some(("12", "The return", "some-id")),
+            parse_chapter_dir_name("Ch. 12 The return Scans some-id")
+        );
+        assert_eq!(
+            Some(("12", "", "some-id")),
+            parse_chapter_dir_name("Ch. 12  Scans some-id")
+        );
+        assert_eq!(
+            Some(("12", "The return", "some-id")),
+            parse_chapter_dir_name("Ch. 12 The return  some-id")
+        );
+        assert_eq!(None, parse_chapter_dir_name("12 The return Scans some-id"));
+    }
+}
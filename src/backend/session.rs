@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use super::{AppDirectories, APP_DATA_DIR};
+use crate::global::is_persistence_disabled;
+
+pub static SESSION_FILE: &str = "manga-tui-session.json";
+
+/// Where the app was when it was closed, with `restore_session` enabled it's saved on exit and the
+/// next launch starts from it. Only ids are kept, everything else is fetched again when it's
+/// restored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "tab", rename_all = "snake_case")]
+pub enum Session {
+    Home,
+    Search {
+        /// `None` if no results were shown
+        results: Option<SearchResults>,
+    },
+    Feed,
+    Stats,
+    Manga {
+        manga_id: String,
+    },
+    Reader {
+        manga_id: String,
+        chapter_id: String,
+        /// Page of the chapter
+        page: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchResults {
+    pub term: String,
+    /// Page of the results
+    pub page: u32,
+    pub selected: Option<usize>,
+}
+
+impl Session {
+    /// `None` when there is no data directory
+    pub fn file_path() -> Option<PathBuf> {
+        Some(
+            APP_DATA_DIR
+                .as_ref()?
+                .join(AppDirectories::Config.to_string())
+                .join(SESSION_FILE),
+        )
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(path) = Self::file_path().filter(|_| !is_persistence_disabled()) else {
+            return Ok(());
+        };
+
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// The session is only restored once, so it's removed as soon as it's read. Without
+    /// persistence it's neither read nor removed
+    pub fn take() -> Option<Self> {
+        let path = Self::file_path().filter(|_| !is_persistence_disabled())?;
+        let contents = fs::read_to_string(&path).ok()?;
+        fs::remove_file(&path).ok();
+        serde_json::from_str(&contents).ok()
+    }
+}
@@ -1,4 +1,7 @@
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture, KeyEvent, MouseEvent};
+use crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, KeyEvent,
+    MouseEvent,
+};
 use crossterm::execute;
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
@@ -6,12 +9,28 @@ use crossterm::terminal::{
 use futures::{FutureExt, StreamExt};
 use ratatui::backend::Backend;
 use ratatui::prelude::*;
+use ratatui::layout::Flex;
+use ratatui::widgets::Paragraph;
+use ratatui_image::picker::ProtocolType;
 use std::error::Error;
+use std::io::Stdout;
+use std::path::PathBuf;
 use std::time::Duration;
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use throbber_widgets_tui::{Throbber, ThrobberState};
 use tokio::task::JoinHandle;
+use unicode_width::UnicodeWidthStr;
 use crate::common::{Artist, Author};
+use crate::backend::filter::Languages;
+use crate::backend::color_depth::reduce_buffer_colors;
+use crate::backend::error_log::{write_to_error_log, ErrorType};
+use crate::backend::session::Session;
+use crate::config::MangaTuiConfig;
+use crate::global::{is_offline, is_persistence_disabled};
+use crate::utils::remap_key;
 use crate::view::app::{App, AppState};
+use crate::view::pages::onboarding::Onboarding;
+use crate::view::pages::reader::ChapterToRead;
 use crate::view::pages::SelectedPage;
 use crate::view::widgets::search::MangaItem;
 use crate::view::widgets::Component;
@@ -24,48 +43,121 @@ pub enum Action {
 
 /// These are the events this app will listen to
 #[derive(Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum Events {
     Tick,
     Key(KeyEvent),
     Mouse(MouseEvent),
+    /// Text pasted with bracketed paste, it arrives at once instead of as a burst of key events
+    Paste(String),
     GoToMangaPage(MangaItem),
+    /// Open the manga page and read the first chapter which is not read yet
+    ContinueReading(MangaItem),
     GoToHome,
     GoSearchPage,
     GoSearchMangasAuthor(Author),
     GoSearchMangasArtist(Artist),
     GoFeedPage,
-    ReadChapter(ChapterPagesResponse),
+    GoStatsPage,
+    /// Sent from the reader at the end of a chapter
+    ReadNextChapter,
+    /// Go back to where the chapter was opened from
+    CloseReader,
+    ReadChapter(ChapterPagesResponse, ChapterToRead),
+    /// Read a chapter from the download directory instead of mangadex
+    ReadDownloadedChapter(ChapterToRead, PathBuf),
+    /// Display a short message on top of the current page
+    ShowNotification(String),
+    /// Open another search with the term and filters of the current one
+    DuplicateSearch,
+    /// Show the next of the searches opened with `DuplicateSearch`
+    SwitchSearch,
+    CloseSearch,
 }
 
 /// Initialize the terminal
 pub fn init() -> std::io::Result<()> {
-    execute!(std::io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        std::io::stdout(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
     enable_raw_mode()?;
     Ok(())
 }
 
 pub fn restore() -> std::io::Result<()> {
-    execute!(std::io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    execute!(
+        std::io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste
+    )?;
     disable_raw_mode()?;
     Ok(())
 }
 
+/// Bounds of `tick_rate_ms`, faster ticks would only burn CPU and slower ones make the app feel
+/// stuck
+const MIN_TICK_RATE_MS: u64 = 16;
+const MAX_TICK_RATE_MS: u64 = 2000;
+
 ///Start app's main loop
-pub async fn run_app(backend: impl Backend) -> Result<(), Box<dyn Error>> {
+/// `local_folder` is a folder of images to read as soon as the app starts, see `manga-tui read`
+pub async fn run_app(
+    backend: impl Backend,
+    local_folder: Option<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
     let mut terminal = Terminal::new(backend)?;
 
     let mut app = App::new();
 
-    let tick_rate = std::time::Duration::from_millis(250);
+    if is_persistence_disabled() {
+        app.global_event_tx
+            .send(Events::ShowNotification(
+                "The data directory can't be written to, the history and the settings are only kept until the app is closed"
+                    .to_string(),
+            ))
+            .ok();
+    }
+
+    // The session is not restored offline, since the pages are fetched again from mangadex
+    let restore_session = MangaTuiConfig::get().restore_session && !is_offline();
+
+    if let Some(folder) = local_folder {
+        let chapter = ChapterToRead::from_local_folder(&folder);
+        app.global_event_tx
+            .send(Events::ReadDownloadedChapter(chapter, folder))
+            .ok();
+    } else if restore_session {
+        if let Some(session) = Session::take() {
+            app.restore_session(session);
+        }
+    }
+
+    let tick_rate = Duration::from_millis(
+        MangaTuiConfig::get()
+            .tick_rate_ms
+            .clamp(MIN_TICK_RATE_MS, MAX_TICK_RATE_MS),
+    );
 
     let main_event_handle = handle_events(tick_rate, app.global_event_tx.clone());
 
     while app.state == AppState::Runnning {
         terminal.draw(|f| {
             app.render(f.size(), f);
+            reduce_buffer_colors(f.buffer_mut());
         })?;
 
         if let Some(event) = app.global_event_rx.recv().await {
+            let event = match event {
+                Events::Key(key_event) if !app.is_typing() => {
+                    Events::Key(remap_key(key_event, &MangaTuiConfig::get().key_remap))
+                }
+                event => event,
+            };
+
             app.handle_events(event.clone());
             match app.current_tab {
                 SelectedPage::Search => {
@@ -83,6 +175,9 @@ pub async fn run_app(backend: impl Backend) -> Result<(), Box<dyn Error>> {
                 SelectedPage::Feed => {
                     app.feed_page.handle_events(event);
                 }
+                SelectedPage::Stats => {
+                    app.stats_page.handle_events(event);
+                }
             };
         }
 
@@ -120,14 +215,138 @@ pub async fn run_app(backend: impl Backend) -> Result<(), Box<dyn Error>> {
                     app.feed_page.update(feed_event);
                 }
             }
+            SelectedPage::Stats => {
+                if let Ok(stats_action) = app.stats_page.local_action_rx.try_recv() {
+                    app.stats_page.update(stats_action);
+                }
+            }
         };
     }
 
     main_event_handle.abort();
 
+    if restore_session {
+        if let Err(e) = app.session().save() {
+            write_to_error_log(ErrorType::FromError(e));
+        }
+    }
+
     Ok(())
 }
 
+/// Show the onboarding until it's completed or skipped, the settings chosen are written to the
+/// config file. Returns `false` if it was quit with `<Ctrl+c>`, then nothing is written
+pub async fn run_onboarding(
+    backend: impl Backend,
+    detected_protocol: Option<ProtocolType>,
+    language: Languages,
+) -> Result<bool, Box<dyn Error>> {
+    let mut terminal = Terminal::new(backend)?;
+
+    let mut onboarding = Onboarding::new(detected_protocol, language);
+
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel::<Events>();
+
+    let event_handle = handle_events(Duration::from_millis(250), event_tx);
+
+    while !onboarding.is_done {
+        terminal.draw(|f| {
+            onboarding.render(f.size(), f.buffer_mut());
+            reduce_buffer_colors(f.buffer_mut());
+        })?;
+
+        if let Some(Events::Key(key_event)) = event_rx.recv().await {
+            onboarding.handle_key_events(key_event);
+        }
+    }
+
+    event_handle.abort();
+
+    Ok(!onboarding.is_quit)
+}
+
+/// Shown while the app starts up: while mangadex is checked, the image support of the terminal is
+/// detected and the library is loaded. The steps block, so the screen is only drawn when each one
+/// starts. With `splash_screen` disabled nothing is drawn and the terminal is set up at the end
+pub struct Splash {
+    terminal: Option<Terminal<CrosstermBackend<Stdout>>>,
+    throbber_state: ThrobberState,
+}
+
+impl Splash {
+    pub fn start(is_enabled: bool) -> std::io::Result<Self> {
+        let terminal = if is_enabled {
+            init()?;
+            Some(Terminal::new(CrosstermBackend::new(std::io::stdout()))?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            terminal,
+            throbber_state: ThrobberState::default(),
+        })
+    }
+
+    pub fn is_shown(&self) -> bool {
+        self.terminal.is_some()
+    }
+
+    pub fn show(&mut self, step: &str) -> std::io::Result<()> {
+        let Some(terminal) = self.terminal.as_mut() else {
+            return Ok(());
+        };
+
+        self.throbber_state.calc_next();
+        let throbber_state = &mut self.throbber_state;
+
+        terminal.draw(|f| {
+            let [_, title_area, step_area, _] = Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Length(2),
+                Constraint::Length(1),
+                Constraint::Fill(1),
+            ])
+            .areas(f.size());
+
+            Paragraph::new("manga-tui".bold().yellow())
+                .alignment(Alignment::Center)
+                .render(title_area, f.buffer_mut());
+
+            // The throbber is drawn before the label, so both of them are centered together
+            let [step_area] = Layout::horizontal([Constraint::Length(step.width() as u16 + 2)])
+                .flex(Flex::Center)
+                .areas(step_area);
+
+            let throbber = Throbber::default()
+                .label(step)
+                .style(Style::default().fg(Color::Yellow))
+                .throbber_set(throbber_widgets_tui::BRAILLE_SIX);
+
+            StatefulWidget::render(throbber, step_area, f.buffer_mut(), throbber_state);
+        })?;
+
+        Ok(())
+    }
+
+    /// Where startup stops before the app runs, so a message can be printed on the normal screen
+    pub fn close(self) -> std::io::Result<()> {
+        if self.is_shown() {
+            restore()?;
+        }
+        Ok(())
+    }
+
+    /// Leaves the terminal ready for the app, the splash is cleared so the first frame of the app
+    /// doesn't keep parts of it
+    pub fn finish(self) -> std::io::Result<()> {
+        match self.terminal {
+            Some(mut terminal) => terminal.clear(),
+            None => init(),
+        }
+    }
+}
+
 pub fn handle_events(tick_rate: Duration, event_tx: UnboundedSender<Events>) -> JoinHandle<()> {
     tokio::spawn(async move {
         let mut reader = crossterm::event::EventStream::new();
@@ -141,14 +360,16 @@ pub fn handle_events(tick_rate: Duration, event_tx: UnboundedSender<Events>) ->
                     match maybe_event  {
                         Some(Ok(evt)) => {
                             match evt {
-                                crossterm::event::Event::Key(key) => {
-                                    if key.kind == crossterm::event::KeyEventKind::Press {
+                                crossterm::event::Event::Key(key)
+                                    if key.kind == crossterm::event::KeyEventKind::Press => {
                                         event_tx.send(Events::Key(key)).ok();
-                                    }
                                 },
                                 crossterm::event::Event::Mouse(mouse_event) => {
                                     event_tx.send(Events::Mouse(mouse_event)).ok();
                                 }
+                                crossterm::event::Event::Paste(text) => {
+                                    event_tx.send(Events::Paste(text)).ok();
+                                }
                                 _ => {}
                             }
                         }
@@ -1,6 +1,8 @@
 use clap::{crate_version, Parser, Subcommand};
+use std::path::PathBuf;
 use strum::IntoEnumIterator;
 
+use crate::backend::export::{ExportFormat, ExportList};
 use crate::backend::filter::Languages;
 
 #[derive(Subcommand)]
@@ -11,6 +13,24 @@ pub enum Commands {
         #[arg(short, long)]
         set: Option<String>,
     },
+    /// Delete the pages stored in the reader's cache
+    ClearCache,
+    /// Print the image protocol and the color depth which are used to display images
+    Diagnostics,
+    /// Read a folder of images with the reader, the images are sorted by their name and no request
+    /// is made to mangadex
+    Read { folder: PathBuf },
+    /// Write the reading history and plan to read lists as markdown or csv, to the standard
+    /// output unless `--output` is given
+    Export {
+        #[arg(short, long, value_enum, default_value_t)]
+        format: ExportFormat,
+        /// The lists to include, separated by commas
+        #[arg(short, long, value_enum, value_delimiter = ',', default_values_t = [ExportList::ReadingHistory, ExportList::PlanToRead])]
+        lists: Vec<ExportList>,
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 #[derive(Parser)]
@@ -20,6 +40,9 @@ pub struct CliArgs {
     pub command: Option<Commands>,
     #[arg(short, long)]
     pub data_dir: bool,
+    /// Only use the downloaded chapters, no request is made to mangadex
+    #[arg(long)]
+    pub offline: bool,
 }
 
 impl CliArgs {
@@ -12,6 +12,20 @@ pub struct Artist {
     pub name: String,
 }
 
+/// The group is one of mangadex's tag groups: `genre`, `theme`, `format` or `content`
+#[derive(Default, Clone, Debug)]
+pub struct MangaTag {
+    pub name: String,
+    pub group: String,
+}
+
+/// A page about the manga on another site, like anilist or its official publisher
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct MangaLink {
+    pub name: String,
+    pub url: String,
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct Manga {
     pub id: String,
@@ -19,11 +33,14 @@ pub struct Manga {
     pub description: String,
     pub content_rating: String,
     pub publication_demographic : String,
-    pub tags: Vec<String>,
+    pub tags: Vec<MangaTag>,
     pub status: String,
     pub img_url: Option<String>,
     pub author: Author,
     pub artist: Artist,
     pub available_languages: Vec<Languages>,
     pub created_at: String,
+    pub links: Vec<MangaLink>,
+    pub year: Option<u32>,
+    pub original_language: Option<String>,
 }
@@ -0,0 +1,523 @@
+use manga_tui::exists;
+use once_cell::sync::Lazy;
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{RwLock, RwLockReadGuard};
+use strum::Display;
+
+use crate::backend::color_depth::ColorDepth;
+use crate::backend::database::HistoryRetention;
+use crate::backend::error_log::{write_to_error_log, ErrorType};
+use crate::backend::filter::ContentRating;
+use crate::backend::{AppDirectories, APP_DATA_DIR};
+use crate::global::is_persistence_disabled;
+use crate::view::pages::manga::ChapterOrder;
+use crate::view::pages::reader::EndOfChapter;
+use crate::view::pages::search::{EnterOnResults, SelectAction};
+use crate::view::widgets::feed::FeedLayout;
+use crate::view::widgets::search::ResultsGrouping;
+
+pub static CONFIG_FILE: &str = "manga-tui-config.json";
+
+/// Holds the user's settings, it starts with the default values and is replaced by the contents
+/// of the config file when the app starts, see `MangaTuiConfig::load`
+pub static CONFIG: Lazy<RwLock<MangaTuiConfig>> =
+    Lazy::new(|| RwLock::new(MangaTuiConfig::default()));
+
+/// Settings which are stored in the config file, any field missing in the file takes its default
+/// value so older config files keep working as new settings are added
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MangaTuiConfig {
+    /// Percentage of the manga preview (on the search page) which is used by the description,
+    /// the rest is used by the cover and the tags
+    pub preview_description_percentage: u16,
+    /// Maximum amount of megabytes the reader's page cache can use, `0` disables the cache
+    pub reader_cache_size_mb: u64,
+    /// Order of the chapter list when a manga is opened
+    pub chapter_order: ChapterOrder,
+    /// Order of the chapter list when a manga is opened from the feed, where catching up with
+    /// the newest chapters is more common
+    pub feed_chapter_order: ChapterOrder,
+    /// Suggest titles while typing in the search bar, each suggestion is a request to mangadex
+    pub search_suggestions: bool,
+    /// Require pressing the quit key twice in a row to exit, so a mis-hit doesn't close the app
+    pub confirm_quit: bool,
+    /// Colors of the tags of a manga according to their group
+    pub tag_colors: TagColors,
+    /// Rows used by each manga of the search results, a smaller height shows more mangas at once
+    /// and a larger one shows more details of each manga
+    pub search_item_height: u16,
+    /// Always start in offline mode, the same as running the app with `--offline`
+    pub offline: bool,
+    /// How many times a search is made again when it fails before showing the error
+    pub search_retries: u32,
+    /// What opening a manga from the search results does
+    pub select_action: SelectAction,
+    /// Longest search term which is sent to mangadex, longer ones (like an accidental paste) are
+    /// cut to this amount of characters
+    pub max_search_length: usize,
+    /// How images are drawn, see `ImageProtocol`
+    pub image_protocol: ImageProtocol,
+    /// Colors the terminal can display, it's detected when it's not set. Without truecolor support
+    /// images are drawn with blocks if there is no image protocol, dithered to the terminal's
+    /// colors, and the colors of the screen are swapped for the closest ones it has
+    pub color_depth: Option<ColorDepth>,
+    /// How many pages around the one being read are kept decoded by the reader, the rest are
+    /// dropped and loaded again (usually from the cache) when they are read
+    pub max_loaded_pages: usize,
+    /// How many pages the reader downloads at the same time, `1` downloads them one by one which
+    /// is gentler on slow or metered connections
+    pub parallel_page_downloads: usize,
+    /// Most requests made to mangadex at the same time by the whole app, profiles can set their own
+    pub max_parallel_requests: usize,
+    /// Title line of each search result, see `format_search_item_title` for the placeholders
+    pub search_item_title: String,
+    /// Draw a line between the search results
+    pub search_item_separator: bool,
+    /// Show the tags of the search results when they are tall enough, with `<g>` on the search
+    /// page
+    pub search_item_tags: bool,
+    /// What going past the last page of a chapter does
+    pub end_of_chapter: EndOfChapter,
+    /// Write every request made to mangadex to `manga-tui-request-logs.txt`, next to the error
+    /// logs, it can also be enabled with the `MANGA_TUI_LOG_REQUESTS` environment variable
+    pub log_requests: bool,
+    /// Content ratings of the popular mangas of the home page, the search starts with them selected
+    /// on its filters
+    pub content_rating: Vec<ContentRating>,
+    /// ISO code of the language mangas are searched and read in, `lang --set` overrides it
+    pub language: Option<String>,
+    /// Most mangas kept on the reading history, the least recently read ones are taken out of it
+    /// when a chapter is read
+    pub history_max_mangas: Option<u32>,
+    /// Mangas not read for this many days are taken out of the reading history
+    pub history_max_age_days: Option<u32>,
+    /// Keep completed mangas on the reading history regardless of `history_max_mangas` and
+    /// `history_max_age_days`
+    pub history_keep_completed: bool,
+    /// Request the covers of the mangas right above and below the selected one before the others,
+    /// and again when moving to them if their cover is not loaded
+    pub prefetch_covers: bool,
+    /// Request the cover of the selected manga and the ones closest to it first so the preview is
+    /// filled right away
+    pub prioritize_selected_cover: bool,
+    /// Shrink the search bar to a single line once the results are shown, so more of them fit on
+    /// small terminals
+    pub collapse_search_bar: bool,
+    /// How the mangas of the feed are drawn, see `FeedLayout`
+    pub feed_layout: FeedLayout,
+    /// Covers narrower or shorter than this many pixels are shown as missing, mangadex sometimes
+    /// answers with a tiny image instead of the cover. `0` shows every cover
+    pub min_cover_size: u32,
+    /// Text copied with `<c>` on the search page, see `format_share_text` for the placeholders
+    pub share_template: String,
+    /// Codes of the languages titles are shown in, for example `["en", "ja-ro"]`, the first one
+    /// a manga has a title in is used. Mangas without a title in any of them, or an empty list,
+    /// show the main title given by mangadex
+    pub title_languages: Vec<String>,
+    /// strftime format the dates of the chapter list, the feed and the manga page are shown with,
+    /// for example `"%d/%m/%Y %H:%M"`. Without one the dates of the chapters are shown as the time
+    /// since they were published, "3 days ago"
+    pub date_format: Option<String>,
+    /// Timezone the dates are shown in, see `Timezone`
+    pub timezone: Timezone,
+    /// How the selected manga of the search results is shown, see `SelectionStyle`
+    pub selection_style: SelectionStyle,
+    /// Ask before the first search of the session made with the erotica or pornographic content
+    /// ratings, declining makes that search with the safe rating only
+    pub confirm_explicit_search: bool,
+    /// Save the page, the selected manga and the page of the chapter being read on exit and start
+    /// from them on the next launch
+    pub restore_session: bool,
+    /// Milliseconds between the ticks which draw the loading animations and pick up the images
+    /// which finished loading. A lower value makes the app more responsive and uses more CPU, a
+    /// higher one saves battery
+    pub tick_rate_ms: u64,
+    /// How the search results are grouped, see `ResultsGrouping`
+    pub results_grouping: ResultsGrouping,
+    /// Keys pressed instead of the ones the app uses, from the character the keyboard layout
+    /// produces to the key it stands for, they are not remapped while typing
+    pub key_remap: HashMap<char, char>,
+    /// Show the name of the app and what it's doing while it starts, instead of a blank screen
+    pub splash_screen: bool,
+    /// Frame drawn around the covers, see `CoverFrame`
+    pub cover_frame: CoverFrame,
+    /// The sources mangas can be taken from, see `SourceProfile`
+    pub profiles: Vec<SourceProfile>,
+    /// Name of the profile the requests are made to
+    pub active_profile: String,
+    /// Milliseconds each page is shown with auto page turn (`<a>` on the reader) before going to
+    /// the next one, between 500 and 60000
+    pub auto_page_turn_ms: u64,
+    /// Shape of the covers of the recently added mangas on the home page, see `CoverAspect`
+    pub carousel_cover_aspect: CoverAspect,
+    /// What pressing `<Enter>` with nothing typed on the search bar does, see `EmptySearch`
+    pub empty_search: EmptySearch,
+    /// Code of the language descriptions are shown in, for example `"es"`. Mangas without a
+    /// description in it show the english one, or the first one they have
+    pub description_language: Option<String>,
+    /// Names of the scanlation groups to read first, for example `["group a", "group b"]`. When
+    /// several groups translated a chapter the first one in the list is picked
+    pub preferred_groups: Vec<String>,
+    /// `preferred_groups` for some mangas only, keyed by the id of the manga
+    pub manga_preferred_groups: HashMap<String, Vec<String>>,
+    /// Add the mangas to the plan to read list when their first chapter is read, they can be
+    /// removed from it like the ones added by hand
+    pub auto_plan_to_read: bool,
+    /// Content ratings of the recently added mangas of the home page
+    pub recently_added_content_rating: Vec<ContentRating>,
+    /// What `<Enter>` does on the search results, see `EnterOnResults`
+    pub enter_on_results: EnterOnResults,
+}
+
+/// `local` shows the dates in the timezone of the computer, `utc` shows them as given by mangadex
+#[derive(Display, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Timezone {
+    #[default]
+    Local,
+    Utc,
+}
+
+/// A source of mangas, every source uses the api of mangadex so the other ones are mirrors or
+/// proxies of it. `headers` are sent with every request to the source, for the ones which need a
+/// key, and are never written to the request logs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SourceProfile {
+    pub name: String,
+    pub api_url: String,
+    pub covers_url: String,
+    pub headers: HashMap<String, String>,
+    /// Used instead of `language` while the profile is the active one
+    pub language: Option<String>,
+    /// Most requests sent to the source each second, the rate limit the source reports is followed
+    /// either way
+    pub rate_limit: Option<u32>,
+    /// Used instead of `max_parallel_requests` for the requests made to the source
+    pub max_parallel_requests: Option<usize>,
+}
+
+impl Default for SourceProfile {
+    fn default() -> Self {
+        Self {
+            name: "mangadex".to_string(),
+            api_url: "https://api.mangadex.org".to_string(),
+            covers_url: "https://uploads.mangadex.org/covers".to_string(),
+            headers: HashMap::new(),
+            language: None,
+            rate_limit: None,
+            max_parallel_requests: None,
+        }
+    }
+}
+
+/// `hint` only tells nothing was typed, `tags` browses the mangas with the tags selected on the
+/// filters (or shows the hint if there are none) and `home` goes to the home page
+#[derive(Display, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum EmptySearch {
+    #[default]
+    Hint,
+    Tags,
+    Home,
+}
+
+/// `uniform` crops every cover to the same shape so the cells of a row of covers look the same,
+/// `natural` keeps the whole cover and its own shape
+#[derive(Display, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum CoverAspect {
+    #[default]
+    Uniform,
+    Natural,
+}
+
+/// How the covers of the mangas are framed, the cover shrinks to fit inside the frame
+#[derive(Display, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum CoverFrame {
+    /// The same border as the rest of the app
+    #[default]
+    Border,
+    Rounded,
+    /// A dark shade below and on the right of the cover
+    Shadow,
+    None,
+}
+
+/// `auto` uses the best image protocol the terminal supports and shows no images if there is none,
+/// `blocks` draws the images with colored unicode blocks which works on any terminal with colors
+#[derive(Display, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ImageProtocol {
+    #[default]
+    Auto,
+    Blocks,
+}
+
+/// Colors can be written by their name (`red`, `light-blue`), their hex value (`#ff8800`) or
+/// their ANSI index (`208`), an invalid color uses the terminal's default one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TagColors {
+    pub genre: String,
+    pub theme: String,
+    pub format: String,
+    pub content: String,
+    /// Used for tags without a group
+    pub default: String,
+}
+
+impl Default for TagColors {
+    fn default() -> Self {
+        Self {
+            genre: "cyan".to_string(),
+            theme: "green".to_string(),
+            format: "magenta".to_string(),
+            content: "red".to_string(),
+            default: "reset".to_string(),
+        }
+    }
+}
+
+impl TagColors {
+    pub fn color_for_group(&self, group: &str) -> Color {
+        let color = match group {
+            "genre" => &self.genre,
+            "theme" => &self.theme,
+            "format" => &self.format,
+            "content" => &self.content,
+            _ => &self.default,
+        };
+
+        Color::from_str(color).unwrap_or_default()
+    }
+}
+
+/// The options can be combined, by default only the text and the border of the selected item take
+/// `color`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SelectionStyle {
+    /// Written like the colors of `TagColors`
+    pub color: String,
+    /// Fill the selected item with `color` and write its text in black
+    pub background: bool,
+    pub bold: bool,
+    /// Draw a bar of `color` on the left edge of the selected item
+    pub marker: bool,
+}
+
+impl Default for SelectionStyle {
+    fn default() -> Self {
+        Self {
+            color: "yellow".to_string(),
+            background: false,
+            bold: false,
+            marker: false,
+        }
+    }
+}
+
+impl SelectionStyle {
+    pub fn color(&self) -> Color {
+        Color::from_str(&self.color).unwrap_or_default()
+    }
+
+    pub fn style(&self) -> Style {
+        let style = if self.background {
+            Style::default().fg(Color::Black).bg(self.color())
+        } else {
+            Style::default().fg(self.color())
+        };
+
+        if self.bold {
+            style.add_modifier(Modifier::BOLD)
+        } else {
+            style
+        }
+    }
+}
+
+impl Default for MangaTuiConfig {
+    fn default() -> Self {
+        Self {
+            preview_description_percentage: 60,
+            reader_cache_size_mb: 200,
+            chapter_order: ChapterOrder::Ascending,
+            feed_chapter_order: ChapterOrder::Descending,
+            search_suggestions: true,
+            confirm_quit: false,
+            tag_colors: TagColors::default(),
+            search_item_height: 4,
+            offline: false,
+            search_retries: 3,
+            select_action: SelectAction::default(),
+            max_search_length: 500,
+            image_protocol: ImageProtocol::default(),
+            color_depth: None,
+            max_loaded_pages: 10,
+            parallel_page_downloads: 3,
+            max_parallel_requests: 6,
+            search_item_title: "{title}".to_string(),
+            search_item_separator: false,
+            search_item_tags: true,
+            end_of_chapter: EndOfChapter::default(),
+            log_requests: false,
+            content_rating: vec![ContentRating::Safe, ContentRating::Suggestive],
+            language: None,
+            history_max_mangas: None,
+            history_max_age_days: None,
+            history_keep_completed: true,
+            prefetch_covers: true,
+            prioritize_selected_cover: true,
+            collapse_search_bar: false,
+            feed_layout: FeedLayout::default(),
+            min_cover_size: 16,
+            share_template: "{title} ({status})\n{description}\nTags: {tags}\n{url}".to_string(),
+            title_languages: vec![],
+            date_format: None,
+            timezone: Timezone::default(),
+            selection_style: SelectionStyle::default(),
+            confirm_explicit_search: false,
+            restore_session: false,
+            tick_rate_ms: 250,
+            results_grouping: ResultsGrouping::default(),
+            key_remap: HashMap::new(),
+            splash_screen: true,
+            cover_frame: CoverFrame::default(),
+            profiles: vec![SourceProfile::default()],
+            active_profile: SourceProfile::default().name,
+            auto_page_turn_ms: 5000,
+            carousel_cover_aspect: CoverAspect::default(),
+            empty_search: EmptySearch::default(),
+            description_language: None,
+            preferred_groups: vec![],
+            manga_preferred_groups: HashMap::new(),
+            auto_plan_to_read: false,
+            recently_added_content_rating: vec![
+                ContentRating::Safe,
+                ContentRating::Suggestive,
+                ContentRating::Erotic,
+            ],
+            enter_on_results: EnterOnResults::default(),
+        }
+    }
+}
+
+impl MangaTuiConfig {
+    /// `None` when there is no data directory
+    pub fn config_file_path() -> Option<PathBuf> {
+        Some(
+            APP_DATA_DIR
+                .as_ref()?
+                .join(AppDirectories::Config.to_string())
+                .join(CONFIG_FILE),
+        )
+    }
+
+    /// The config file is written the first time the app is started, so it's missing only on the
+    /// first run
+    pub fn file_exists() -> bool {
+        Self::config_file_path().is_some_and(|config_file| exists!(&config_file))
+    }
+
+    pub fn history_retention(&self) -> HistoryRetention {
+        HistoryRetention {
+            max_mangas: self.history_max_mangas,
+            max_age_days: self.history_max_age_days,
+            keep_completed: self.history_keep_completed,
+        }
+    }
+
+    pub fn preferred_groups_of(&self, manga_id: &str) -> &[String] {
+        self.manga_preferred_groups
+            .get(manga_id)
+            .unwrap_or(&self.preferred_groups)
+    }
+
+    /// The built-in mangadex profile is used if `active_profile` names none of the profiles
+    pub fn active_profile(&self) -> SourceProfile {
+        self.profiles
+            .iter()
+            .find(|profile| profile.name == self.active_profile)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Makes the profile after the active one active and returns its name
+    pub fn switch_profile() -> String {
+        Self::update(|config| {
+            let next = config
+                .profiles
+                .iter()
+                .position(|profile| profile.name == config.active_profile)
+                .map_or(0, |index| index + 1);
+
+            if let Some(profile) = config.profiles.get(next).or(config.profiles.first()) {
+                config.active_profile = profile.name.clone();
+            }
+        });
+
+        Self::get().active_profile().name
+    }
+
+    pub fn get() -> RwLockReadGuard<'static, MangaTuiConfig> {
+        CONFIG.read().unwrap()
+    }
+
+    /// Read the config file and make it the current config, if the file does not exist the
+    /// default values are used
+    pub fn load() -> Result<(), Box<dyn std::error::Error>> {
+        let Some(config_file) = Self::config_file_path() else {
+            return Ok(());
+        };
+
+        if !exists!(&config_file) {
+            return Ok(());
+        }
+
+        let contents = fs::read_to_string(config_file)?;
+        let config: MangaTuiConfig = serde_json::from_str(&contents)?;
+
+        *CONFIG.write().unwrap() = config;
+
+        Ok(())
+    }
+
+    /// Change the current config and persist it, so the change is kept the next time the app is
+    /// started
+    pub fn update(change: impl FnOnce(&mut MangaTuiConfig)) {
+        let mut config = CONFIG.write().unwrap();
+
+        change(&mut config);
+
+        if let Err(e) = config.save() {
+            write_to_error_log(ErrorType::FromError(e));
+        }
+    }
+
+    fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(config_file) = Self::config_file_path().filter(|_| !is_persistence_disabled())
+        else {
+            return Ok(());
+        };
+
+        let contents = serde_json::to_string_pretty(self)?;
+        let mut config_file = File::create(config_file)?;
+        config_file.write_all(contents.as_bytes())?;
+        Ok(())
+    }
+}
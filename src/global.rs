@@ -3,8 +3,27 @@ use ratatui::style::{Style, Stylize};
 
 use crate::backend::filter::Languages;
 
+/// Set when the app is started with `lang --set`, it's used instead of the language of the config
+/// and of the profiles for this run
 pub static PREFERRED_LANGUAGE: OnceCell<Languages> = OnceCell::new();
 
+/// Set when the app is started with `--offline` or with `offline` enabled on the config file, no
+/// request is made to mangadex while it's `true`
+pub static OFFLINE: OnceCell<bool> = OnceCell::new();
+
+pub fn is_offline() -> bool {
+    OFFLINE.get().copied().unwrap_or(false)
+}
+
+/// Set when the data directory can't be created or written to, the database is kept in memory
+/// so the history and the other lists only last until the app is closed, and neither the config
+/// nor the error logs are written
+pub static PERSISTENCE_DISABLED: OnceCell<bool> = OnceCell::new();
+
+pub fn is_persistence_disabled() -> bool {
+    PERSISTENCE_DISABLED.get().copied().unwrap_or(false)
+}
+
 pub static INSTRUCTIONS_STYLE: Lazy<Style> =
     Lazy::new(|| Style::default().bold().underlined().yellow());
 
@@ -1,86 +1,295 @@
 #![forbid(unsafe_code)]
 use clap::Parser;
+use image::DynamicImage;
 use once_cell::sync::Lazy;
 use ratatui::backend::CrosstermBackend;
 use ratatui_image::picker::{Picker, ProtocolType};
+use ratatui_image::protocol::StatefulProtocol;
 use reqwest::{Client, StatusCode};
-use self::backend::error_log::init_error_hooks;
+use self::backend::blocklist::Blocklist;
+use self::backend::cache::{clear_cache, clear_external_viewer_dir};
+use self::backend::color_depth::{reduce_colors, ColorDepth, COLOR_DEPTH};
+use self::backend::error_log::{init_error_hooks, write_to_error_log, ErrorType};
+use self::backend::export::export_reading_lists;
 use self::backend::fetch::{MangadexClient, MANGADEX_CLIENT_INSTANCE};
 use self::backend::filter::Languages;
-use self::backend::tui::{init, restore, run_app};
-use self::backend::{build_data_dir, APP_DATA_DIR};
+use self::backend::library::refresh_library_index;
+use self::backend::tui::{init, restore, run_app, run_onboarding, Splash};
+use self::backend::{build_data_dir, is_data_dir_writable, APP_DATA_DIR};
 use self::cli::CliArgs;
-use self::global::PREFERRED_LANGUAGE;
+use self::config::{ImageProtocol, MangaTuiConfig};
+use self::global::{
+    is_offline, is_persistence_disabled, OFFLINE, PERSISTENCE_DISABLED, PREFERRED_LANGUAGE,
+};
+use self::view::pages::reader::MangaReader;
+use std::sync::RwLock;
 
 mod backend;
 mod cli;
 mod common;
+mod config;
 mod global;
 mod utils;
 mod view;
 
 #[cfg(unix)]
-pub static PICKER: Lazy<Option<Picker>> = Lazy::new(|| {
-    Picker::from_termios()
-        .ok()
-        .map(|mut picker| {
-            picker.guess_protocol();
-            picker
-        })
-        .filter(|picker| picker.protocol_type != ProtocolType::Halfblocks)
-});
+fn detect_picker() -> Option<Picker> {
+    Picker::from_termios().ok().map(|mut picker| {
+        picker.guess_protocol();
+        picker
+    })
+}
 
 #[cfg(target_os = "windows")]
-pub static PICKER: Lazy<Option<Picker>> = Lazy::new(|| {
+fn detect_picker() -> Option<Picker> {
     // Todo! figure out how to get the size of the terminal on windows
     // I think with the winapi it is possible
     let mut picker = Picker::new((10, 17));
 
-    let protocol = picker.guess_protocol();
+    picker.guess_protocol();
 
-    if protocol == ProtocolType::Halfblocks {
-        return None;
-    }
     Some(picker)
+}
+
+fn use_block_art() -> bool {
+    MangaTuiConfig::get().image_protocol == ImageProtocol::Blocks
+}
+
+/// Block art can be dithered to the colors of the terminal, so it's picked on its own on terminals
+/// without truecolor support
+fn is_low_color_terminal() -> bool {
+    *COLOR_DEPTH < ColorDepth::TrueColor
+}
+
+/// The picker used to build the images, it's `None` if the terminal has no image support. It can be
+/// changed at runtime with `cycle_image_protocol`. Halfblocks are only used when block art is
+/// enabled on the config, or when no graphics protocol was detected on a terminal without
+/// truecolor support, since they need no support from the terminal a picker is always built in
+/// that case
+pub static PICKER: Lazy<RwLock<Option<Picker>>> = Lazy::new(|| {
+    let detected =
+        detect_picker().filter(|picker| picker.protocol_type != ProtocolType::Halfblocks);
+
+    let picker = if use_block_art() || (detected.is_none() && is_low_color_terminal()) {
+        let mut picker = detected.unwrap_or_else(|| Picker::new((10, 20)));
+        picker.protocol_type = ProtocolType::Halfblocks;
+        Some(picker)
+    } else {
+        detected
+    };
+
+    RwLock::new(picker)
 });
 
+pub fn picker() -> Option<Picker> {
+    *PICKER.read().unwrap()
+}
+
+/// Build the protocol used to display an image, with block art the colors are reduced to the ones
+/// the terminal supports. Must only be called if there is a picker
+pub fn new_image_protocol(image: DynamicImage) -> Box<dyn StatefulProtocol> {
+    let mut picker = picker().unwrap();
+
+    let image = if picker.protocol_type == ProtocolType::Halfblocks {
+        reduce_colors(image, *COLOR_DEPTH)
+    } else {
+        image
+    };
+
+    picker.new_resize_protocol(image)
+}
+
+/// Printed by the `diagnostics` command, so users know why images look the way they do
+fn print_diagnostics() {
+    let config = MangaTuiConfig::get();
+
+    let protocol = match picker() {
+        Some(picker) => format!("{:?}", picker.protocol_type),
+        None => "none, images are not displayed".to_string(),
+    };
+
+    let color_depth_source = if config.color_depth.is_some() {
+        "set on the config file"
+    } else {
+        "detected"
+    };
+
+    println!("Image protocol setting: {}", config.image_protocol);
+    println!("Image protocol: {protocol}");
+    println!("Color depth: {} ({color_depth_source})", *COLOR_DEPTH);
+
+    if is_low_color_terminal() {
+        println!(
+            "Colors are reduced to {}, block art is used when no graphics protocol is detected",
+            *COLOR_DEPTH
+        );
+    }
+}
+
+/// Switch to the next image protocol so users can find the one that works with their terminal,
+/// halfblocks is skipped unless block art is enabled or the terminal has no truecolor support. If
+/// no protocol was detected when the app started the detection is ran again. Returns the protocol
+/// which is now in use
+pub fn cycle_image_protocol() -> Option<ProtocolType> {
+    let mut current_picker = PICKER.write().unwrap();
+    let use_block_art = use_block_art() || is_low_color_terminal();
+
+    match current_picker.as_mut() {
+        Some(picker) => {
+            while picker.cycle_protocols() == ProtocolType::Halfblocks && !use_block_art {}
+        }
+        None => {
+            *current_picker = detect_picker().map(|mut picker| {
+                if picker.protocol_type == ProtocolType::Halfblocks {
+                    picker.cycle_protocols();
+                }
+                picker
+            });
+        }
+    }
+
+    current_picker.map(|picker| picker.protocol_type)
+}
+
 #[tokio::main(flavor = "multi_thread", worker_threads = 7)]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli_args = CliArgs::parse();
 
     if cli_args.data_dir {
-        let app_dir = APP_DATA_DIR.as_ref().unwrap();
-        println!("{}", app_dir.to_str().unwrap());
+        match APP_DATA_DIR.as_ref() {
+            Some(app_dir) => println!("{}", app_dir.display()),
+            None => println!("The data directory could not be found"),
+        }
         return Ok(());
     }
 
-    match cli_args.command {
-        Some(command) => match command {
+    let mut show_diagnostics = false;
+    let mut cli_language = None;
+    let mut local_folder = None;
+    let mut export = None;
+
+    if let Some(command) = cli_args.command {
+        match command {
             cli::Commands::Lang { print, set } => {
                 if print {
                     CliArgs::print_available_languages();
                     return Ok(());
                 }
 
-                match set {
-                    Some(lang) => {
-                        let try_lang = Languages::try_from_iso_code(lang.as_str());
-
-                        if try_lang.is_none() {
-                            println!("`{}` is not a valid ISO language code, run `{} lang --print` to list available languages and their ISO codes", lang, env!("CARGO_BIN_NAME"));
+                if let Some(lang) = set {
+                    let try_lang = Languages::try_from_iso_code(lang.as_str());
 
-                            return Ok(());
-                        }
+                    if try_lang.is_none() {
+                        println!("`{}` is not a valid ISO language code, run `{} lang --print` to list available languages and their ISO codes", lang, env!("CARGO_BIN_NAME"));
 
-                        PREFERRED_LANGUAGE.set(try_lang.unwrap()).unwrap()
+                        return Ok(());
                     }
-                    None => PREFERRED_LANGUAGE.set(Languages::default()).unwrap(),
+
+                    cli_language = try_lang;
+                }
+            }
+            cli::Commands::ClearCache => {
+                match clear_cache() {
+                    Ok(()) => println!("The cache was cleared"),
+                    Err(e) => println!("The cache could not be cleared, details: {e}"),
                 }
+                return Ok(());
             }
-        },
-        None => PREFERRED_LANGUAGE.set(Languages::default()).unwrap(),
+            // The diagnostics need the config, so they are printed once it's loaded
+            cli::Commands::Diagnostics => show_diagnostics = true,
+            cli::Commands::Read { folder } => {
+                let folder = folder.canonicalize().unwrap_or(folder);
+                if MangaReader::page_files(&folder).is_empty() {
+                    println!("`{}` has no images to read", folder.display());
+                    return Ok(());
+                }
+                local_folder = Some(folder);
+            }
+            // The lists are read from the database, which is on the data directory
+            cli::Commands::Export {
+                format,
+                lists,
+                output,
+            } => export = Some((format, lists, output)),
+        }
+    }
+
+    // Without the data directory the app can still be used to search and read, but everything it
+    // saves is lost when it's closed
+    let data_dir = build_data_dir();
+    let is_data_dir_usable = data_dir.is_ok() && is_data_dir_writable();
+
+    // The lists are exported from the database of the data directory, so there is nothing to
+    // export without it
+    if let (Err(e), Some(_)) = (&data_dir, &export) {
+        eprint!(
+            "Data directory could not be created, this is where your manga history and manga downloads is stored
+             \n this could be for many reasons such as the application not having enough permissions
+            \n Try setting the environment variable `MANGA_TUI_DATA_DIR` to some path pointing to a directory, example: /home/user/somedirectory 
+            \n Error details : {e}"
+        );
+        return Ok(());
+    }
+
+    if let Some((format, lists, output)) = export {
+        match export_reading_lists(&lists, format) {
+            Ok(exported) => match output {
+                Some(path) => match std::fs::write(&path, exported) {
+                    Ok(()) => println!("The lists were exported to {}", path.display()),
+                    Err(e) => println!("The lists could not be written, details: {e}"),
+                },
+                None => print!("{exported}"),
+            },
+            Err(e) => println!("The lists could not be exported, details: {e}"),
+        }
+        return Ok(());
+    }
+
+    PERSISTENCE_DISABLED.set(!is_data_dir_usable).unwrap();
+
+    let is_first_run = !MangaTuiConfig::file_exists();
+
+    if let Err(e) = MangaTuiConfig::load() {
+        write_to_error_log(ErrorType::FromError(e));
+    }
+
+    if show_diagnostics {
+        print_diagnostics();
+        return Ok(());
+    }
+
+    init_error_hooks()?;
+
+    // The choices of the onboarding could not be saved, so it would be shown every time
+    if is_first_run && !is_persistence_disabled() {
+        let detected_protocol = detect_picker()
+            .map(|picker| picker.protocol_type)
+            .filter(|protocol| *protocol != ProtocolType::Halfblocks);
+
+        init()?;
+        let onboarding = run_onboarding(
+            CrosstermBackend::new(std::io::stdout()),
+            detected_protocol,
+            cli_language.unwrap_or_default(),
+        )
+        .await;
+        restore()?;
+
+        if !onboarding? {
+            return Ok(());
+        }
+    }
+
+    // The language of `lang --set` is only used for this run, the one on the config is kept.
+    // Without it the language of the active profile or of the config is read on each request
+    if let Some(language) = cli_language {
+        PREFERRED_LANGUAGE.set(language).unwrap();
     }
 
+    OFFLINE
+        .set(cli_args.offline || local_folder.is_some() || MangaTuiConfig::get().offline)
+        .unwrap();
+
     let user_agent = format!(
         "manga-tui/0.1.0 ({}/{}/{})",
         std::env::consts::FAMILY,
@@ -91,41 +300,52 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mangadex_client =
         MangadexClient::new(Client::builder().user_agent(user_agent).build().unwrap());
 
-    println!("Checking mangadex status...");
+    let mut splash = Splash::start(MangaTuiConfig::get().splash_screen)?;
+
+    if !is_offline() {
+        if splash.is_shown() {
+            splash.show("Checking mangadex status")?;
+        } else {
+            println!("Checking mangadex status...");
+        }
 
-    let mangadex_status = mangadex_client.check_status().await;
+        let mangadex_status = mangadex_client.check_status().await;
 
-    match mangadex_status {
-        Ok(status) => {
-            if status != StatusCode::OK {
+        match mangadex_status {
+            Ok(status) => {
+                if status != StatusCode::OK {
+                    splash.close()?;
+                    println!("Mangadex appears to be in maintenance, please come backe later");
+                    return Ok(());
+                }
+            }
+            Err(_) => {
+                splash.close()?;
                 println!("Mangadex appears to be in maintenance, please come backe later");
                 return Ok(());
             }
         }
-        Err(_) => {
-            println!("Mangadex appears to be in maintenance, please come backe later");
-            return Ok(());
-        }
     }
 
     MANGADEX_CLIENT_INSTANCE.set(mangadex_client).unwrap();
 
-    match build_data_dir() {
-        Ok(_) => {}
-        Err(e) => {
-            eprint!(
-            "Data directory could not be created, this is where your manga history and manga downloads is stored
-             \n this could be for many reasons such as the application not having enough permissions
-            \n Try setting the environment variable `MANGA_TUI_DATA_DIR` to some path pointing to a directory, example: /home/user/somedirectory 
-            \n Error details : {e}"
-            );
-            return Ok(());
-        }
+    splash.show("Detecting the image support of the terminal")?;
+    Lazy::force(&PICKER);
+
+    splash.show("Loading the library")?;
+    refresh_library_index();
+
+    if let Err(e) = Blocklist::load() {
+        write_to_error_log(ErrorType::FromError(Box::new(e)));
     }
 
-    init_error_hooks()?;
-    init()?;
-    run_app(CrosstermBackend::new(std::io::stdout())).await?;
+    splash.finish()?;
+    run_app(CrosstermBackend::new(std::io::stdout()), local_folder).await?;
     restore()?;
+
+    if let Err(e) = clear_external_viewer_dir() {
+        write_to_error_log(ErrorType::FromError(Box::new(e)));
+    }
+
     Ok(())
 }
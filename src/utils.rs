@@ -1,16 +1,26 @@
-use crate::backend::error_log::write_to_error_log;
+use crate::backend::error_log::{catch_panic, write_to_error_log};
 use crate::backend::fetch::MangadexClient;
 use crate::backend::filter::Languages;
-use crate::backend::Data;
-use crate::common::{Artist, Author, Manga};
+use crate::backend::{Attributes, Data, Description};
+use crate::common::{Artist, Author, Manga, MangaLink, MangaTag};
+use crate::config::{CoverAspect, CoverFrame, MangaTuiConfig, Timezone};
+use crate::new_image_protocol;
 use crate::view::widgets::ImageHandler;
-use crate::PICKER;
+use bytes::Bytes;
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use crossterm::event::{KeyCode, KeyEvent};
 use image::io::Reader;
+use image::DynamicImage;
 use ratatui::{prelude::*, widgets::*};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Write;
 use std::io::Cursor;
+use std::ops::Range;
 use tokio::sync::mpsc::UnboundedSender;
-use tokio::task::JoinSet;
-use tui_input::Input;
+use tokio::task::{AbortHandle, JoinSet};
+use tui_input::{Input, InputRequest};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 pub fn set_tags_style(tag: &str) -> Span<'_> {
     match tag.to_lowercase().as_str() {
@@ -23,6 +33,94 @@ pub fn set_tags_style(tag: &str) -> Span<'_> {
     }
 }
 
+/// Tags are colored by their group, the colors are set on the config
+pub fn set_tag_group_style(tag: &MangaTag) -> Span<'_> {
+    let color = MangaTuiConfig::get().tag_colors.color_for_group(&tag.group);
+    format!("{}  ", tag.name).fg(color)
+}
+
+/// Columns a tag takes on the line of tags, its name is followed by two spaces like in
+/// `set_tag_group_style`
+fn tag_width(tag: &MangaTag) -> usize {
+    tag.name.width() + 2
+}
+
+/// When the tags don't fit the groups are kept in this order
+fn tag_group_priority(group: &str) -> u8 {
+    match group {
+        "genre" => 0,
+        "theme" => 1,
+        "content" => 2,
+        "format" => 3,
+        _ => 4,
+    }
+}
+
+/// How many lines the items take when they are put one after the other and wrapped to `width`,
+/// an item is never split between two lines
+fn wrapped_items_lines(widths: impl Iterator<Item = usize>, width: usize) -> usize {
+    let mut lines = 0;
+    let mut current_width = width;
+
+    for item_width in widths {
+        if current_width + item_width > width {
+            lines += 1;
+            current_width = item_width;
+        } else {
+            current_width += item_width;
+        }
+    }
+
+    lines
+}
+
+/// Shown after the tags which fit, `<T>` shows all of them
+pub fn more_tags_label(hidden: usize) -> String {
+    format!("+{hidden} more <T>")
+}
+
+/// The tags which fit in `lines` lines of `width` columns, in their original order, and how many
+/// were left out. When they don't all fit the genres are kept before the themes, the content
+/// warnings and the formats, leaving room for the `+N more` label
+pub fn fit_tags(tags: &[MangaTag], width: usize, lines: usize) -> (Vec<&MangaTag>, usize) {
+    let fits = |chosen: &[usize]| {
+        let hidden = tags.len() - chosen.len();
+        let label_width = (hidden > 0).then(|| more_tags_label(hidden).width());
+        let widths = chosen
+            .iter()
+            .map(|index| tag_width(&tags[*index]))
+            .chain(label_width);
+        wrapped_items_lines(widths, width) <= lines
+    };
+
+    let all_tags: Vec<usize> = (0..tags.len()).collect();
+
+    if fits(&all_tags) {
+        return (tags.iter().collect(), 0);
+    }
+
+    let mut by_priority = all_tags;
+    by_priority.sort_by_key(|index| tag_group_priority(&tags[*index].group));
+
+    let mut chosen: Vec<usize> = vec![];
+
+    for index in by_priority {
+        let mut candidate = chosen.clone();
+        candidate.push(index);
+        candidate.sort();
+        if fits(&candidate) {
+            chosen = candidate;
+        }
+    }
+
+    let hidden = tags.len() - chosen.len();
+
+    (
+        chosen.into_iter().map(|index| &tags[index]).collect(),
+        hidden,
+    )
+}
+
 pub fn set_status_style(status: &str) -> Span<'_> {
     match status.to_lowercase().as_str() {
         "completed" => format!(" 🔵 {status} ").into(),
@@ -33,70 +131,161 @@ pub fn set_status_style(status: &str) -> Span<'_> {
     }
 }
 
+/// Guess the format of the image and decode it, `None` if it can't be decoded or the decoder
+/// panics, in which case the panic is written to the error log
+pub fn decode_image(bytes: Bytes) -> Option<DynamicImage> {
+    catch_panic(move || {
+        Reader::new(Cursor::new(bytes))
+            .with_guessed_format()
+            .ok()?
+            .decode()
+            .ok()
+    })
+    .flatten()
+}
+
+/// Same as `decode_image`, but the covers smaller than `min_cover_size` count as not found
+pub fn decode_cover(bytes: Bytes) -> Option<DynamicImage> {
+    let min_size = MangaTuiConfig::get().min_cover_size;
+    decode_image(bytes).filter(|image| image.width() >= min_size && image.height() >= min_size)
+}
+
+/// Width and height of the shape covers are cropped to with `CoverAspect::Uniform`, most covers
+/// are close to it
+const COVER_ASPECT: (u32, u32) = (7, 10);
+
+/// Crops the sides or the bottom of the cover so it has the shape of `COVER_ASPECT`, the sides
+/// are cropped evenly so the middle of the cover is kept
+pub fn crop_to_cover_aspect(image: DynamicImage) -> DynamicImage {
+    let (width, height) = (image.width(), image.height());
+    let (aspect_width, aspect_height) = COVER_ASPECT;
+
+    let uniform_width = height * aspect_width / aspect_height;
+    if uniform_width < width {
+        image.crop_imm((width - uniform_width) / 2, 0, uniform_width, height)
+    } else {
+        let uniform_height = width * aspect_height / aspect_width;
+        image.crop_imm(0, 0, width, uniform_height.min(height))
+    }
+}
+
 pub fn search_manga_cover<IM: ImageHandler>(
     file_name: String,
     manga_id: String,
+    aspect: CoverAspect,
     join_set: &mut JoinSet<()>,
     tx: UnboundedSender<IM>,
-) {
+) -> AbortHandle {
     join_set.spawn(async move {
         let response = MangadexClient::global()
             .get_cover_for_manga_lower_quality(&manga_id, &file_name)
             .await;
 
         match response {
-            Ok(bytes) => {
-                let dyn_img = Reader::new(Cursor::new(bytes))
-                    .with_guessed_format()
-                    .unwrap();
-
-                let maybe_decoded = dyn_img.decode();
-
-                if let Ok(decoded) = maybe_decoded {
-                    let protocol = PICKER.unwrap().new_resize_protocol(decoded);
+            Ok(bytes) => match decode_cover(bytes) {
+                Some(decoded) => {
+                    let decoded = match aspect {
+                        CoverAspect::Uniform => crop_to_cover_aspect(decoded),
+                        CoverAspect::Natural => decoded,
+                    };
+                    let protocol = new_image_protocol(decoded);
                     tx.send(IM::load(protocol, manga_id)).ok();
                 }
-            }
+                None => {
+                    tx.send(IM::not_found(manga_id)).ok();
+                }
+            },
             Err(e) => {
                 write_to_error_log(crate::backend::error_log::ErrorType::FromError(Box::new(e)));
                 tx.send(IM::not_found(manga_id)).ok();
             }
         }
-    });
+    })
+}
+
+/// The title in the first of `languages` the manga has one in, either its main title or one of
+/// its alternative titles
+fn preferred_title(attributes: &Attributes, languages: &[String]) -> Option<String> {
+    languages
+        .iter()
+        .find_map(|language| {
+            attributes.title.in_language(language).or_else(|| {
+                attributes
+                    .alt_titles
+                    .iter()
+                    .find_map(|alt_title| alt_title.get(language))
+            })
+        })
+        .cloned()
+}
+
+/// The description in `language`, otherwise the english one or the first one the manga has, the
+/// languages are sorted so it's always the same one
+pub fn preferred_description<'a>(
+    description: &'a Description,
+    language: Option<&str>,
+) -> Option<&'a String> {
+    language
+        .and_then(|language| description.in_language(language))
+        .or_else(|| description.in_language("en"))
+        .or_else(|| {
+            description
+                .0
+                .iter()
+                .filter(|(_, description)| !description.is_empty())
+                .min_by_key(|(language, _)| language.as_str())
+                .map(|(_, description)| description)
+        })
 }
 
 pub fn from_manga_response(value: Data) -> Manga {
     let id = value.id;
 
+    let preferred_title =
+        preferred_title(&value.attributes, &MangaTuiConfig::get().title_languages);
+
     // Todo! maybe there is a better way to do this
-    let title = value.attributes.title.en.unwrap_or(
-        value.attributes.title.ja_ro.unwrap_or(
-            value.attributes.title.ja.unwrap_or(
-                value.attributes.title.jp.unwrap_or(
-                    value.attributes.title.zh.unwrap_or(
-                        value
-                            .attributes
-                            .title
-                            .ko
-                            .unwrap_or(value.attributes.title.ko_ro.unwrap_or_default()),
+    let title = preferred_title.unwrap_or_else(|| {
+        value.attributes.title.en.unwrap_or(
+            value.attributes.title.ja_ro.unwrap_or(
+                value.attributes.title.ja.unwrap_or(
+                    value.attributes.title.jp.unwrap_or(
+                        value.attributes.title.zh.unwrap_or(
+                            value
+                                .attributes
+                                .title
+                                .ko
+                                .unwrap_or(value.attributes.title.ko_ro.unwrap_or_default()),
+                        ),
                     ),
                 ),
             ),
-        ),
-    );
+        )
+    });
 
-    let description = match value.attributes.description {
-        Some(description) => description.en.unwrap_or("No description".to_string()),
-        None => String::from("No description"),
-    };
+    let description = value
+        .attributes
+        .description
+        .as_ref()
+        .and_then(|description| {
+            preferred_description(
+                description,
+                MangaTuiConfig::get().description_language.as_deref(),
+            )
+        })
+        .cloned()
+        .unwrap_or("No description".to_string());
 
     let content_rating = value.attributes.content_rating;
 
-    let tags: Vec<String> = value
+    let tags: Vec<MangaTag> = value
         .attributes
         .tags
         .iter()
-        .map(|tag| tag.attributes.name.en.to_string())
+        .map(|tag| MangaTag {
+            name: tag.attributes.name.en.to_string(),
+            group: tag.attributes.group.to_string(),
+        })
         .collect();
 
     let mut img_url: Option<String> = Option::default();
@@ -118,7 +307,7 @@ pub fn from_manga_response(value: Data) -> Manga {
                         name: attributes.name.as_ref().cloned().unwrap_or_default(),
                     }
                 }
-                "cover_art" => img_url = Some(attributes.file_name.as_ref().unwrap().to_string()),
+                "cover_art" => img_url = attributes.file_name.clone(),
                 _ => {}
             }
         }
@@ -138,6 +327,13 @@ pub fn from_manga_response(value: Data) -> Manga {
 
     let created_at = value.attributes.created_at;
 
+    let links = value
+        .attributes
+        .links
+        .as_ref()
+        .map(external_links)
+        .unwrap_or_default();
+
     Manga {
         id,
         title,
@@ -151,7 +347,220 @@ pub fn from_manga_response(value: Data) -> Manga {
         publication_demographic,
         available_languages: languages,
         created_at,
+        links,
+        year: value.attributes.year,
+        original_language: value.attributes.original_language,
+    }
+}
+
+/// Build the title line of a search result from `search_item_title`, the placeholders are
+/// `{title}`, `{year}`, `{status}`, `{rating}` and `{author}`. The ones the manga has no data for
+/// are left empty and the brackets of the template around them are removed, the ones of the
+/// title itself are kept
+pub fn format_search_item_title(template: &str, manga: &Manga) -> String {
+    let year = manga.year.map(|year| year.to_string()).unwrap_or_default();
+
+    let placeholders = [
+        ("{title}", manga.title.as_str()),
+        ("{year}", year.as_str()),
+        ("{status}", manga.status.as_str()),
+        ("{rating}", manga.content_rating.as_str()),
+        ("{author}", manga.author.name.as_str()),
+    ];
+
+    let template = placeholders
+        .iter()
+        .filter(|(_, value)| value.is_empty())
+        .fold(template.to_string(), |template, (placeholder, _)| {
+            template
+                .replace(&format!("({placeholder})"), "")
+                .replace(&format!("[{placeholder}]"), "")
+        });
+
+    let formatted = placeholders
+        .iter()
+        .fold(template, |template, (placeholder, value)| {
+            template.replace(placeholder, value)
+        });
+
+    formatted
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Longest description which is put on the text shared with `format_share_text`
+const SHARE_DESCRIPTION_LENGTH: usize = 300;
+
+/// The first paragraph of the description, cut at a word if it's longer than `max_chars`
+fn description_excerpt(description: &str, max_chars: usize) -> String {
+    let first_paragraph = description
+        .trim()
+        .split("\n\n")
+        .next()
+        .unwrap_or_default()
+        .trim();
+
+    if first_paragraph.chars().count() <= max_chars {
+        return first_paragraph.to_string();
     }
+
+    let cut: String = first_paragraph.chars().take(max_chars).collect();
+    let until_last_word = cut
+        .rsplit_once(' ')
+        .map_or(cut.as_str(), |(start, _)| start);
+
+    format!("{}…", until_last_word.trim_end())
+}
+
+/// The text copied with `<c>` on the search page, `share_template` on the config can use the
+/// placeholders of `format_search_item_title` along with `{description}`, `{tags}` and `{url}`
+pub fn format_share_text(template: &str, manga: &Manga) -> String {
+    let tags = manga
+        .tags
+        .iter()
+        .map(|tag| tag.name.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ");
+
+    template
+        .replace("{title}", &manga.title)
+        .replace(
+            "{year}",
+            &manga.year.map(|year| year.to_string()).unwrap_or_default(),
+        )
+        .replace("{status}", &manga.status)
+        .replace("{rating}", &manga.content_rating)
+        .replace("{author}", &manga.author.name)
+        .replace("{tags}", &tags)
+        .replace("{url}", &format!("https://mangadex.org/title/{}", manga.id))
+        .replace(
+            "{description}",
+            &description_excerpt(&manga.description, SHARE_DESCRIPTION_LENGTH),
+        )
+}
+
+/// Mangadex stores the id or the slug of the manga on most sites instead of the url, see:
+/// https://api.mangadex.org/docs/3-enumerations/#manga-links-data. Sites which are not known are
+/// skipped unless their value is already a url
+pub fn external_links(links: &HashMap<String, String>) -> Vec<MangaLink> {
+    const SITES: [(&str, &str); 12] = [
+        ("al", "AniList"),
+        ("mal", "MyAnimeList"),
+        ("mu", "MangaUpdates"),
+        ("ap", "Anime-Planet"),
+        ("kt", "Kitsu"),
+        ("nu", "NovelUpdates"),
+        ("bw", "BookWalker"),
+        ("amz", "Amazon"),
+        ("ebj", "eBookJapan"),
+        ("cdj", "CDJapan"),
+        ("raw", "Official raw"),
+        ("engtl", "Official english"),
+    ];
+
+    let url_for = |site: &str, value: &str| -> Option<String> {
+        if value.starts_with("http") {
+            return Some(value.to_string());
+        }
+        let url = match site {
+            "al" => format!("https://anilist.co/manga/{value}"),
+            "mal" => format!("https://myanimelist.net/manga/{value}"),
+            // Old mangaupdates ids are numbers, the new ones are base 36
+            "mu" if value.chars().all(|c| c.is_ascii_digit()) => {
+                format!("https://www.mangaupdates.com/series.html?id={value}")
+            }
+            "mu" => format!("https://www.mangaupdates.com/series/{value}"),
+            "ap" => format!("https://www.anime-planet.com/manga/{value}"),
+            "kt" => format!("https://kitsu.app/manga/{value}"),
+            "nu" => format!("https://www.novelupdates.com/series/{value}"),
+            "bw" => format!("https://bookwalker.jp/{value}"),
+            _ => return None,
+        };
+        Some(url)
+    };
+
+    let mut external_links: Vec<MangaLink> = SITES
+        .iter()
+        .filter_map(|(site, name)| {
+            let value = links.get(*site)?;
+            Some(MangaLink {
+                name: name.to_string(),
+                url: url_for(site, value)?,
+            })
+        })
+        .collect();
+
+    let mut unknown_sites: Vec<MangaLink> = links
+        .iter()
+        .filter(|(site, value)| {
+            !SITES.iter().any(|(known, _)| known == site) && value.starts_with("http")
+        })
+        .map(|(site, value)| MangaLink {
+            name: site.to_string(),
+            url: value.to_string(),
+        })
+        .collect();
+
+    unknown_sites.sort_by(|a, b| a.name.cmp(&b.name));
+    external_links.append(&mut unknown_sites);
+
+    external_links
+}
+
+/// Dates from mangadex are rfc3339, the ones saved on the database are written by sqlite's
+/// `datetime('now')` which is utc
+pub fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|date| date.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").map(|date| date.and_utc())
+        })
+        .ok()
+}
+
+/// Format the date with `format` in the given timezone, `None` if the format is not valid
+pub fn format_date(date: DateTime<Utc>, format: &str, timezone: Timezone) -> Option<String> {
+    let mut formatted = String::new();
+    let result = match timezone {
+        Timezone::Local => write!(formatted, "{}", date.with_timezone(&Local).format(format)),
+        Timezone::Utc => write!(formatted, "{}", date.format(format)),
+    };
+    result.ok().map(|_| formatted)
+}
+
+/// Show the date of a chapter with the `date_format` of the config, or as the time since `now`
+/// when there is none or it's not valid
+pub fn display_date(date: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let config = MangaTuiConfig::get();
+
+    if let Some(formatted) = config
+        .date_format
+        .as_deref()
+        .and_then(|format| format_date(date, format, config.timezone))
+    {
+        return formatted;
+    }
+
+    let days = match config.timezone {
+        Timezone::Local => {
+            now.with_timezone(&Local).date_naive() - date.with_timezone(&Local).date_naive()
+        }
+        Timezone::Utc => now.date_naive() - date.date_naive(),
+    };
+
+    display_dates_since_publication(days.num_days())
+}
+
+/// The publication date of a manga is always shown as a date, with `%Y-%m-%d` if there is no
+/// `date_format` on the config
+pub fn display_publication_date(date: &str) -> String {
+    let config = MangaTuiConfig::get();
+    let format = config.date_format.as_deref().unwrap_or("%Y-%m-%d");
+
+    parse_date(date)
+        .and_then(|date| format_date(date, format, config.timezone))
+        .unwrap_or(date.to_string())
 }
 
 pub fn display_dates_since_publication(day: i64) -> String {
@@ -160,12 +569,34 @@ pub fn display_dates_since_publication(day: i64) -> String {
     if day <= 31 {
         format!("{} days ago", day.abs())
     } else if month <= 12 {
-        return format!("{} months ago", month);
+        format!("{} months ago", month)
     } else {
-        return format!("{} years ago", year);
+        format!("{} years ago", year)
     }
 }
 
+/// Draws the frame set with `cover_frame` and returns the area left for the cover
+pub fn render_cover_frame(area: Rect, buf: &mut Buffer) -> Rect {
+    let block = match MangaTuiConfig::get().cover_frame {
+        CoverFrame::Border => Block::bordered(),
+        CoverFrame::Rounded => Block::bordered().border_type(BorderType::Rounded),
+        CoverFrame::Shadow => Block::new()
+            .borders(Borders::RIGHT | Borders::BOTTOM)
+            .border_set(symbols::border::Set {
+                vertical_right: "▌",
+                horizontal_bottom: "▀",
+                bottom_right: "▘",
+                ..symbols::border::PLAIN
+            })
+            .border_style(Style::default().fg(Color::DarkGray)),
+        CoverFrame::None => return area,
+    };
+
+    let inner = block.inner(area);
+    block.render(area, buf);
+    inner
+}
+
 pub fn centered_rect(r: Rect, percent_x: u16, percent_y: u16) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -208,11 +639,612 @@ pub fn render_search_bar(
 
     let scroll = input.visual_scroll(width as usize);
 
-    match is_typing {
-        true => frame.set_cursor(
+    if is_typing {
+        frame.set_cursor(
             area.x + ((input.visual_cursor()).max(scroll) - scroll) as u16 + 1,
             area.y + 1,
-        ),
-        false => {}
+        )
+    }
+}
+
+/// Insert pasted text at the cursor's position, new lines are turned into spaces since inputs are
+/// single line
+pub fn paste_into_input(input: &mut Input, text: &str) {
+    for character in text.chars() {
+        let character = if character == '\n' || character == '\r' {
+            ' '
+        } else {
+            character
+        };
+        input.handle(InputRequest::InsertChar(character));
+    }
+}
+
+/// Cut the input's value to `max_characters`, keeping the cursor where it was if it's still
+/// inside the value. Returns whether the value was cut
+pub fn truncate_input(input: &mut Input, max_characters: usize) -> bool {
+    if input.value().chars().count() <= max_characters {
+        return false;
+    }
+
+    let truncated: String = input.value().chars().take(max_characters).collect();
+    let cursor = input.cursor().min(max_characters);
+
+    *input = Input::new(truncated).with_cursor(cursor);
+
+    true
+}
+
+/// The pages the reader keeps loaded: at most `max_pages` around the selected one, less of them are
+/// kept before it when it's near the end of the chapter so the amount stays the same
+pub fn loaded_pages_window(selected: usize, total_pages: usize, max_pages: usize) -> Range<usize> {
+    let max_pages = max_pages.max(1);
+    let end = (selected.saturating_sub(max_pages / 2) + max_pages).min(total_pages);
+
+    end.saturating_sub(max_pages)..end
+}
+
+/// How close a title is to what was searched, ignoring case: `0` if it's the same, `1` if it
+/// starts with it, `2` if it contains it and `3` otherwise
+pub fn title_match_rank(title: &str, term: &str) -> u8 {
+    let title = title.trim().to_lowercase();
+    let term = term.trim().to_lowercase();
+
+    if term.is_empty() {
+        3
+    } else if title == term {
+        0
+    } else if title.starts_with(&term) {
+        1
+    } else if title.contains(&term) {
+        2
+    } else {
+        3
+    }
+}
+
+/// Where a list gets shorter the selection moves to its last item instead of pointing past the
+/// end, and nothing is selected when the list is empty
+pub fn clamp_selection(selected: Option<usize>, len: usize) -> Option<usize> {
+    match len {
+        0 => None,
+        _ => selected.map(|index| index.min(len - 1)),
+    }
+}
+
+/// The indexes of a list ordered by how far they are from the selected one, at the same distance
+/// the one below comes first since lists are usually scrolled down
+pub fn indexes_by_distance(selected: usize, len: usize) -> Vec<usize> {
+    let mut indexes: Vec<usize> = (0..len).collect();
+    indexes.sort_by_key(|index| (index.abs_diff(selected), *index < selected));
+    indexes
+}
+
+/// A looser version of a search term which found nothing, in case the last word has a typo: the
+/// last word is removed, or if there is only one the last character of it
+pub fn relaxed_search_term(search_term: &str) -> Option<String> {
+    let words: Vec<&str> = search_term.split_whitespace().collect();
+
+    match words.as_slice() {
+        [] => None,
+        [word] => {
+            let characters = word.chars().count();
+            (characters > 3).then(|| word.chars().take(characters - 1).collect())
+        }
+        [rest @ .., _] => Some(rest.join(" ")),
+    }
+}
+
+/// Cut the text so it takes at most `max_width` columns, ending it with `…` when it's cut. The
+/// width of each character is used instead of counting them since CJK characters and most emojis
+/// take two columns and combining characters take none
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let mut truncated = String::new();
+    let mut width = 0;
+
+    for character in text.chars() {
+        let character_width = character.width().unwrap_or(0);
+        // One column is left for the ellipsis
+        if width + character_width > max_width - 1 {
+            break;
+        }
+        width += character_width;
+        truncated.push(character);
+    }
+
+    truncated.push('…');
+    truncated
+}
+
+/// Compare file names the way people number them, the numbers in them are compared by their value
+/// so `page2.png` comes before `page10.png`
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek(), b_chars.peek()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a_char), Some(b_char)) if a_char.is_ascii_digit() && b_char.is_ascii_digit() => {
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars<'_>>| {
+                    let mut number = String::new();
+                    while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+                        number.push(digit);
+                    }
+                    number.trim_start_matches('0').to_string()
+                };
+
+                let a_number = take_number(&mut a_chars);
+                let b_number = take_number(&mut b_chars);
+
+                let ordering = a_number
+                    .len()
+                    .cmp(&b_number.len())
+                    .then_with(|| a_number.cmp(&b_number));
+
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(a_char), Some(b_char)) => {
+                let ordering = a_char.to_lowercase().cmp(b_char.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+/// Crossterm reports the character the keyboard layout produces, so on layouts other than qwerty
+/// (or with other alphabets) `key_remap` tells which key of the app each one stands for
+pub fn remap_key(mut key_event: KeyEvent, key_remap: &HashMap<char, char>) -> KeyEvent {
+    if let KeyCode::Char(character) = key_event.code {
+        if let Some(mapped) = key_remap.get(&character) {
+            key_event.code = KeyCode::Char(*mapped);
+        }
+    }
+    key_event
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::backend::{MangaSearchAttributes, MangaSearchRelationship, Title};
+
+    fn relationship(
+        id: &str,
+        type_field: &str,
+        attributes: MangaSearchAttributes,
+    ) -> MangaSearchRelationship {
+        MangaSearchRelationship {
+            id: id.to_string(),
+            type_field: type_field.to_string(),
+            attributes: Some(attributes),
+        }
+    }
+
+    #[test]
+    fn cover_art_is_chosen_over_other_relationships() {
+        let manga = Data {
+            id: "manga_id".to_string(),
+            relationships: vec![
+                relationship(
+                    "author_id",
+                    "author",
+                    MangaSearchAttributes {
+                        name: Some("some author".to_string()),
+                        file_name: Some("not_a_cover.jpg".to_string()),
+                        ..Default::default()
+                    },
+                ),
+                relationship(
+                    "artist_id",
+                    "artist",
+                    MangaSearchAttributes {
+                        name: Some("some artist".to_string()),
+                        ..Default::default()
+                    },
+                ),
+                relationship(
+                    "cover_id",
+                    "cover_art",
+                    MangaSearchAttributes {
+                        file_name: Some("cover.jpg".to_string()),
+                        ..Default::default()
+                    },
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let manga = from_manga_response(manga);
+
+        assert_eq!(Some("cover.jpg".to_string()), manga.img_url);
+        assert_eq!("some author", manga.author.name);
+        assert_eq!("some artist", manga.artist.name);
+    }
+
+    #[test]
+    fn title_is_the_first_preferred_language_available() {
+        let attributes = Attributes {
+            title: Title {
+                ja_ro: Some("Shingeki no Kyojin".to_string()),
+                ..Default::default()
+            },
+            alt_titles: vec![HashMap::from([(
+                "en".to_string(),
+                "Attack on Titan".to_string(),
+            )])],
+            ..Default::default()
+        };
+
+        let languages =
+            |codes: &[&str]| -> Vec<String> { codes.iter().map(|code| code.to_string()).collect() };
+
+        assert_eq!(
+            Some("Attack on Titan".to_string()),
+            preferred_title(&attributes, &languages(&["fr", "en", "ja-ro"]))
+        );
+        assert_eq!(
+            Some("Shingeki no Kyojin".to_string()),
+            preferred_title(&attributes, &languages(&["ja-ro", "en"]))
+        );
+        assert_eq!(None, preferred_title(&attributes, &languages(&["fr"])));
+    }
+
+    #[test]
+    fn description_falls_back_to_english_then_the_first_language() {
+        let description = Description(HashMap::from([
+            ("pt-br".to_string(), "Descrição".to_string()),
+            ("en".to_string(), "Description".to_string()),
+            ("es".to_string(), "Descripción".to_string()),
+        ]));
+
+        assert_eq!(
+            Some(&"Descripción".to_string()),
+            preferred_description(&description, Some("es"))
+        );
+        assert_eq!(
+            Some(&"Description".to_string()),
+            preferred_description(&description, Some("fr"))
+        );
+
+        let without_english = Description(HashMap::from([
+            ("pt-br".to_string(), "Descrição".to_string()),
+            ("es".to_string(), "Descripción".to_string()),
+        ]));
+
+        assert_eq!(
+            Some(&"Descripción".to_string()),
+            preferred_description(&without_english, None)
+        );
+        assert_eq!(None, preferred_description(&Description::default(), None));
+    }
+
+    #[test]
+    fn manga_without_cover_art_has_no_cover() {
+        let manga = Data {
+            id: "manga_id".to_string(),
+            relationships: vec![relationship(
+                "author_id",
+                "author",
+                MangaSearchAttributes {
+                    name: Some("some author".to_string()),
+                    ..Default::default()
+                },
+            )],
+            ..Default::default()
+        };
+
+        let manga = from_manga_response(manga);
+
+        assert!(manga.img_url.is_none());
+    }
+
+    #[test]
+    fn input_is_truncated_to_the_maximum_characters() {
+        let mut input = Input::new("naruto shippuden".to_string());
+
+        assert!(truncate_input(&mut input, 6));
+        assert_eq!("naruto", input.value());
+        assert_eq!(6, input.cursor());
+
+        let mut input = Input::new("ñandú".to_string()).with_cursor(1);
+
+        assert!(!truncate_input(&mut input, 5));
+        assert_eq!("ñandú", input.value());
+        assert_eq!(1, input.cursor());
+    }
+
+    #[test]
+    fn external_links_are_built_from_the_ids_of_each_site() {
+        let links = HashMap::from([
+            ("mal".to_string(), "2".to_string()),
+            ("al".to_string(), "30002".to_string()),
+            ("mu".to_string(), "3f8m8e".to_string()),
+            (
+                "engtl".to_string(),
+                "https://www.darkhorse.com/Books/Berserk".to_string(),
+            ),
+        ]);
+
+        let links = external_links(&links);
+
+        assert_eq!(
+            vec![
+                MangaLink {
+                    name: "AniList".to_string(),
+                    url: "https://anilist.co/manga/30002".to_string(),
+                },
+                MangaLink {
+                    name: "MyAnimeList".to_string(),
+                    url: "https://myanimelist.net/manga/2".to_string(),
+                },
+                MangaLink {
+                    name: "MangaUpdates".to_string(),
+                    url: "https://www.mangaupdates.com/series/3f8m8e".to_string(),
+                },
+                MangaLink {
+                    name: "Official english".to_string(),
+                    url: "https://www.darkhorse.com/Books/Berserk".to_string(),
+                },
+            ],
+            links
+        );
+    }
+
+    #[test]
+    fn unknown_sites_are_only_kept_when_they_are_urls() {
+        let links = HashMap::from([
+            ("xyz".to_string(), "12345".to_string()),
+            ("other".to_string(), "https://example.com/manga".to_string()),
+        ]);
+
+        let links = external_links(&links);
+
+        assert_eq!(
+            vec![MangaLink {
+                name: "other".to_string(),
+                url: "https://example.com/manga".to_string(),
+            }],
+            links
+        );
+    }
+
+    #[test]
+    fn search_item_title_is_formatted_with_the_template() {
+        let manga = Manga {
+            title: "Berserk".to_string(),
+            status: "ongoing".to_string(),
+            year: Some(1989),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            "[ongoing] Berserk (1989)",
+            format_search_item_title("[{status}] {title} ({year})", &manga)
+        );
+        assert_eq!("Berserk", format_search_item_title("{title}", &manga));
+
+        let without_year = Manga {
+            year: None,
+            ..manga
+        };
+
+        assert_eq!(
+            "Berserk",
+            format_search_item_title("{title} ({year})", &without_year)
+        );
+
+        let with_brackets_in_title = Manga {
+            title: "Berserk () [Deluxe]".to_string(),
+            ..without_year
+        };
+
+        assert_eq!(
+            "Berserk () [Deluxe]",
+            format_search_item_title("[{rating}] {title} ({year})", &with_brackets_in_title)
+        );
+    }
+
+    #[test]
+    fn share_text_has_the_first_paragraph_of_the_description() {
+        let manga = Manga {
+            id: "801513ba".to_string(),
+            title: "Berserk".to_string(),
+            description: "Guts, a former mercenary.\n\n---\nLinks".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            "Berserk: Guts, a former mercenary.\nhttps://mangadex.org/title/801513ba",
+            format_share_text("{title}: {description}\n{url}", &manga)
+        );
+        assert_eq!(
+            "Guts, a…",
+            description_excerpt("Guts, a former mercenary.", 10)
+        );
+    }
+
+    #[test]
+    fn loaded_pages_window_follows_the_selected_page() {
+        assert_eq!(0..5, loaded_pages_window(0, 20, 5));
+        assert_eq!(8..13, loaded_pages_window(10, 20, 5));
+        assert_eq!(15..20, loaded_pages_window(19, 20, 5));
+        assert_eq!(0..3, loaded_pages_window(1, 3, 10));
+        assert_eq!(4..5, loaded_pages_window(4, 20, 0));
+    }
+
+    #[test]
+    fn indexes_closest_to_the_selected_one_come_first() {
+        assert_eq!(vec![2, 3, 1, 4, 0, 5], indexes_by_distance(2, 6));
+        assert_eq!(vec![0, 1, 2], indexes_by_distance(0, 3));
+        assert_eq!(vec![2, 1, 0], indexes_by_distance(5, 3));
+    }
+
+    #[test]
+    fn covers_are_cropped_to_the_same_shape() {
+        let wide = crop_to_cover_aspect(DynamicImage::new_rgb8(200, 200));
+        assert_eq!((140, 200), (wide.width(), wide.height()));
+
+        let tall = crop_to_cover_aspect(DynamicImage::new_rgb8(140, 400));
+        assert_eq!((140, 200), (tall.width(), tall.height()));
+
+        let uniform = crop_to_cover_aspect(DynamicImage::new_rgb8(70, 100));
+        assert_eq!((70, 100), (uniform.width(), uniform.height()));
+    }
+
+    #[test]
+    fn exact_titles_rank_first() {
+        assert_eq!(0, title_match_rank("One Piece", "one piece "));
+        assert_eq!(1, title_match_rank("One Piece: Strong World", "one piece"));
+        assert_eq!(2, title_match_rank("Wanted! One Piece", "one piece"));
+        assert_eq!(3, title_match_rank("One Punch-Man", "one piece"));
+        assert_eq!(3, title_match_rank("One Piece", ""));
+    }
+
+    #[test]
+    fn selection_is_clamped_to_the_list() {
+        assert_eq!(Some(2), clamp_selection(Some(5), 3));
+        assert_eq!(Some(1), clamp_selection(Some(1), 3));
+        assert_eq!(None, clamp_selection(Some(1), 0));
+        assert_eq!(None, clamp_selection(None, 3));
+    }
+
+    #[test]
+    fn search_term_is_relaxed_by_dropping_the_last_word() {
+        assert_eq!(
+            Some("one piece".to_string()),
+            relaxed_search_term(" one piece  colr ")
+        );
+        assert_eq!(Some("berserk".to_string()), relaxed_search_term("berserkk"));
+        assert_eq!(None, relaxed_search_term("aot"));
+        assert_eq!(None, relaxed_search_term("   "));
+    }
+
+    #[test]
+    fn text_is_truncated_by_its_display_width() {
+        assert_eq!("One Piece", truncate_to_width("One Piece", 9));
+        assert_eq!("One Pi…", truncate_to_width("One Piece", 7));
+
+        // Each of these characters takes two columns
+        assert_eq!("ワンピ…", truncate_to_width("ワンピース", 7));
+        assert_eq!("ワン…", truncate_to_width("ワンピース", 6));
+        assert_eq!("进击的巨人", truncate_to_width("进击的巨人", 10));
+
+        assert_eq!("🔥 Fi…", truncate_to_width("🔥 Fire Force", 6));
+
+        // The accent is a combining character, it takes no column of its own
+        assert_eq!("Poke\u{301}mon", truncate_to_width("Poke\u{301}mon", 7));
+        assert_eq!("Poke\u{301}…", truncate_to_width("Poke\u{301}mon", 5));
+
+        assert_eq!("", truncate_to_width("ワンピース", 0));
+    }
+
+    #[test]
+    fn tags_which_dont_fit_keep_the_genres_first() {
+        let tag = |name: &str, group: &str| MangaTag {
+            name: name.to_string(),
+            group: group.to_string(),
+        };
+
+        let tags = vec![
+            tag("Long Strip", "format"),
+            tag("Action", "genre"),
+            tag("Gore", "content"),
+            tag("Romance", "genre"),
+        ];
+
+        let (fitting, hidden) = fit_tags(&tags, 40, 1);
+        assert_eq!(4, fitting.len());
+        assert_eq!(0, hidden);
+
+        // `Action  Romance  ` and `+2 more <T>` take 28 columns
+        let (fitting, hidden) = fit_tags(&tags, 28, 1);
+        let names: Vec<&str> = fitting.iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(vec!["Action", "Romance"], names);
+        assert_eq!(2, hidden);
+
+        let (fitting, hidden) = fit_tags(&tags, 28, 2);
+        let names: Vec<&str> = fitting.iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(vec!["Long Strip", "Action", "Gore", "Romance"], names);
+        assert_eq!(0, hidden);
+    }
+
+    #[test]
+    fn file_names_are_sorted_by_the_value_of_their_numbers() {
+        let mut files = vec![
+            "page10.png",
+            "Page2.png",
+            "page1.png",
+            "cover.jpg",
+            "page02b.png",
+        ];
+
+        files.sort_by(|a, b| natural_cmp(a, b));
+
+        assert_eq!(
+            vec![
+                "cover.jpg",
+                "page1.png",
+                "Page2.png",
+                "page02b.png",
+                "page10.png"
+            ],
+            files
+        );
+    }
+
+    #[test]
+    fn dates_are_parsed_and_formatted() {
+        let from_api = parse_date("2024-03-05T22:30:00+02:00").unwrap();
+        let from_database = parse_date("2024-03-05 20:30:00").unwrap();
+
+        assert_eq!(from_api, from_database);
+        assert_eq!(
+            Some("05/03/2024 20:30".to_string()),
+            format_date(from_api, "%d/%m/%Y %H:%M", Timezone::Utc)
+        );
+        assert_eq!(None, format_date(from_api, "%Q", Timezone::Utc));
+        assert_eq!(None, parse_date("yesterday"));
+    }
+
+    #[test]
+    fn keys_are_remapped() {
+        use crossterm::event::KeyModifiers;
+
+        let key_remap = HashMap::from([('о', 'j'), ('Л', 'K')]);
+
+        let remapped = remap_key(
+            KeyEvent::new(KeyCode::Char('о'), KeyModifiers::NONE),
+            &key_remap,
+        );
+        assert_eq!(KeyCode::Char('j'), remapped.code);
+
+        let remapped = remap_key(
+            KeyEvent::new(KeyCode::Char('Л'), KeyModifiers::SHIFT),
+            &key_remap,
+        );
+        assert_eq!(KeyCode::Char('K'), remapped.code);
+        assert_eq!(KeyModifiers::SHIFT, remapped.modifiers);
+
+        let not_remapped = remap_key(
+            KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+            &key_remap,
+        );
+        assert_eq!(KeyCode::Enter, not_remapped.code);
     }
 }
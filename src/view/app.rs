@@ -1,19 +1,32 @@
 use self::feed::Feed;
 use self::home::Home;
 use self::manga::MangaPage;
-use self::reader::MangaReader;
+use self::reader::{ChapterToRead, MangaReader};
 use self::search::{InputMode, SearchPage};
+use self::stats::Stats;
+use crate::backend::error_log::{write_to_error_log, ErrorType};
+use crate::backend::fetch::MangadexClient;
+use crate::backend::session::Session;
 use crate::backend::tui::{Action, Events};
 use crate::backend::{ChapterPagesResponse};
-use crate::global::INSTRUCTIONS_STYLE;
+use crate::config::MangaTuiConfig;
+use crate::global::{is_offline, INSTRUCTIONS_STYLE};
+use crate::utils::{decode_cover, from_manga_response, truncate_to_width};
+use crate::{cycle_image_protocol, new_image_protocol, picker};
 use crate::view::pages::*;
 use ::crossterm::event::KeyCode;
 use crossterm::event::{KeyEvent, KeyModifiers};
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::widgets::{Block, Borders, Tabs, Widget};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Tabs, Widget};
 use ratatui::Frame;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use unicode_width::UnicodeWidthStr;
 
 use super::widgets::search::MangaItem;
 use super::widgets::Component;
@@ -34,15 +47,34 @@ pub struct App {
     pub manga_page: Option<MangaPage>,
     pub manga_reader_page: Option<MangaReader>,
     pub search_page: SearchPage,
+    /// The searches opened with `<D>` which are not shown, the one after `search_page` first
+    other_search_pages: VecDeque<SearchPage>,
+    /// Position of `search_page` among all the searches, for the tab title
+    search_page_position: usize,
     pub home_page: Home,
     pub feed_page: Feed,
+    pub stats_page: Stats,
+    /// Short message displayed on top of the current page, it goes away after
+    /// `NOTIFICATION_DURATION`
+    notification: Option<(String, Instant)>,
+    /// When the quit key was pressed for the first time, used when `confirm_quit` is enabled
+    quit_requested_at: Option<Instant>,
+    /// Manga, chapter and page of a restored session, the chapter is read once the manga page is
+    /// opened
+    resumed_chapter: Option<(String, String, usize)>,
 }
 
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(3);
+
 impl Component for App {
     type Actions = Action;
     fn render(&mut self, area: Rect, frame: &mut Frame<'_>) {
-        if self.manga_reader_page.is_some() && self.current_tab == SelectedPage::ReaderTab {
-            self.manga_reader_page.as_mut().unwrap().render(area, frame);
+        if let Some(reader_page) = self
+            .manga_reader_page
+            .as_mut()
+            .filter(|_| self.current_tab == SelectedPage::ReaderTab)
+        {
+            reader_page.render(area, frame);
         } else {
             let main_layout =
                 Layout::vertical([Constraint::Percentage(6), Constraint::Percentage(94)]);
@@ -53,18 +85,42 @@ impl Component for App {
 
             self.render_pages(page_area, frame);
         }
+
+        self.render_notification(area, frame.buffer_mut());
     }
 
     fn handle_events(&mut self, events: Events) {
         match events {
             Events::Key(key_event) => self.handle_key_events(key_event),
+            Events::GoToHome
+            | Events::GoSearchPage
+            | Events::GoToMangaPage(_)
+            | Events::ContinueReading(_)
+            | Events::GoSearchMangasAuthor(_)
+            | Events::GoSearchMangasArtist(_)
+                if is_offline() =>
+            {
+                self.notify("Unavailable offline");
+            }
             Events::GoToMangaPage(manga) => self.go_to_manga_page(manga),
-            Events::ReadChapter(chapter_response) => self.go_to_read_chapter(chapter_response),
+            Events::ContinueReading(manga) => {
+                self.go_to_manga_page(manga);
+                if let Some(manga_page) = self.manga_page.as_mut() {
+                    manga_page.continue_reading();
+                }
+            }
+            Events::ReadChapter(chapter_response, chapter) => {
+                self.go_to_read_chapter(chapter_response, chapter)
+            }
+            Events::ReadDownloadedChapter(chapter, pages_dir) => {
+                self.go_to_read_downloaded_chapter(chapter, pages_dir)
+            }
             Events::GoSearchPage => {
                 self.go_search_page();
             }
             Events::GoToHome => self.go_to_home(),
             Events::GoFeedPage => self.go_feed_page(),
+            Events::GoStatsPage => self.go_stats_page(),
 
             Events::GoSearchMangasAuthor(author) => {
                 self.go_search_page();
@@ -74,6 +130,17 @@ impl Component for App {
                 self.go_search_page();
                 self.search_page.search_mangas_of_artist(artist);
             }
+            Events::ShowNotification(message) => self.notify(message),
+            Events::DuplicateSearch => self.duplicate_search(),
+            Events::SwitchSearch => self.switch_search(),
+            Events::CloseSearch => self.close_search(),
+            Events::CloseReader => self.close_reader(),
+            Events::ReadNextChapter => {
+                self.close_reader();
+                if let Some(manga_page) = self.manga_page.as_mut() {
+                    manga_page.read_next_chapter();
+                }
+            }
             _ => {}
         }
     }
@@ -93,13 +160,21 @@ impl App {
         let (global_action_tx, global_action_rx) = unbounded_channel::<Action>();
         let (global_event_tx, global_event_rx) = unbounded_channel::<Events>();
 
-        global_event_tx.send(Events::GoToHome).ok();
+        // Only the downloaded chapters can be read in offline mode
+        if is_offline() {
+            global_event_tx.send(Events::GoFeedPage).ok();
+        } else {
+            global_event_tx.send(Events::GoToHome).ok();
+        }
 
         App {
             current_tab: SelectedPage::default(),
             search_page: SearchPage::init(global_event_tx.clone()),
+            other_search_pages: VecDeque::new(),
+            search_page_position: 0,
             feed_page: Feed::new(global_event_tx.clone()),
             home_page: Home::new(global_event_tx.clone()),
+            stats_page: Stats::new(),
             manga_page: None,
             manga_reader_page: None,
             global_action_tx,
@@ -107,11 +182,190 @@ impl App {
             global_event_tx,
             global_event_rx,
             state: AppState::Runnning,
+            notification: None,
+            quit_requested_at: None,
+            resumed_chapter: None,
+        }
+    }
+
+    /// Chapters read from the downloads are opened from the feed, so the feed is where they are
+    /// restored
+    pub fn session(&self) -> Session {
+        match self.current_tab {
+            SelectedPage::Home => Session::Home,
+            SelectedPage::Search => Session::Search {
+                results: self.search_page.shown_results(),
+            },
+            SelectedPage::Feed => Session::Feed,
+            SelectedPage::Stats => Session::Stats,
+            SelectedPage::MangaTab => match self.manga_page.as_ref() {
+                Some(manga_page) => Session::Manga {
+                    manga_id: manga_page.manga.id.clone(),
+                },
+                None => Session::Home,
+            },
+            SelectedPage::ReaderTab => {
+                let position = self
+                    .manga_reader_page
+                    .as_ref()
+                    .and_then(|reader| reader.reading_position());
+                match (self.manga_page.as_ref(), position) {
+                    (Some(manga_page), Some((chapter_id, page))) => Session::Reader {
+                        manga_id: manga_page.manga.id.clone(),
+                        chapter_id: chapter_id.to_string(),
+                        page,
+                    },
+                    _ => Session::Feed,
+                }
+            }
+        }
+    }
+
+    /// The pages are opened with events so they come after the home page, which is opened as
+    /// soon as the app starts
+    pub fn restore_session(&mut self, session: Session) {
+        match session {
+            Session::Home => {}
+            Session::Search { results } => {
+                self.global_event_tx.send(Events::GoSearchPage).ok();
+                if let Some(results) = results {
+                    self.search_page.restore_results(results);
+                }
+            }
+            Session::Feed => {
+                self.global_event_tx.send(Events::GoFeedPage).ok();
+            }
+            Session::Stats => {
+                self.global_event_tx.send(Events::GoStatsPage).ok();
+            }
+            Session::Manga { manga_id } => self.restore_manga_page(manga_id),
+            Session::Reader {
+                manga_id,
+                chapter_id,
+                page,
+            } => {
+                self.resumed_chapter = Some((manga_id.clone(), chapter_id, page));
+                self.restore_manga_page(manga_id);
+            }
+        }
+    }
+
+    fn restore_manga_page(&self, manga_id: String) {
+        let tx = self.global_event_tx.clone();
+        tokio::spawn(async move {
+            let manga = match MangadexClient::global().get_one_manga(&manga_id).await {
+                Ok(response) => from_manga_response(response.data),
+                Err(e) => {
+                    write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    tx.send(Events::ShowNotification(
+                        "The manga of the last session could not be opened".to_string(),
+                    ))
+                    .ok();
+                    return;
+                }
+            };
+
+            let cover = match (picker(), manga.img_url.as_deref()) {
+                (Some(_), Some(img_url)) => MangadexClient::global()
+                    .get_cover_for_manga(&manga_id, img_url)
+                    .await
+                    .ok()
+                    .and_then(decode_cover)
+                    .map(new_image_protocol),
+                _ => None,
+            };
+
+            tx.send(Events::GoToMangaPage(MangaItem::new(manga, cover)))
+                .ok();
+        });
+    }
+
+    pub fn notify(&mut self, message: impl Into<String>) {
+        self.notification = Some((message.into(), Instant::now()));
+    }
+
+    fn render_notification(&mut self, area: Rect, buf: &mut Buffer) {
+        if self
+            .notification
+            .as_ref()
+            .is_some_and(|(_, shown_at)| shown_at.elapsed() > NOTIFICATION_DURATION)
+        {
+            self.notification = None;
+        }
+
+        if let Some((message, _)) = self.notification.as_ref() {
+            let message = truncate_to_width(message, area.width.saturating_sub(4) as usize);
+            let width = (message.width() as u16 + 4).min(area.width);
+            let notification_area =
+                Rect::new(area.right().saturating_sub(width), area.y, width, 3).intersection(area);
+
+            Clear.render(notification_area, buf);
+            Paragraph::new(message)
+                .block(Block::bordered().border_style(*INSTRUCTIONS_STYLE))
+                .render(notification_area, buf);
+        }
+    }
+
+    /// If `confirm_quit` is enabled the quit key has to be pressed again within
+    /// `NOTIFICATION_DURATION`, which is as long as the prompt is displayed
+    fn request_quit(&mut self) {
+        let confirmed = !MangaTuiConfig::get().confirm_quit
+            || self
+                .quit_requested_at
+                .is_some_and(|requested_at| requested_at.elapsed() <= NOTIFICATION_DURATION);
+
+        if confirmed {
+            self.global_action_tx.send(Action::Quit).ok();
+        } else {
+            self.quit_requested_at = Some(Instant::now());
+            self.notify("Press <Ctrl+c> again to quit");
+        }
+    }
+
+    /// Switch to the next image protocol and build the images of the pages which are open again
+    fn cycle_image_protocol(&mut self) {
+        match cycle_image_protocol() {
+            Some(protocol) => {
+                self.home_page.reload_images();
+                self.search_page.reload_images();
+                self.other_search_pages
+                    .iter_mut()
+                    .for_each(SearchPage::reload_images);
+                if let Some(manga_page) = self.manga_page.as_mut() {
+                    manga_page.reload_images();
+                }
+                if let Some(reader_page) = self.manga_reader_page.as_mut() {
+                    reader_page.reload_images();
+                }
+                self.notify(format!("Image protocol: {protocol:?}"));
+            }
+            None => self.notify("No image protocol is supported by this terminal"),
         }
     }
 
     pub fn render_top_tabs(&self, area: Rect, buf: &mut Buffer) {
-        let mut titles: Vec<&str> = vec!["Home <F1>/<u>", "Search <F2>/<i>", "Feed <F3>/<o>"];
+        let online_tab_style = if is_offline() {
+            Style::default().dark_gray().crossed_out()
+        } else {
+            Style::default()
+        };
+
+        let search_title = if self.other_search_pages.is_empty() {
+            "Search <F2>/<i>".to_string()
+        } else {
+            format!(
+                "Search {}/{} <F2>/<i>",
+                self.search_page_position + 1,
+                self.other_search_pages.len() + 1
+            )
+        };
+
+        let mut titles: Vec<Line<'_>> = vec![
+            Line::styled("Home <F1>/<u>", online_tab_style),
+            Line::styled(search_title, online_tab_style),
+            "Feed <F3>/<o>".into(),
+            "Stats <F5>".into(),
+        ];
 
         let tabs_block = Block::default().borders(Borders::BOTTOM);
 
@@ -119,13 +373,18 @@ impl App {
             SelectedPage::Home => 0,
             SelectedPage::Search => 1,
             SelectedPage::Feed => 2,
+            SelectedPage::Stats => 3,
             SelectedPage::MangaTab => {
-                titles.push(" 📖 Manga page");
-                3
+                titles.push(" 📖 Manga page".into());
+                4
             }
             _ => 0,
         };
 
+        if is_offline() {
+            titles.push("Offline mode".into());
+        }
+
         Tabs::new(titles)
             .block(tabs_block)
             .highlight_style(*INSTRUCTIONS_STYLE)
@@ -141,6 +400,7 @@ impl App {
             SelectedPage::MangaTab => self.render_manga_page(area, frame),
             SelectedPage::Home => self.render_home_page(area, frame),
             SelectedPage::Feed => self.render_feed_page(area, frame),
+            SelectedPage::Stats => self.stats_page.render(area, frame),
             // Reader tab should be on full screen
             SelectedPage::ReaderTab => {}
         }
@@ -164,37 +424,44 @@ impl App {
         self.home_page.render(area, frame);
     }
 
+    /// Whether a key goes to a text input instead of being a shortcut
+    pub fn is_typing(&mut self) -> bool {
+        self.search_page.input_mode == InputMode::Typing
+            || self.search_page.is_typing_filter()
+            || self.feed_page.is_typing()
+    }
+
     fn handle_key_events(&mut self, key_event: KeyEvent) {
-        if self.search_page.input_mode != InputMode::Typing
-            && !self.search_page.is_typing_filter()
-            && !self.feed_page.is_typing()
-        {
+        if !self.is_typing() {
             match key_event.code {
                 KeyCode::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => {
-                    self.global_action_tx.send(Action::Quit).ok();
+                    self.request_quit();
+                }
+                KeyCode::Char('u') | KeyCode::F(1)
+                    if self.current_tab != SelectedPage::ReaderTab =>
+                {
+                    self.global_event_tx.send(Events::GoToHome).ok();
                 }
-                KeyCode::Char('u') | KeyCode::F(1) => {
-                    if self.current_tab != SelectedPage::ReaderTab {
-                        self.global_event_tx.send(Events::GoToHome).ok();
-                    }
+                KeyCode::Char('i') | KeyCode::F(2)
+                    if self.current_tab != SelectedPage::ReaderTab =>
+                {
+                    self.global_event_tx.send(Events::GoSearchPage).ok();
                 }
-                KeyCode::Char('i') | KeyCode::F(2) => {
-                    if self.current_tab != SelectedPage::ReaderTab {
-                        self.global_event_tx.send(Events::GoSearchPage).ok();
-                    }
+                KeyCode::Char('o') | KeyCode::F(3)
+                    if self.current_tab != SelectedPage::ReaderTab =>
+                {
+                    self.global_event_tx.send(Events::GoFeedPage).ok();
                 }
-                KeyCode::Char('o') | KeyCode::F(3) => {
-                    if self.current_tab != SelectedPage::ReaderTab {
-                        self.global_event_tx.send(Events::GoFeedPage).ok();
-                    }
+                KeyCode::F(4) => self.cycle_image_protocol(),
+                KeyCode::F(5) if self.current_tab != SelectedPage::ReaderTab => {
+                    self.global_event_tx.send(Events::GoStatsPage).ok();
                 }
-                KeyCode::Backspace => {
-                    if self.current_tab == SelectedPage::ReaderTab
-                        && self.manga_reader_page.is_some()
-                    {
-                        self.manga_reader_page.as_mut().unwrap().clean_up();
-                        self.current_tab = SelectedPage::MangaTab;
-                    }
+                KeyCode::F(6) if self.current_tab != SelectedPage::ReaderTab => {
+                    let profile = MangaTuiConfig::switch_profile();
+                    self.notify(format!("Mangas are now taken from {profile}"));
+                }
+                KeyCode::Backspace if self.current_tab == SelectedPage::ReaderTab => {
+                    self.close_reader();
                 }
 
                 _ => {}
@@ -211,6 +478,42 @@ impl App {
         self.current_tab = SelectedPage::Search;
     }
 
+    /// The new search is shown right after the current one
+    fn duplicate_search(&mut self) {
+        let duplicate = self.search_page.duplicate();
+        self.other_search_pages.push_front(duplicate);
+        self.switch_search();
+    }
+
+    /// The searches which are not shown keep their results, and the ones which were loading
+    /// finish loading once they are shown again
+    fn switch_search(&mut self) {
+        let Some(next) = self.other_search_pages.pop_front() else {
+            self.notify("There is only one search, press <D> to duplicate it");
+            return;
+        };
+
+        let previous = std::mem::replace(&mut self.search_page, next);
+        self.other_search_pages.push_back(previous);
+        self.search_page_position =
+            (self.search_page_position + 1) % (self.other_search_pages.len() + 1);
+    }
+
+    fn close_search(&mut self) {
+        let Some(next) = self.other_search_pages.pop_front() else {
+            self.notify("The last search can't be closed");
+            return;
+        };
+
+        let mut closed = std::mem::replace(&mut self.search_page, next);
+        closed.clean_up();
+
+        // The one after the last search is the first one
+        if self.search_page_position > self.other_search_pages.len() {
+            self.search_page_position = 0;
+        }
+    }
+
     fn go_to_manga_page(&mut self, manga: MangaItem) {
         if self.manga_reader_page.is_some() {
             self.manga_reader_page.as_mut().unwrap().clean_up();
@@ -219,24 +522,51 @@ impl App {
 
         self.feed_page.clean_up();
 
+        let chapter_order = if self.current_tab == SelectedPage::Feed {
+            MangaTuiConfig::get().feed_chapter_order
+        } else {
+            MangaTuiConfig::get().chapter_order
+        };
+
+        let resumed_chapter = self
+            .resumed_chapter
+            .take_if(|(manga_id, _, _)| *manga_id == manga.manga.id);
+
         self.current_tab = SelectedPage::MangaTab;
-        self.manga_page = Some(MangaPage::new(
+        let mut manga_page = MangaPage::new(
             manga.manga,
             manga.image_state,
+            chapter_order,
             self.global_event_tx.clone(),
-        ));
+        );
+        if let Some((_, chapter_id, page)) = resumed_chapter {
+            manga_page.resume_reading(chapter_id, page);
+        }
+        self.manga_page = Some(manga_page);
     }
 
-    fn go_to_read_chapter(&mut self, chapter_response: ChapterPagesResponse) {
+    fn go_to_read_chapter(
+        &mut self,
+        chapter_response: ChapterPagesResponse,
+        chapter: ChapterToRead,
+    ) {
         self.home_page.clean_up();
         self.feed_page.clean_up();
         self.current_tab = SelectedPage::ReaderTab;
         self.manga_reader_page = Some(MangaReader::new(
             self.global_event_tx.clone(),
-            chapter_response.chapter.hash,
-            chapter_response.base_url,
-            chapter_response.chapter.data_saver,
-            chapter_response.chapter.data,
+            chapter,
+            chapter_response,
+        ));
+    }
+
+    fn go_to_read_downloaded_chapter(&mut self, chapter: ChapterToRead, pages_dir: PathBuf) {
+        self.feed_page.clean_up();
+        self.current_tab = SelectedPage::ReaderTab;
+        self.manga_reader_page = Some(MangaReader::from_downloaded_chapter(
+            self.global_event_tx.clone(),
+            chapter,
+            pages_dir,
         ));
     }
 
@@ -263,4 +593,26 @@ impl App {
         self.feed_page.init_search();
         self.current_tab = SelectedPage::Feed;
     }
+
+    fn close_reader(&mut self) {
+        if let Some(reader_page) = self.manga_reader_page.as_mut() {
+            reader_page.clean_up();
+            // Downloaded chapters are opened from the feed, there is no manga page to go back to
+            if self.manga_page.is_some() {
+                self.current_tab = SelectedPage::MangaTab;
+            } else {
+                self.go_feed_page();
+            }
+        }
+    }
+
+    fn go_stats_page(&mut self) {
+        if self.manga_page.is_some() {
+            self.manga_page.as_mut().unwrap().clean_up();
+            self.manga_page = None;
+        }
+        self.feed_page.clean_up();
+        self.stats_page.load_stats();
+        self.current_tab = SelectedPage::Stats;
+    }
 }
@@ -1,10 +1,21 @@
+use std::time::Duration;
 use strum::{Display, EnumCount, EnumIter, FromRepr};
 
 pub mod feed;
 pub mod home;
 pub mod manga;
+pub mod onboarding;
 pub mod reader;
 pub mod search;
+pub mod stats;
+
+/// Most local events a page handles on each tick, so a burst of covers finishing at once is shown
+/// within a tick or two while the screen keeps being drawn in between
+pub const MAX_EVENTS_PER_TICK: usize = 20;
+
+/// Time a page spends handling its local events on each tick, building the protocol of a cover
+/// can take a while so once it's spent the rest of the events are left for the next tick
+pub const TICK_TIME_BUDGET: Duration = Duration::from_millis(5);
 
 #[derive(
     Clone, Copy, Default, FromRepr, Display, EnumIter, EnumCount, PartialEq, Eq, PartialOrd, Ord,
@@ -16,4 +27,5 @@ pub enum SelectedPage {
     Home,
     Search,
     Feed,
+    Stats,
 }
@@ -1,18 +1,24 @@
-use crate::backend::database::{get_history, MangaHistoryResponse, MangaHistoryType};
+use crate::backend::database::{
+    clear_reading_history, get_history, get_library, get_library_chapters,
+    remove_from_plan_to_read, toggle_pinned_manga, MangaHistoryResponse, MangaHistoryType,
+};
 use crate::backend::error_log::{write_to_error_log, ErrorType};
 use crate::backend::fetch::MangadexClient;
 use crate::backend::tui::Events;
 use crate::backend::ChapterResponse;
-use crate::global::{ERROR_STYLE, INSTRUCTIONS_STYLE};
-use crate::utils::{from_manga_response, render_search_bar};
+use crate::config::MangaTuiConfig;
+use crate::global::{is_offline, ERROR_STYLE, INSTRUCTIONS_STYLE};
+use crate::utils::{
+    centered_rect, decode_cover, from_manga_response, paste_into_input, render_search_bar,
+};
+use crate::view::pages::reader::ChapterToRead;
 use crate::view::widgets::feed::{FeedTabs, HistoryWidget, MangasRead};
 use crate::view::widgets::search::MangaItem;
 use crate::view::widgets::Component;
-use crate::PICKER;
+use crate::{new_image_protocol, picker};
 use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
-use image::io::Reader;
 use ratatui::{prelude::*, widgets::*};
-use std::io::Cursor;
+use std::path::PathBuf;
 use throbber_widgets_tui::{Throbber, ThrobberState};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinSet;
@@ -38,6 +44,12 @@ pub enum FeedActions {
     PreviousPage,
     ChangeTab,
     GoToMangaPage,
+    AskClearHistory,
+    ConfirmClearHistory,
+    CancelClearHistory,
+    ToggleLayout,
+    TogglePin,
+    RemoveFromPlanToRead,
 }
 
 pub enum FeedEvents {
@@ -62,6 +74,8 @@ pub struct Feed {
     pub local_event_rx: UnboundedReceiver<FeedEvents>,
     search_bar: Input,
     is_typing: bool,
+    /// Set while the confirmation to clear the whole reading history is shown
+    is_clear_history_confirmation_open: bool,
     tasks: JoinSet<()>,
 }
 
@@ -70,7 +84,11 @@ impl Feed {
         let (local_action_tx, local_action_rx) = mpsc::unbounded_channel::<FeedActions>();
         let (local_event_tx, local_event_rx) = mpsc::unbounded_channel::<FeedEvents>();
         Self {
-            tabs: FeedTabs::History,
+            tabs: if is_offline() {
+                FeedTabs::Downloads
+            } else {
+                FeedTabs::History
+            },
             loading_state: None,
             history: None,
             state: FeedState::DisplayingHistory,
@@ -82,6 +100,7 @@ impl Feed {
             tasks: JoinSet::new(),
             search_bar: Input::default(),
             is_typing: false,
+            is_clear_history_confirmation_open: false,
         }
     }
 
@@ -114,14 +133,35 @@ impl Feed {
         let selected_tab = match self.tabs {
             FeedTabs::History => 0,
             FeedTabs::PlantToRead => 1,
+            FeedTabs::Downloads => 2,
         };
 
-        let tabs_instructions = Line::from(vec![
+        let mut tabs_instructions = Line::from(vec![
             "Switch tab: ".into(),
             Span::raw("<tab>").style(*INSTRUCTIONS_STYLE),
         ]);
 
-        Tabs::new(vec!["Reading history", "Plan to Read"])
+        tabs_instructions.push_span(" Layout: ");
+        tabs_instructions.push_span(Span::raw("<l>").style(*INSTRUCTIONS_STYLE));
+        tabs_instructions.push_span(" Pin: ");
+        tabs_instructions.push_span(Span::raw("<p>").style(*INSTRUCTIONS_STYLE));
+
+        if matches!(self.tabs, FeedTabs::History) {
+            tabs_instructions.push_span(" Clear history: ");
+            tabs_instructions.push_span(Span::raw("<C>").style(*INSTRUCTIONS_STYLE));
+        }
+
+        if matches!(self.tabs, FeedTabs::PlantToRead) {
+            tabs_instructions.push_span(" Remove: ");
+            tabs_instructions.push_span(Span::raw("<d>").style(*INSTRUCTIONS_STYLE));
+        }
+
+        if is_offline() && matches!(self.tabs, FeedTabs::Downloads) {
+            tabs_instructions.push_span(" Read latest download: ");
+            tabs_instructions.push_span(Span::raw("<r>").style(*INSTRUCTIONS_STYLE));
+        }
+
+        Tabs::new(vec!["Reading history", "Plan to Read", "Downloads"])
             .select(selected_tab)
             .block(Block::bordered().title(tabs_instructions))
             .highlight_style(Style::default().fg(Color::Yellow))
@@ -187,12 +227,46 @@ impl Feed {
         self.render_searching_status(searching_area, frame.buffer_mut());
     }
 
+    fn render_clear_history_confirmation(&mut self, area: Rect, buf: &mut Buffer) {
+        let popup_area = centered_rect(area, 40, 20);
+
+        Clear.render(popup_area, buf);
+
+        let instructions = Line::from(vec![
+            "Yes ".into(),
+            Span::raw("<y>").style(*INSTRUCTIONS_STYLE),
+            " No ".into(),
+            Span::raw("<n>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        Paragraph::new(
+            "Clear the whole reading history? The chapters read and the stats are forgotten, plan to read and downloads are kept",
+        )
+        .wrap(Wrap { trim: true })
+        .block(Block::bordered().title_bottom(instructions))
+        .render(popup_area, buf);
+    }
+
     pub fn init_search(&mut self) {
         self.local_event_tx.send(FeedEvents::SearchHistory).ok();
     }
 
     fn handle_key_events(&mut self, key_event: KeyEvent) {
-        if self.is_typing && self.state != FeedState::SearchingMangaPage {
+        if self.is_clear_history_confirmation_open {
+            match key_event.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    self.local_action_tx
+                        .send(FeedActions::ConfirmClearHistory)
+                        .ok();
+                }
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    self.local_action_tx
+                        .send(FeedActions::CancelClearHistory)
+                        .ok();
+                }
+                _ => {}
+            }
+        } else if self.is_typing && self.state != FeedState::SearchingMangaPage {
             match key_event.code {
                 KeyCode::Enter => {
                     self.local_event_tx.send(FeedEvents::SearchHistory).ok();
@@ -231,6 +305,20 @@ impl Feed {
                 KeyCode::Char('s') => {
                     self.local_action_tx.send(FeedActions::ToggleSearchBar).ok();
                 }
+                KeyCode::Char('l') => {
+                    self.local_action_tx.send(FeedActions::ToggleLayout).ok();
+                }
+                KeyCode::Char('p') => {
+                    self.local_action_tx.send(FeedActions::TogglePin).ok();
+                }
+                KeyCode::Char('d') if matches!(self.tabs, FeedTabs::PlantToRead) => {
+                    self.local_action_tx
+                        .send(FeedActions::RemoveFromPlanToRead)
+                        .ok();
+                }
+                KeyCode::Char('C') if matches!(self.tabs, FeedTabs::History) => {
+                    self.local_action_tx.send(FeedActions::AskClearHistory).ok();
+                }
                 _ => {}
             }
         }
@@ -263,6 +351,13 @@ impl Feed {
     }
 
     fn search_latest_chapters(&mut self) {
+        if let FeedTabs::Downloads = self.tabs {
+            self.load_downloaded_chapters();
+            return;
+        }
+        if is_offline() {
+            return;
+        }
         if let Some(history) = self.history.as_mut() {
             for manga in history.mangas.clone() {
                 let manga_id = manga.id;
@@ -286,6 +381,18 @@ impl Feed {
         }
     }
 
+    /// The chapters shown in the `Downloads` tab come from the library index instead of mangadex
+    fn load_downloaded_chapters(&mut self) {
+        if let Some(history) = self.history.as_mut() {
+            for manga in history.mangas.clone() {
+                match get_library_chapters(&manga.id) {
+                    Ok(chapters) => history.set_downloaded_chapters(manga.id, chapters),
+                    Err(e) => write_to_error_log(ErrorType::FromError(Box::new(e))),
+                }
+            }
+        }
+    }
+
     fn display_error_searching_manga(&mut self) {
         self.loading_state = None;
         self.state = FeedState::MangaPageNotFound;
@@ -303,12 +410,16 @@ impl Feed {
         };
 
         let history_type = match self.tabs {
-            FeedTabs::History => MangaHistoryType::ReadingHistory,
-            FeedTabs::PlantToRead => MangaHistoryType::PlanToRead,
+            FeedTabs::History => Some(MangaHistoryType::ReadingHistory),
+            FeedTabs::PlantToRead => Some(MangaHistoryType::PlanToRead),
+            FeedTabs::Downloads => None,
         };
 
         self.tasks.spawn(async move {
-            let maybe_reading_history = get_history(history_type, page, &search_term);
+            let maybe_reading_history = match history_type {
+                Some(history_type) => get_history(history_type, page, &search_term),
+                None => get_library(page, &search_term),
+            };
 
             match maybe_reading_history {
                 Ok(history) => {
@@ -337,6 +448,10 @@ impl Feed {
     }
 
     fn load_history(&mut self, maybe_history: Option<MangaHistoryResponse>) {
+        let recent_chapters_title = match self.tabs {
+            FeedTabs::Downloads => "Downloaded chapters",
+            _ => "Latest chapters",
+        };
         match maybe_history {
             Some(history) => {
                 self.history = Some(HistoryWidget {
@@ -348,7 +463,9 @@ impl Feed {
                         .map(|history| MangasRead {
                             id: history.id.clone(),
                             title: history.title.clone(),
+                            is_pinned: history.is_pinned,
                             recent_chapters: vec![],
+                            recent_chapters_title,
                             style: Style::default(),
                         })
                         .collect(),
@@ -381,11 +498,60 @@ impl Feed {
     fn change_tab(&mut self) {
         match self.tabs {
             FeedTabs::History => self.tabs = FeedTabs::PlantToRead,
-            FeedTabs::PlantToRead => self.tabs = FeedTabs::History,
+            FeedTabs::PlantToRead => self.tabs = FeedTabs::Downloads,
+            FeedTabs::Downloads => self.tabs = FeedTabs::History,
+        }
+    }
+
+    /// In offline mode there is no manga page to go to, so the most recently downloaded chapter of
+    /// the manga is opened instead
+    fn read_latest_download(&mut self) {
+        let Some(manga) = self
+            .history
+            .as_ref()
+            .and_then(|history| history.get_current_manga_selected())
+        else {
+            return;
+        };
+
+        if !matches!(self.tabs, FeedTabs::Downloads) {
+            self.global_event_tx
+                .send(Events::ShowNotification(
+                    "Only downloaded chapters can be read offline".to_string(),
+                ))
+                .ok();
+            return;
+        }
+
+        match get_library_chapters(&manga.id) {
+            Ok(chapters) => {
+                if let Some(chapter) = chapters.into_iter().next() {
+                    let chapter_to_read = ChapterToRead {
+                        id: chapter.chapter_id,
+                        title: chapter.title,
+                        number: chapter.number,
+                        lang: chapter.lang,
+                        manga_id: manga.id.clone(),
+                        manga_title: manga.title.clone(),
+                        ..Default::default()
+                    };
+                    self.global_event_tx
+                        .send(Events::ReadDownloadedChapter(
+                            chapter_to_read,
+                            PathBuf::from(chapter.path),
+                        ))
+                        .ok();
+                }
+            }
+            Err(e) => write_to_error_log(ErrorType::FromError(Box::new(e))),
         }
     }
 
     fn go_to_manga_page(&mut self) {
+        if is_offline() {
+            self.read_latest_download();
+            return;
+        }
         if let Some(history) = self.history.as_mut() {
             if let Some(currently_selected_manga) = history.get_current_manga_selected() {
                 self.state = FeedState::SearchingMangaPage;
@@ -400,7 +566,7 @@ impl Feed {
                         Ok(manga) => {
                             let manga_found = from_manga_response(manga.data);
 
-                            if PICKER.is_some() {
+                            if picker().is_some() {
                                 let cover = MangadexClient::global()
                                     .get_cover_for_manga(
                                         &manga_id,
@@ -412,16 +578,9 @@ impl Feed {
 
                                 match cover {
                                     Ok(bytes) => {
-                                        let dyn_img = Reader::new(Cursor::new(bytes))
-                                            .with_guessed_format()
-                                            .unwrap();
-
-                                        let maybe_decoded = dyn_img.decode();
                                         tx.send(Events::GoToMangaPage(MangaItem::new(
                                             manga_found,
-                                            maybe_decoded.ok().map(|decoded| {
-                                                PICKER.unwrap().new_resize_protocol(decoded)
-                                            }),
+                                            decode_cover(bytes).map(new_image_protocol),
                                         )))
                                         .ok();
                                     }
@@ -448,6 +607,76 @@ impl Feed {
         }
     }
 
+    fn clear_history(&mut self) {
+        let notification = match clear_reading_history() {
+            Ok(()) => "The reading history was cleared".to_string(),
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                "The reading history could not be cleared".to_string()
+            }
+        };
+
+        self.global_event_tx
+            .send(Events::ShowNotification(notification))
+            .ok();
+
+        if let Some(history) = self.history.as_mut() {
+            history.page = 1;
+        }
+        self.search_history();
+    }
+
+    /// Pinned mangas stay at the top of the tabs, so the current page is searched again to move
+    /// the manga to its new place
+    fn toggle_pin(&mut self) {
+        let Some(manga) = self
+            .history
+            .as_ref()
+            .and_then(|history| history.get_current_manga_selected())
+        else {
+            return;
+        };
+
+        let notification = match toggle_pinned_manga(&manga.id) {
+            Ok(true) => format!("{} was pinned", manga.title),
+            Ok(false) => format!("{} was unpinned", manga.title),
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                "The manga could not be pinned".to_string()
+            }
+        };
+
+        self.global_event_tx
+            .send(Events::ShowNotification(notification))
+            .ok();
+
+        self.search_history();
+    }
+
+    fn remove_from_plan_to_read(&mut self) {
+        let Some(manga) = self
+            .history
+            .as_ref()
+            .and_then(|history| history.get_current_manga_selected())
+        else {
+            return;
+        };
+
+        let notification = match remove_from_plan_to_read(&manga.id) {
+            Ok(()) => format!("{} was removed from plan to read", manga.title),
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                "The manga could not be removed from plan to read".to_string()
+            }
+        };
+
+        self.global_event_tx
+            .send(Events::ShowNotification(notification))
+            .ok();
+
+        self.search_history();
+    }
+
     fn toggle_focus_search_bar(&mut self) {
         self.is_typing = !self.is_typing;
     }
@@ -477,6 +706,10 @@ impl Component for Feed {
         self.render_top_area(tabs_area, frame);
 
         self.render_history(history_area, frame.buffer_mut());
+
+        if self.is_clear_history_confirmation_open {
+            self.render_clear_history_confirmation(area, frame.buffer_mut());
+        }
     }
 
     fn update(&mut self, action: Self::Actions) {
@@ -495,6 +728,21 @@ impl Component for Feed {
                     self.change_tab();
                     self.search_history();
                 }
+                FeedActions::AskClearHistory => self.is_clear_history_confirmation_open = true,
+                FeedActions::ConfirmClearHistory => {
+                    self.is_clear_history_confirmation_open = false;
+                    self.clear_history();
+                }
+                FeedActions::ToggleLayout => {
+                    MangaTuiConfig::update(|config| {
+                        config.feed_layout = config.feed_layout.toggle()
+                    });
+                }
+                FeedActions::TogglePin => self.toggle_pin(),
+                FeedActions::RemoveFromPlanToRead => self.remove_from_plan_to_read(),
+                FeedActions::CancelClearHistory => {
+                    self.is_clear_history_confirmation_open = false;
+                }
             }
         }
     }
@@ -511,6 +759,7 @@ impl Component for Feed {
                 self.handle_key_events(key_event);
             }
             Events::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
+            Events::Paste(text) if self.is_typing => paste_into_input(&mut self.search_bar, &text),
             Events::Tick => self.tick(),
             _ => {}
         }
@@ -1,11 +1,10 @@
 use crossterm::event::{KeyCode, KeyEvent};
-use image::io::Reader;
 use image::DynamicImage;
 use ratatui::{prelude::*, widgets::*};
 use ratatui_image::protocol::StatefulProtocol;
 use ratatui_image::{Resize, StatefulImage};
 use std::env;
-use std::io::Cursor;
+use std::time::Instant;
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinSet;
 
@@ -13,14 +12,16 @@ use crate::backend::error_log::{write_to_error_log, ErrorType};
 use crate::backend::fetch::MangadexClient;
 use crate::backend::tui::Events;
 use crate::backend::SearchMangaResponse;
+use crate::config::MangaTuiConfig;
 use crate::global::INSTRUCTIONS_STYLE;
-use crate::utils::search_manga_cover;
+use crate::utils::{decode_cover, decode_image, search_manga_cover};
+use crate::view::pages::{MAX_EVENTS_PER_TICK, TICK_TIME_BUDGET};
 use crate::view::widgets::home::{
     CarrouselItem, CarrouselState, PopularMangaCarrousel, RecentlyAddedCarrousel,
 };
 use crate::view::widgets::search::MangaItem;
 use crate::view::widgets::{Component, ImageHandler};
-use crate::PICKER;
+use crate::{new_image_protocol, picker};
 
 use self::text::ToSpan;
 
@@ -29,6 +30,38 @@ pub enum HomeState {
     Unused,
 }
 
+/// The row of the home page which `<h>`/`<l>` and `<Enter>` act on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HomeSection {
+    #[default]
+    Popular,
+    RecentlyAdded,
+}
+
+impl HomeSection {
+    fn next(self) -> Self {
+        match self {
+            Self::Popular => Self::RecentlyAdded,
+            Self::RecentlyAdded => Self::Popular,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            Self::Popular => Self::RecentlyAdded,
+            Self::RecentlyAdded => Self::Popular,
+        }
+    }
+
+    fn border_style(self, focused: Self) -> Style {
+        if self == focused {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        }
+    }
+}
+
 pub enum HomeEvents {
     SearchPopularNewMangas,
     SearchPopularMangasCover,
@@ -55,17 +88,21 @@ pub enum HomeActions {
     SelectNextPopularManga,
     SelectPreviousPopularManga,
     GoToPopularMangaPage,
-    GoToRecentlyAddedMangaPage,
-    SelectNextRecentlyAddedManga,
-    SelectPreviousRecentlyAddedManga,
     SupportMangadex,
     SupportProject,
+    FocusNextSection,
+    FocusPreviousSection,
+    FocusSection(HomeSection),
+    SelectNextInSection,
+    SelectPreviousInSection,
+    GoToSectionMangaPage,
 }
 
 pub struct Home {
     carrousel_popular_mangas: PopularMangaCarrousel,
     carrousel_recently_added: RecentlyAddedCarrousel,
     state: HomeState,
+    focused_section: HomeSection,
     pub global_event_tx: UnboundedSender<Events>,
     pub local_action_tx: UnboundedSender<HomeActions>,
     pub local_action_rx: UnboundedReceiver<HomeActions>,
@@ -98,24 +135,25 @@ impl Component for Home {
                 self.carrousel_popular_mangas.previous_item()
             }
             HomeActions::GoToPopularMangaPage => self.go_to_manga_page_popular(),
-            HomeActions::SelectNextRecentlyAddedManga => {
-                self.carrousel_recently_added.select_next()
-            }
-            HomeActions::SelectPreviousRecentlyAddedManga => {
-                self.carrousel_recently_added.select_previous()
-            }
-            HomeActions::GoToRecentlyAddedMangaPage => {
-                if let Some(item) = self.carrousel_recently_added.get_current_selected_manga() {
-                    self.global_event_tx
-                        .send(Events::GoToMangaPage(MangaItem::new(
-                            item.manga.clone(),
-                            item.cover_state.clone(),
-                        )))
-                        .ok();
-                }
-            }
             HomeActions::SupportProject => self.support_project(),
             HomeActions::SupportMangadex => self.support_mangadex(),
+            HomeActions::FocusNextSection => self.focused_section = self.focused_section.next(),
+            HomeActions::FocusPreviousSection => {
+                self.focused_section = self.focused_section.previous()
+            }
+            HomeActions::FocusSection(section) => self.focused_section = section,
+            HomeActions::SelectNextInSection => match self.focused_section {
+                HomeSection::Popular => self.carrousel_popular_mangas.next_item(),
+                HomeSection::RecentlyAdded => self.carrousel_recently_added.select_next(),
+            },
+            HomeActions::SelectPreviousInSection => match self.focused_section {
+                HomeSection::Popular => self.carrousel_popular_mangas.previous_item(),
+                HomeSection::RecentlyAdded => self.carrousel_recently_added.select_previous(),
+            },
+            HomeActions::GoToSectionMangaPage => match self.focused_section {
+                HomeSection::Popular => self.go_to_manga_page_popular(),
+                HomeSection::RecentlyAdded => self.go_to_manga_page_recently_added(),
+            },
         }
     }
 
@@ -145,6 +183,7 @@ impl Home {
             carrousel_popular_mangas: PopularMangaCarrousel::default(),
             carrousel_recently_added: RecentlyAddedCarrousel::default(),
             state: HomeState::Unused,
+            focused_section: HomeSection::default(),
             global_event_tx: tx,
             local_event_tx,
             local_event_rx,
@@ -167,6 +206,8 @@ impl Home {
             Span::raw("<b>").style(*INSTRUCTIONS_STYLE),
             " read ".into(),
             Span::raw("<r>").style(*INSTRUCTIONS_STYLE),
+            " Switch section ".into(),
+            Span::raw("<Tab>").style(*INSTRUCTIONS_STYLE),
             format!(
                 " No.{} Total : {}",
                 self.carrousel_popular_mangas.current_item_visible_index,
@@ -178,6 +219,7 @@ impl Home {
         Block::bordered()
             .title(Line::from(vec!["Popular new titles".bold()]))
             .title_bottom(instructions)
+            .border_style(HomeSection::Popular.border_style(self.focused_section))
             .render(area, buf);
 
         StatefulWidget::render(
@@ -199,6 +241,17 @@ impl Home {
         }
     }
 
+    fn go_to_manga_page_recently_added(&self) {
+        if let Some(item) = self.carrousel_recently_added.get_current_selected_manga() {
+            self.global_event_tx
+                .send(Events::GoToMangaPage(MangaItem::new(
+                    item.manga.clone(),
+                    item.cover_state.clone(),
+                )))
+                .ok();
+        }
+    }
+
     fn get_current_popular_manga(&self) -> Option<&CarrouselItem> {
         self.carrousel_popular_mangas.get_current_item()
     }
@@ -208,6 +261,9 @@ impl Home {
             || self.carrousel_recently_added.items.is_empty()
     }
 
+    /// The sections are requested in the order they are displayed and each one is shown as soon
+    /// as it arrives, their covers and the support image are background requests so they don't
+    /// delay the sections
     pub fn init_search(&mut self) {
         self.local_event_tx
             .send(HomeEvents::SearchPopularNewMangas)
@@ -216,17 +272,54 @@ impl Home {
         self.local_event_tx
             .send(HomeEvents::SearchRecentlyAddedMangas)
             .ok();
-        if PICKER.is_some() {
+        if picker().is_some() {
             self.local_event_tx
                 .send(HomeEvents::SearchSupportImage)
                 .ok();
         }
     }
 
+    /// Build the covers again, used when the image protocol changes so they are displayed with the
+    /// new one
+    pub fn reload_images(&mut self) {
+        if picker().is_none() {
+            return;
+        }
+
+        self.carrousel_popular_mangas
+            .items
+            .iter_mut()
+            .for_each(|item| item.cover_state = None);
+
+        self.carrousel_recently_added
+            .items
+            .iter_mut()
+            .for_each(|item| item.cover_state = None);
+
+        self.support_image = None;
+
+        self.local_event_tx
+            .send(HomeEvents::SearchPopularMangasCover)
+            .ok();
+        self.local_event_tx
+            .send(HomeEvents::SearchRecentlyCover)
+            .ok();
+        self.local_event_tx
+            .send(HomeEvents::SearchSupportImage)
+            .ok();
+    }
+
     pub fn tick(&mut self) {
         self.carrousel_popular_mangas.tick();
         self.carrousel_recently_added.tick();
-        if let Ok(local_event) = self.local_event_rx.try_recv() {
+        let started = Instant::now();
+        for _ in 0..MAX_EVENTS_PER_TICK {
+            if started.elapsed() >= TICK_TIME_BUDGET {
+                break;
+            }
+            let Ok(local_event) = self.local_event_rx.try_recv() else {
+                break;
+            };
             match local_event {
                 HomeEvents::SearchPopularMangasCover => self.search_popular_mangas_cover(),
                 HomeEvents::SearchPopularNewMangas => self.search_popular_mangas(),
@@ -254,19 +347,8 @@ impl Home {
                         let response = MangadexClient::global().get_mangadex_image_support().await;
                         match response {
                             Ok(bytes) => {
-                                let dyn_img = Reader::new(Cursor::new(bytes))
-                                    .with_guessed_format()
-                                    .unwrap();
-
-                                let maybe_decoded = dyn_img.decode();
-                                match maybe_decoded {
-                                    Ok(image) => {
-                                        tx.send(HomeEvents::LoadSupportImage(Some(image))).ok();
-                                    }
-                                    Err(_) => {
-                                        tx.send(HomeEvents::LoadSupportImage(None)).ok();
-                                    }
-                                };
+                                tx.send(HomeEvents::LoadSupportImage(decode_image(bytes)))
+                                    .ok();
                             }
                             Err(_) => {
                                 tx.send(HomeEvents::LoadSupportImage(None)).ok();
@@ -276,7 +358,7 @@ impl Home {
                 }
                 HomeEvents::LoadSupportImage(maybe_image) => {
                     if let Some(image) = maybe_image {
-                        let protocol = PICKER.unwrap().new_resize_protocol(image);
+                        let protocol = new_image_protocol(image);
                         self.support_image = Some(protocol);
                     }
                 }
@@ -289,7 +371,7 @@ impl Home {
             Some(response) => {
                 self.carrousel_popular_mangas = PopularMangaCarrousel::from_response(response);
 
-                if PICKER.is_some() {
+                if picker().is_some() {
                     self.local_event_tx
                         .send(HomeEvents::SearchPopularMangasCover)
                         .ok();
@@ -350,19 +432,8 @@ impl Home {
                             .get_cover_for_manga(&manga_id, &file_name)
                             .await;
 
-                        if let Ok(bytes) = response {
-                            let dyn_img = Reader::new(Cursor::new(bytes))
-                                .with_guessed_format()
-                                .unwrap();
-
-                            let maybe_decoded = dyn_img.decode();
-
-                            if let Ok(decoded) = maybe_decoded {
-                                let protocol = PICKER.unwrap().new_resize_protocol(decoded);
-                                tx.send(HomeEvents::LoadCover(Some(protocol), manga_id))
-                                    .ok();
-                            }
-                        }
+                        let cover = response.ok().and_then(decode_cover).map(new_image_protocol);
+                        tx.send(HomeEvents::LoadCover(cover, manga_id)).ok();
                     });
                 }
                 None => {
@@ -393,7 +464,7 @@ impl Home {
         match maybe_response {
             Some(response) => {
                 self.carrousel_recently_added = RecentlyAddedCarrousel::from_response(response);
-                if PICKER.is_some() {
+                if picker().is_some() {
                     self.local_event_tx
                         .send(HomeEvents::SearchRecentlyCover)
                         .ok();
@@ -413,7 +484,8 @@ impl Home {
                 Some(file_name) => {
                     let file_name = file_name.clone();
 
-                    search_manga_cover(file_name, manga_id, &mut self.tasks, tx);
+                    let aspect = MangaTuiConfig::get().carousel_cover_aspect;
+                    search_manga_cover(file_name, manga_id, aspect, &mut self.tasks, tx);
                 }
                 None => {
                     tx.send(HomeEvents::LoadRecentlyAddedMangasCover(None, manga_id))
@@ -471,6 +543,7 @@ impl Home {
 
         Block::bordered()
             .title(instructions)
+            .border_style(HomeSection::RecentlyAdded.border_style(self.focused_section))
             .render(recently_added_mangas_area, buf);
 
         StatefulWidget::render(
@@ -531,17 +604,37 @@ impl Home {
             }
             KeyCode::Char('l') | KeyCode::Right => {
                 self.local_action_tx
-                    .send(HomeActions::SelectNextRecentlyAddedManga)
+                    .send(HomeActions::SelectNextInSection)
                     .ok();
             }
             KeyCode::Char('h') | KeyCode::Left => {
                 self.local_action_tx
-                    .send(HomeActions::SelectPreviousRecentlyAddedManga)
+                    .send(HomeActions::SelectPreviousInSection)
                     .ok();
             }
             KeyCode::Enter => {
                 self.local_action_tx
-                    .send(HomeActions::GoToRecentlyAddedMangaPage)
+                    .send(HomeActions::GoToSectionMangaPage)
+                    .ok();
+            }
+            KeyCode::Tab => {
+                self.local_action_tx
+                    .send(HomeActions::FocusNextSection)
+                    .ok();
+            }
+            KeyCode::BackTab => {
+                self.local_action_tx
+                    .send(HomeActions::FocusPreviousSection)
+                    .ok();
+            }
+            KeyCode::Char('1') => {
+                self.local_action_tx
+                    .send(HomeActions::FocusSection(HomeSection::Popular))
+                    .ok();
+            }
+            KeyCode::Char('2') => {
+                self.local_action_tx
+                    .send(HomeActions::FocusSection(HomeSection::RecentlyAdded))
                     .ok();
             }
             KeyCode::Char('m') => {
@@ -1,21 +1,39 @@
 use crate::backend::database::{get_chapters_history_status, save_history, SetChapterDownloaded};
+use crate::backend::database::{
+    get_library_chapters, get_manga_bookmarks, prune_history, save_manga_tags, set_manga_completed,
+    PageBookmark,
+};
+use crate::backend::database::{
+    is_manga_in_reading_history, save_plan_to_read, MangaPlanToReadSave,
+};
 use crate::backend::database::{set_chapter_downloaded, MangaReadingHistorySave};
-use crate::backend::download::{download_chapter, DownloadChapter};
+use crate::backend::database::{
+    set_chapters_read, set_manga_chapters_read, set_manga_chapters_unread, ChapterToMark,
+    MangaInsert,
+};
+use crate::backend::download::{download_chapter, DownloadChapter, DownloadHandler};
 use crate::backend::error_log::{self, write_to_error_log};
-use crate::backend::fetch::{MangadexClient, ITEMS_PER_PAGE_CHAPTERS};
+use crate::backend::fetch::{FetchError, MangadexClient, ITEMS_PER_PAGE_CHAPTERS};
 use crate::backend::filter::Languages;
 use crate::backend::tui::Events;
-use crate::backend::{ChapterResponse, MangaStatisticsResponse, Statistics};
+use crate::backend::{ChapterData, ChapterResponse, MangaStatisticsResponse, Statistics};
 use crate::common::Manga;
+use crate::config::MangaTuiConfig;
 use crate::global::{ERROR_STYLE, INSTRUCTIONS_STYLE};
-use crate::utils::{set_status_style, set_tags_style};
+use crate::utils::{
+    centered_rect, decode_cover, display_publication_date, render_cover_frame, set_status_style,
+    set_tag_group_style, set_tags_style,
+};
+use crate::view::pages::reader::ChapterToRead;
 use crate::view::widgets::manga::{ChapterItem, ChaptersListWidget};
 use crate::view::widgets::Component;
-use crate::PICKER;
+use crate::{new_image_protocol, picker};
 use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
 use ratatui::{prelude::*, widgets::*};
 use ratatui_image::protocol::StatefulProtocol;
 use ratatui_image::{Resize, StatefulImage};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use strum::Display;
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinSet;
@@ -35,6 +53,7 @@ pub enum PageState {
 
 pub enum MangaPageActions {
     DownloadChapter,
+    DownloadMissingChapters,
     ScrollChapterDown,
     ScrollChapterUp,
     ToggleOrder,
@@ -46,6 +65,40 @@ pub enum MangaPageActions {
     GoMangasArtist,
     SearchNextChapterPage,
     SearchPreviousChapterPage,
+    ToggleExternalLinks,
+    ScrollDownExternalLinks,
+    ScrollUpExternalLinks,
+    OpenExternalLink,
+    ReadFirstUnreadChapter,
+    AskMarkAll(MarkAllAs),
+    ConfirmMarkAll,
+    CancelMarkAll,
+    ToggleCoverGallery,
+    ScrollDownCovers,
+    ScrollUpCovers,
+    SetCoverAsPreview,
+    RefreshChapters,
+    ToggleChapterSelection,
+    ToggleBookmarks,
+    ScrollDownBookmarks,
+    ScrollUpBookmarks,
+    ReadBookmark,
+}
+
+/// Marking every chapter of a manga at once, it has to be confirmed first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkAllAs {
+    Read,
+    Unread,
+}
+
+impl MarkAllAs {
+    fn as_human_readable(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Unread => "unread",
+        }
+    }
 }
 
 pub enum MangaPageEvents {
@@ -60,14 +113,46 @@ pub enum MangaPageEvents {
     ReadSuccesful,
     LoadChapters(Option<ChapterResponse>),
     LoadStatistics(Option<MangaStatisticsResponse>),
+    SearchCover,
+    LoadCover(Option<Box<dyn StatefulProtocol>>),
+    LoadCoverList(Vec<CoverArt>),
+    CoverListError,
+    /// The file name of the cover and its image
+    LoadGalleryCover(String, Option<Box<dyn StatefulProtocol>>),
+}
+
+pub struct CoverArt {
+    volume: Option<String>,
+    file_name: String,
 }
 
-#[derive(Display, Default, Clone, Copy)]
+/// Every cover of the manga, it's fetched the first time the gallery is opened and only the
+/// selected cover is loaded
+struct CoverGallery {
+    covers: Vec<CoverArt>,
+    state: ListState,
+    image: Option<Box<dyn StatefulProtocol>>,
+}
+
+impl DownloadHandler for MangaPageEvents {
+    fn progress(progress: f64, chapter_id: String) -> Self {
+        Self::SetDownloadProgress(progress, chapter_id)
+    }
+    fn finished(chapter_id: String) -> Self {
+        Self::ChapterFinishedDownloading(chapter_id)
+    }
+    fn failed(chapter_id: String) -> Self {
+        Self::DownloadError(chapter_id)
+    }
+}
+
+#[derive(Display, Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ChapterOrder {
     #[strum(to_string = "asc")]
+    #[default]
     Ascending,
     #[strum(to_string = "desc")]
-    #[default]
     Descending,
 }
 
@@ -78,6 +163,20 @@ impl ChapterOrder {
             ChapterOrder::Descending => ChapterOrder::Ascending,
         }
     }
+
+    fn as_human_readable(self) -> &'static str {
+        match self {
+            ChapterOrder::Ascending => "Ascending",
+            ChapterOrder::Descending => "Descending",
+        }
+    }
+
+    fn as_arrow(self) -> &'static str {
+        match self {
+            ChapterOrder::Ascending => "↑",
+            ChapterOrder::Descending => "↓",
+        }
+    }
 }
 
 pub struct MangaPage {
@@ -95,6 +194,25 @@ pub struct MangaPage {
     tasks: JoinSet<()>,
     available_languages_state: ListState,
     is_list_languages_open: bool,
+    is_external_links_open: bool,
+    external_links_state: ListState,
+    /// Read the first chapter which is not read yet as soon as the chapters are loaded
+    continue_reading: bool,
+    /// Chapter and page read as soon as the chapters are loaded, set when a session is restored
+    resumed_chapter: Option<(String, usize)>,
+    /// Set while the confirmation to mark every chapter is shown
+    mark_all_confirmation: Option<MarkAllAs>,
+    is_cover_gallery_open: bool,
+    cover_gallery: Option<CoverGallery>,
+    /// The covers could not be fetched, nothing is kept so opening the gallery again retries
+    is_cover_gallery_error: bool,
+    /// Set while the chapters are being fetched again with `<R>`
+    chapters_refresh: Option<ChaptersRefresh>,
+    /// Set while the pages bookmarked in the reader are listed with `<p>`
+    bookmarks: Option<Vec<PageBookmark>>,
+    bookmarks_state: ListState,
+    /// Page the next chapter is opened at, it's set when a bookmark is opened
+    start_page: usize,
 }
 
 struct MangaStatistics {
@@ -108,8 +226,19 @@ impl MangaStatistics {
     }
 }
 
+/// What the chapter list looked like before it was refreshed, to keep the same chapter selected and
+/// tell how many chapters came out since
+struct ChaptersRefresh {
+    selected_chapter_id: Option<String>,
+    selected_index: Option<usize>,
+    total_result: u32,
+}
+
 struct ChaptersData {
     state: tui_widget_list::ListState,
+    /// Ids of the chapters selected with `<Space>`, while there are any downloading and marking
+    /// chapters apply to them instead of the chapter under the cursor or the whole manga
+    selection: HashSet<String>,
     widget: ChaptersListWidget,
     page: u32,
     total_result: u32,
@@ -119,6 +248,7 @@ impl MangaPage {
     pub fn new(
         manga: Manga,
         image_state: Option<Box<dyn StatefulProtocol>>,
+        chapter_order: ChapterOrder,
         global_event_tx: UnboundedSender<Events>,
     ) -> Self {
         let (local_action_tx, local_action_rx) = mpsc::unbounded_channel::<MangaPageActions>();
@@ -127,6 +257,11 @@ impl MangaPage {
         local_event_tx.send(MangaPageEvents::SearchChapters).ok();
         local_event_tx.send(MangaPageEvents::FethStatistics).ok();
 
+        // The manga may come without a cover, for example when it's opened from a suggestion
+        if image_state.is_none() && picker().is_some() {
+            local_event_tx.send(MangaPageEvents::SearchCover).ok();
+        }
+
         Self {
             manga,
             image_state,
@@ -136,12 +271,24 @@ impl MangaPage {
             local_event_tx,
             local_event_rx,
             chapters: None,
-            chapter_order: ChapterOrder::default(),
+            chapter_order,
             state: PageState::SearchingChapters,
             statistics: None,
             tasks: JoinSet::new(),
             available_languages_state: ListState::default(),
             is_list_languages_open: false,
+            is_external_links_open: false,
+            external_links_state: ListState::default(),
+            continue_reading: false,
+            resumed_chapter: None,
+            mark_all_confirmation: None,
+            is_cover_gallery_open: false,
+            cover_gallery: None,
+            is_cover_gallery_error: false,
+            chapters_refresh: None,
+            bookmarks: None,
+            bookmarks_state: ListState::default(),
+            start_page: 0,
         }
     }
     fn render_cover(&mut self, area: Rect, buf: &mut Buffer) {
@@ -150,12 +297,13 @@ impl MangaPage {
 
         Paragraph::new(format!(
             " \n Publication date : \n {}",
-            self.manga.created_at
+            display_publication_date(&self.manga.created_at)
         ))
         .render(more_details_area, buf);
 
         match self.image_state.as_mut() {
             Some(state) => {
+                let cover_area = render_cover_frame(cover_area, buf);
                 let image = StatefulImage::new(None).resize(Resize::Fit(None));
                 StatefulWidget::render(image, cover_area, buf, state);
             }
@@ -196,6 +344,8 @@ impl MangaPage {
                 author_and_artist,
                 " | More about author/artist ".into(),
                 go_to_author_artist_instructions,
+                " | External links ".into(),
+                Span::raw("<e>").style(*INSTRUCTIONS_STYLE),
             ]))
             .render(manga_information_area, buf);
 
@@ -209,12 +359,7 @@ impl MangaPage {
             Layout::vertical([Constraint::Percentage(20), Constraint::Percentage(80)]).margin(1);
         let [tags_area, description_area] = layout.areas(area);
 
-        let mut tags: Vec<Span<'_>> = self
-            .manga
-            .tags
-            .iter()
-            .map(|tag| set_tags_style(tag))
-            .collect();
+        let mut tags: Vec<Span<'_>> = self.manga.tags.iter().map(set_tag_group_style).collect();
 
         tags.push(set_status_style(&self.manga.publication_demographic));
 
@@ -245,18 +390,39 @@ impl MangaPage {
                 let total = format!("Total chapters {}", chapters.total_result);
 
                 let mut chapter_instructions = vec![
+                    format!(
+                        "{} {} | ",
+                        self.chapter_order.as_arrow(),
+                        self.chapter_order.as_human_readable()
+                    )
+                    .into(),
                     "Scroll Down/Up ".into(),
                     Span::raw(" <j>/<k> ").style(*INSTRUCTIONS_STYLE),
                     " Download chapter ".into(),
                     Span::raw(" <d> ").style(*INSTRUCTIONS_STYLE),
+                    " Download new ".into(),
+                    Span::raw(" <D> ").style(*INSTRUCTIONS_STYLE),
+                    " Select ".into(),
+                    Span::raw(" <Space> ").style(*INSTRUCTIONS_STYLE),
                 ];
 
-                if PICKER.is_some() {
+                if picker().is_some() {
                     chapter_instructions.push(" Read chapter ".into());
                     chapter_instructions.push(Span::raw(" <r> ").style(*INSTRUCTIONS_STYLE));
+                    chapter_instructions.push(" First unread ".into());
+                    chapter_instructions.push(Span::raw(" <n> ").style(*INSTRUCTIONS_STYLE));
+                    chapter_instructions.push(" Covers ".into());
+                    chapter_instructions.push(Span::raw(" <g> ").style(*INSTRUCTIONS_STYLE));
                 }
 
-                let pagination_instructions: Vec<Span<'_>> = vec![
+                chapter_instructions.push(" Mark all read/unread ".into());
+                chapter_instructions.push(Span::raw(" <m>/<M> ").style(*INSTRUCTIONS_STYLE));
+                chapter_instructions.push(" Refresh ".into());
+                chapter_instructions.push(Span::raw(" <R> ").style(*INSTRUCTIONS_STYLE));
+                chapter_instructions.push(" Bookmarks ".into());
+                chapter_instructions.push(Span::raw(" <p> ").style(*INSTRUCTIONS_STYLE));
+
+                let mut pagination_instructions: Vec<Span<'_>> = vec![
                     page.into(),
                     " | ".into(),
                     total.into(),
@@ -266,17 +432,27 @@ impl MangaPage {
                     Span::raw("<b>").style(*INSTRUCTIONS_STYLE),
                 ];
 
+                if !chapters.selection.is_empty() {
+                    pagination_instructions.push(
+                        format!(
+                            " | {} selected, <d> and <m>/<M> apply to them",
+                            chapters.selection.len()
+                        )
+                        .into(),
+                    );
+                }
+
                 Block::bordered()
                     .title_top(Line::from(chapter_instructions))
                     .title_bottom(Line::from(pagination_instructions))
                     .render(area, buf);
 
-                StatefulWidget::render(
-                    chapters.widget.clone(),
-                    chapters_area,
-                    buf,
-                    &mut chapters.state,
-                );
+                let mut widget = chapters.widget.clone();
+                for chapter in widget.chapters.iter_mut() {
+                    chapter.is_selected = chapters.selection.contains(&chapter.id);
+                }
+
+                StatefulWidget::render(widget, chapters_area, buf, &mut chapters.state);
 
                 self.render_sorting_buttons(sorting_buttons_area, buf);
             }
@@ -301,13 +477,7 @@ impl MangaPage {
         let layout = Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]);
         let [sorting_area, language_area] = layout.areas(area);
 
-        let order_title = format!(
-            "Order: {} ",
-            match self.chapter_order {
-                ChapterOrder::Descending => "Descending",
-                ChapterOrder::Ascending => "Ascending",
-            }
-        );
+        let order_title = format!("Order: {} ", self.chapter_order.as_human_readable());
 
         Paragraph::new(Line::from(vec![
             order_title.into(),
@@ -358,8 +528,522 @@ impl MangaPage {
         }
     }
 
+    fn render_external_links(&mut self, area: Rect, buf: &mut Buffer) {
+        let popup_area = centered_rect(area, 40, 50);
+
+        Clear.render(popup_area, buf);
+
+        let instructions = Line::from(vec![
+            "Close".into(),
+            Span::raw(" <Esc> ").style(*INSTRUCTIONS_STYLE),
+            "Up/Down".into(),
+            Span::raw(" <k><j> ").style(*INSTRUCTIONS_STYLE),
+            "Open in browser ".into(),
+            Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        let block = Block::bordered()
+            .title_top("External links")
+            .title_bottom(instructions);
+
+        if self.manga.links.is_empty() {
+            Paragraph::new("This manga has no external links")
+                .block(block)
+                .render(popup_area, buf);
+            return;
+        }
+
+        let links = List::new(self.manga.links.iter().map(|link| {
+            Line::from(vec![
+                format!("{} ", link.name).bold(),
+                link.url.as_str().into(),
+            ])
+        }))
+        .block(block)
+        .highlight_style(Style::default().on_blue());
+
+        StatefulWidget::render(links, popup_area, buf, &mut self.external_links_state);
+    }
+
+    fn render_bookmarks(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(bookmarks) = self.bookmarks.as_ref() else {
+            return;
+        };
+
+        let popup_area = centered_rect(area, 40, 50);
+
+        Clear.render(popup_area, buf);
+
+        let mut instructions = vec![
+            "Close".into(),
+            Span::raw(" <Esc> ").style(*INSTRUCTIONS_STYLE),
+            "Up/Down".into(),
+            Span::raw(" <k><j> ").style(*INSTRUCTIONS_STYLE),
+        ];
+
+        if picker().is_some() {
+            instructions.push("Read ".into());
+            instructions.push(Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE));
+        }
+
+        let block = Block::bordered()
+            .title_top("Bookmarked pages")
+            .title_bottom(Line::from(instructions));
+
+        if bookmarks.is_empty() {
+            Paragraph::new(
+                "No bookmarks yet, press <m> while reading a chapter to bookmark a page",
+            )
+            .wrap(Wrap { trim: true })
+            .block(block)
+            .render(popup_area, buf);
+            return;
+        }
+
+        let items = List::new(bookmarks.iter().map(|bookmark| {
+            Line::from(vec![
+                format!("Ch. {} ", bookmark.chapter_number).bold(),
+                format!("{} ", bookmark.chapter_title).into(),
+                format!("Page {}", bookmark.page + 1).dark_gray(),
+            ])
+        }))
+        .block(block)
+        .highlight_style(Style::default().on_blue());
+
+        StatefulWidget::render(items, popup_area, buf, &mut self.bookmarks_state);
+    }
+
+    fn render_mark_all_confirmation(&mut self, mark_as: MarkAllAs, area: Rect, buf: &mut Buffer) {
+        let popup_area = centered_rect(area, 40, 20);
+
+        Clear.render(popup_area, buf);
+
+        let instructions = Line::from(vec![
+            "Yes ".into(),
+            Span::raw("<y>").style(*INSTRUCTIONS_STYLE),
+            " No ".into(),
+            Span::raw("<n>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        // The reading history doesn't know the language of the chapters, so marking them as unread
+        // applies to every language
+        let language = self.get_current_selected_language();
+        let selected = self
+            .chapters
+            .as_ref()
+            .map_or(0, |chapters| chapters.selection.len());
+        let question = match mark_as {
+            _ if selected > 0 => format!(
+                "Mark the {selected} selected chapters as {}?",
+                mark_as.as_human_readable()
+            ),
+            MarkAllAs::Read => format!(
+                "Mark every chapter of {} in {} as read?",
+                self.manga.title,
+                language.as_human_readable()
+            ),
+            MarkAllAs::Unread => format!("Mark every chapter of {} as unread?", self.manga.title),
+        };
+
+        Paragraph::new(question)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title_bottom(instructions))
+            .render(popup_area, buf);
+    }
+
+    fn render_cover_gallery(&mut self, area: Rect, buf: &mut Buffer) {
+        let popup_area = centered_rect(area, 60, 70);
+
+        Clear.render(popup_area, buf);
+
+        let instructions = Line::from(vec![
+            "Close".into(),
+            Span::raw(" <Esc> ").style(*INSTRUCTIONS_STYLE),
+            "Up/Down".into(),
+            Span::raw(" <k><j> ").style(*INSTRUCTIONS_STYLE),
+            "Use as preview ".into(),
+            Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        let block = Block::bordered()
+            .title_top("Covers")
+            .title_bottom(instructions);
+
+        let Some(gallery) = self.cover_gallery.as_mut() else {
+            let message = if self.is_cover_gallery_error {
+                Span::raw("The covers could not be loaded, open the gallery again to retry")
+                    .style(*ERROR_STYLE)
+            } else {
+                Span::raw("Searching covers")
+            };
+            Paragraph::new(message)
+                .wrap(Wrap { trim: true })
+                .block(block)
+                .render(popup_area, buf);
+            return;
+        };
+
+        if gallery.covers.is_empty() {
+            Paragraph::new("No covers were found for this manga")
+                .block(block)
+                .render(popup_area, buf);
+            return;
+        }
+
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        let [list_area, cover_area] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .areas(inner);
+
+        let list_title = if gallery.covers.len() == 1 {
+            "This manga has only one cover".to_string()
+        } else {
+            format!("{} covers", gallery.covers.len())
+        };
+
+        let covers = List::new(
+            gallery
+                .covers
+                .iter()
+                .map(|cover| match cover.volume.as_ref() {
+                    Some(volume) => format!("Volume {volume}"),
+                    None => "No volume".to_string(),
+                }),
+        )
+        .block(Block::bordered().title(list_title))
+        .highlight_style(Style::default().on_blue());
+
+        StatefulWidget::render(covers, list_area, buf, &mut gallery.state);
+
+        match gallery.image.as_mut() {
+            Some(image) => {
+                let cover_area = render_cover_frame(cover_area, buf);
+                let cover = StatefulImage::new(None).resize(Resize::Fit(None));
+                StatefulWidget::render(cover, cover_area, buf, image);
+            }
+            None => Block::bordered()
+                .title("Loading cover")
+                .render(cover_area, buf),
+        }
+    }
+
+    fn toggle_cover_gallery(&mut self) {
+        self.is_cover_gallery_open = !self.is_cover_gallery_open;
+        if self.is_cover_gallery_open && self.cover_gallery.is_none() {
+            self.is_cover_gallery_error = false;
+            self.search_cover_list();
+        }
+    }
+
+    fn search_cover_list(&mut self) {
+        let manga_id = self.manga.id.clone();
+        let tx = self.local_event_tx.clone();
+        self.tasks.spawn(async move {
+            match MangadexClient::global().get_manga_covers(&manga_id).await {
+                Ok(response) => {
+                    let covers = response
+                        .data
+                        .into_iter()
+                        .map(|cover| CoverArt {
+                            volume: cover.attributes.volume,
+                            file_name: cover.attributes.file_name,
+                        })
+                        .collect();
+                    tx.send(MangaPageEvents::LoadCoverList(covers)).ok();
+                }
+                Err(e) => {
+                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                    tx.send(MangaPageEvents::CoverListError).ok();
+                }
+            }
+        });
+    }
+
+    fn load_cover_list(&mut self, covers: Vec<CoverArt>) {
+        let mut state = ListState::default();
+        if !covers.is_empty() {
+            state.select(Some(0));
+        }
+        self.cover_gallery = Some(CoverGallery {
+            covers,
+            state,
+            image: None,
+        });
+        self.search_gallery_cover();
+    }
+
+    fn search_gallery_cover(&mut self) {
+        let Some(file_name) = self.cover_gallery.as_ref().and_then(|gallery| {
+            gallery
+                .state
+                .selected()
+                .and_then(|index| gallery.covers.get(index))
+                .map(|cover| cover.file_name.clone())
+        }) else {
+            return;
+        };
+
+        let manga_id = self.manga.id.clone();
+        let tx = self.local_event_tx.clone();
+        self.tasks.spawn(async move {
+            let response = MangadexClient::global()
+                .get_cover_for_manga(&manga_id, &file_name)
+                .await;
+
+            let cover = match response {
+                Ok(bytes) => decode_cover(bytes).map(new_image_protocol),
+                Err(e) => {
+                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                    None
+                }
+            };
+            tx.send(MangaPageEvents::LoadGalleryCover(file_name, cover))
+                .ok();
+        });
+    }
+
+    /// Covers which arrive after another one was selected are ignored
+    fn load_gallery_cover(&mut self, file_name: String, cover: Option<Box<dyn StatefulProtocol>>) {
+        if let Some(gallery) = self.cover_gallery.as_mut() {
+            let is_selected = gallery
+                .state
+                .selected()
+                .and_then(|index| gallery.covers.get(index))
+                .is_some_and(|selected| selected.file_name == file_name);
+
+            if is_selected {
+                gallery.image = cover;
+            }
+        }
+    }
+
+    fn scroll_covers(&mut self, down: bool) {
+        let Some(gallery) = self.cover_gallery.as_mut() else {
+            return;
+        };
+        let Some(selected) = gallery.state.selected() else {
+            return;
+        };
+
+        let next = if down {
+            (selected + 1).min(gallery.covers.len().saturating_sub(1))
+        } else {
+            selected.saturating_sub(1)
+        };
+
+        if next != selected {
+            gallery.state.select(Some(next));
+            gallery.image = None;
+            self.search_gallery_cover();
+        }
+    }
+
+    fn set_cover_as_preview(&mut self) {
+        if let Some(image) = self
+            .cover_gallery
+            .as_mut()
+            .and_then(|gallery| gallery.image.take())
+        {
+            self.image_state = Some(image);
+            self.is_cover_gallery_open = false;
+        }
+    }
+
+    fn toggle_external_links(&mut self) {
+        self.is_external_links_open = !self.is_external_links_open;
+        if self.is_external_links_open && !self.manga.links.is_empty() {
+            self.external_links_state.select(Some(0));
+        }
+    }
+
+    fn scroll_external_links_down(&mut self) {
+        let next = self
+            .external_links_state
+            .selected()
+            .map_or(0, |index| index + 1);
+        if next < self.manga.links.len() {
+            self.external_links_state.select(Some(next));
+        }
+    }
+
+    fn scroll_external_links_up(&mut self) {
+        if let Some(index) = self.external_links_state.selected() {
+            self.external_links_state
+                .select(Some(index.saturating_sub(1)));
+        }
+    }
+
+    fn open_external_link(&mut self) {
+        if let Some(link) = self
+            .external_links_state
+            .selected()
+            .and_then(|index| self.manga.links.get(index))
+        {
+            if let Err(e) = open::that(&link.url) {
+                write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+            }
+        }
+    }
+
+    fn toggle_bookmarks(&mut self) {
+        if self.bookmarks.take().is_some() {
+            return;
+        }
+
+        match get_manga_bookmarks(&self.manga.id) {
+            Ok(bookmarks) => {
+                self.bookmarks_state
+                    .select(if bookmarks.is_empty() { None } else { Some(0) });
+                self.bookmarks = Some(bookmarks);
+            }
+            Err(e) => write_to_error_log(error_log::ErrorType::FromError(Box::new(e))),
+        }
+    }
+
+    fn scroll_bookmarks_down(&mut self) {
+        let total = self.bookmarks.as_ref().map_or(0, Vec::len);
+        let next = self.bookmarks_state.selected().map_or(0, |index| index + 1);
+        if next < total {
+            self.bookmarks_state.select(Some(next));
+        }
+    }
+
+    fn scroll_bookmarks_up(&mut self) {
+        if let Some(index) = self.bookmarks_state.selected() {
+            self.bookmarks_state.select(Some(index.saturating_sub(1)));
+        }
+    }
+
+    /// The chapter of the bookmark is read from the chapter list, so it has to be on the page of
+    /// the list which is shown
+    fn read_bookmark(&mut self) {
+        let Some(bookmark) = self
+            .bookmarks_state
+            .selected()
+            .and_then(|index| self.bookmarks.as_ref()?.get(index))
+            .cloned()
+        else {
+            return;
+        };
+
+        let chapter_index = self.chapters.as_ref().and_then(|chapters| {
+            chapters
+                .widget
+                .chapters
+                .iter()
+                .position(|chapter| chapter.id == bookmark.chapter_id)
+        });
+
+        match chapter_index {
+            Some(index) => {
+                if let Some(chapters) = self.chapters.as_mut() {
+                    chapters.state.select(Some(index));
+                }
+                self.bookmarks = None;
+                self.start_page = bookmark.page;
+                self.read_chapter();
+            }
+            None => {
+                self.global_event_tx
+                    .send(Events::ShowNotification(format!(
+                        "Ch. {} is not on this page of the chapter list",
+                        bookmark.chapter_number
+                    )))
+                    .ok();
+            }
+        }
+    }
+
     fn handle_key_events(&mut self, key_event: KeyEvent) {
-        if self.is_list_languages_open {
+        if self.mark_all_confirmation.is_some() {
+            match key_event.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ConfirmMarkAll)
+                        .ok();
+                }
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    self.local_action_tx
+                        .send(MangaPageActions::CancelMarkAll)
+                        .ok();
+                }
+                _ => {}
+            }
+        } else if self.is_cover_gallery_open {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ScrollDownCovers)
+                        .ok();
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ScrollUpCovers)
+                        .ok();
+                }
+                KeyCode::Enter => {
+                    self.local_action_tx
+                        .send(MangaPageActions::SetCoverAsPreview)
+                        .ok();
+                }
+                KeyCode::Char('g') | KeyCode::Esc => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ToggleCoverGallery)
+                        .ok();
+                }
+                _ => {}
+            }
+        } else if self.is_external_links_open {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ScrollDownExternalLinks)
+                        .ok();
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ScrollUpExternalLinks)
+                        .ok();
+                }
+                KeyCode::Enter => {
+                    self.local_action_tx
+                        .send(MangaPageActions::OpenExternalLink)
+                        .ok();
+                }
+                KeyCode::Char('e') | KeyCode::Esc => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ToggleExternalLinks)
+                        .ok();
+                }
+                _ => {}
+            }
+        } else if self.bookmarks.is_some() {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ScrollDownBookmarks)
+                        .ok();
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ScrollUpBookmarks)
+                        .ok();
+                }
+                KeyCode::Enter if picker().is_some() => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ReadBookmark)
+                        .ok();
+                }
+                KeyCode::Char('p') | KeyCode::Esc => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ToggleBookmarks)
+                        .ok();
+                }
+                _ => {}
+            }
+        } else if self.is_list_languages_open {
             match key_event.code {
                 KeyCode::Char('j') | KeyCode::Down => {
                     self.local_action_tx
@@ -399,18 +1083,21 @@ impl MangaPage {
                         .send(MangaPageActions::ToggleOrder)
                         .ok();
                 }
-                KeyCode::Char('r') | KeyCode::Enter => {
-                    if PICKER.is_some() {
-                        self.local_action_tx
-                            .send(MangaPageActions::ReadChapter)
-                            .ok();
-                    }
+                KeyCode::Char('r') | KeyCode::Enter if picker().is_some() => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ReadChapter)
+                        .ok();
                 }
                 KeyCode::Char('d') => {
                     self.local_action_tx
                         .send(MangaPageActions::DownloadChapter)
                         .ok();
                 }
+                KeyCode::Char('D') => {
+                    self.local_action_tx
+                        .send(MangaPageActions::DownloadMissingChapters)
+                        .ok();
+                }
                 KeyCode::Char('c') => {
                     self.local_action_tx
                         .send(MangaPageActions::GoMangasAuthor)
@@ -421,19 +1108,59 @@ impl MangaPage {
                         .send(MangaPageActions::GoMangasArtist)
                         .ok();
                 }
-                KeyCode::Char('l') | KeyCode::Esc => {
+                KeyCode::Char('l') | KeyCode::Esc => {
+                    self.local_action_tx
+                        .send(MangaPageActions::OpenAvailableLanguagesList)
+                        .ok();
+                }
+                KeyCode::Char('w') => {
+                    self.local_action_tx
+                        .send(MangaPageActions::SearchNextChapterPage)
+                        .ok();
+                }
+                KeyCode::Char('b') => {
+                    self.local_action_tx
+                        .send(MangaPageActions::SearchPreviousChapterPage)
+                        .ok();
+                }
+                KeyCode::Char('e') => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ToggleExternalLinks)
+                        .ok();
+                }
+                KeyCode::Char('g') if picker().is_some() => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ToggleCoverGallery)
+                        .ok();
+                }
+                KeyCode::Char('m') => {
+                    self.local_action_tx
+                        .send(MangaPageActions::AskMarkAll(MarkAllAs::Read))
+                        .ok();
+                }
+                KeyCode::Char('M') => {
+                    self.local_action_tx
+                        .send(MangaPageActions::AskMarkAll(MarkAllAs::Unread))
+                        .ok();
+                }
+                KeyCode::Char('n') => {
+                    self.local_action_tx
+                        .send(MangaPageActions::ReadFirstUnreadChapter)
+                        .ok();
+                }
+                KeyCode::Char('R') => {
                     self.local_action_tx
-                        .send(MangaPageActions::OpenAvailableLanguagesList)
+                        .send(MangaPageActions::RefreshChapters)
                         .ok();
                 }
-                KeyCode::Char('w') => {
+                KeyCode::Char(' ') => {
                     self.local_action_tx
-                        .send(MangaPageActions::SearchNextChapterPage)
+                        .send(MangaPageActions::ToggleChapterSelection)
                         .ok();
                 }
-                KeyCode::Char('b') => {
+                KeyCode::Char('p') => {
                     self.local_action_tx
-                        .send(MangaPageActions::SearchPreviousChapterPage)
+                        .send(MangaPageActions::ToggleBookmarks)
                         .ok();
                 }
 
@@ -458,6 +1185,46 @@ impl MangaPage {
         }
     }
 
+    fn toggle_chapter_selection(&mut self) {
+        if let Some(chapters) = self.chapters.as_mut() {
+            let selected = chapters
+                .state
+                .selected
+                .and_then(|index| chapters.widget.chapters.get(index));
+
+            if let Some(chapter) = selected {
+                if !chapters.selection.remove(&chapter.id) {
+                    chapters.selection.insert(chapter.id.clone());
+                }
+            }
+        }
+    }
+
+    /// The selected chapters in the order they are listed, the selection is cleared since the
+    /// batch action is applied to them
+    fn take_selected_chapters(&mut self) -> Vec<usize> {
+        match self.chapters.as_mut() {
+            Some(chapters) => {
+                let selection = std::mem::take(&mut chapters.selection);
+                chapters
+                    .widget
+                    .chapters
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, chapter)| selection.contains(&chapter.id))
+                    .map(|(index, _)| index)
+                    .collect()
+            }
+            None => vec![],
+        }
+    }
+
+    fn has_selected_chapters(&self) -> bool {
+        self.chapters
+            .as_ref()
+            .is_some_and(|chapters| !chapters.selection.is_empty())
+    }
+
     fn toggle_chapter_order(&mut self) {
         self.chapter_order = self.chapter_order.toggle();
         self.search_chapters();
@@ -478,12 +1245,10 @@ impl MangaPage {
     fn get_current_selected_chapter_mut(&mut self) -> Option<&mut ChapterItem> {
         match self.chapters.as_mut() {
             Some(chapters_data) => match chapters_data.state.selected {
-                Some(selected_chapter_index) => {
-                    return chapters_data
-                        .widget
-                        .chapters
-                        .get_mut(selected_chapter_index)
-                }
+                Some(selected_chapter_index) => chapters_data
+                    .widget
+                    .chapters
+                    .get_mut(selected_chapter_index),
                 None => None,
             },
             None => None,
@@ -494,7 +1259,7 @@ impl MangaPage {
         match self.chapters.as_ref() {
             Some(chapters_data) => match chapters_data.state.selected {
                 Some(selected_chapter_index) => {
-                    return chapters_data.widget.chapters.get(selected_chapter_index)
+                    chapters_data.widget.chapters.get(selected_chapter_index)
                 }
                 None => None,
             },
@@ -502,7 +1267,42 @@ impl MangaPage {
         }
     }
 
+    /// The chapter after the selected one in reading order, so with the descending order it's
+    /// above it
+    fn next_chapter_index(&self) -> Option<usize> {
+        let chapters = self.chapters.as_ref()?;
+        chapters.widget.next_chapter(
+            chapters.state.selected?,
+            self.chapter_order,
+            MangaTuiConfig::get().preferred_groups_of(&self.manga.id),
+        )
+    }
+
+    /// Used from the end of chapter prompt of the reader
+    pub fn read_next_chapter(&mut self) {
+        if let Some(next) = self.next_chapter_index() {
+            if let Some(chapters) = self.chapters.as_mut() {
+                chapters.state.select(Some(next));
+            }
+            self.read_chapter();
+        }
+    }
+
     fn read_chapter(&mut self) {
+        let next_chapter = self
+            .next_chapter_index()
+            .and_then(|index| self.chapters.as_ref()?.widget.chapters.get(index))
+            .map(|chapter| {
+                if chapter.has_same_number && !chapter.scanlator.is_empty() {
+                    format!(
+                        "Ch. {} ({}) {}",
+                        chapter.chapter_number, chapter.scanlator, chapter.title
+                    )
+                } else {
+                    format!("Ch. {} {}", chapter.chapter_number, chapter.title)
+                }
+            });
+
         self.state = PageState::SearchingChapterData;
         match self.get_current_selected_chapter_mut() {
             Some(chapter_selected) => {
@@ -510,9 +1310,27 @@ impl MangaPage {
                 let id_chapter = chapter_selected.id.clone();
                 let chapter_title = chapter_selected.title.clone();
                 let is_read = chapter_selected.is_read;
+                let chapter_to_read = ChapterToRead {
+                    id: id_chapter.clone(),
+                    title: chapter_title.clone(),
+                    number: chapter_selected.chapter_number.clone(),
+                    scanlator: chapter_selected.scanlator.clone(),
+                    lang: chapter_selected
+                        .translated_language
+                        .as_human_readable()
+                        .to_string(),
+                    manga_id: self.manga.id.clone(),
+                    manga_title: self.manga.title.clone(),
+                    manga_img_url: self.manga.img_url.clone(),
+                    manga_original_language: self.manga.original_language.clone(),
+                    next_chapter,
+                    start_page: std::mem::take(&mut self.start_page),
+                };
                 let manga_id = self.manga.id.clone();
                 let title = self.manga.title.clone();
                 let img_url = self.manga.img_url.clone();
+                let tags: Vec<String> =
+                    self.manga.tags.iter().map(|tag| tag.name.clone()).collect();
                 let tx = self.global_event_tx.clone();
                 let local_tx = self.local_event_tx.clone();
 
@@ -523,12 +1341,31 @@ impl MangaPage {
                     match chapter_response {
                         Ok(response) => {
                             if !is_read {
+                                let is_first_read = MangaTuiConfig::get().auto_plan_to_read
+                                    && !is_manga_in_reading_history(&manga_id).unwrap_or(true);
+
                                 let save_response = save_history(MangaReadingHistorySave {
                                     id: &manga_id,
                                     title: &title,
                                     img_url: img_url.as_deref(),
                                     chapter_id: &id_chapter,
                                     chapter_title: &chapter_title,
+                                    tags: &tags,
+                                });
+
+                                let retention = MangaTuiConfig::get().history_retention();
+                                let save_response = save_response
+                                    .and_then(|()| prune_history(&manga_id, retention));
+
+                                let save_response = save_response.and_then(|()| {
+                                    if !is_first_read {
+                                        return Ok(());
+                                    }
+                                    save_plan_to_read(MangaPlanToReadSave {
+                                        id: &manga_id,
+                                        title: &title,
+                                        img_url: img_url.as_deref(),
+                                    })
                                 });
 
                                 if let Err(e) = save_response {
@@ -538,7 +1375,7 @@ impl MangaPage {
                                 }
                             }
 
-                            tx.send(Events::ReadChapter(response)).ok();
+                            tx.send(Events::ReadChapter(response, chapter_to_read)).ok();
                             local_tx.send(MangaPageEvents::CheckChapterStatus).ok();
                             local_tx.send(MangaPageEvents::ReadSuccesful).ok();
                         }
@@ -560,20 +1397,20 @@ impl MangaPage {
                 .manga
                 .available_languages
                 .get(index)
-                .unwrap_or(preferred_language),
+                .unwrap_or(&preferred_language),
             None => {
                 let maybe_preferred_language = self
                     .manga
                     .available_languages
                     .iter()
-                    .find(|lang| *lang == preferred_language);
+                    .find(|lang| **lang == preferred_language);
 
                 maybe_preferred_language.cloned().unwrap_or(
                     self.manga
                         .available_languages
                         .first()
                         .cloned()
-                        .unwrap_or(*preferred_language),
+                        .unwrap_or(preferred_language),
                 )
             }
         }
@@ -648,6 +1485,43 @@ impl MangaPage {
         });
     }
 
+    /// Build the cover again, used when the image protocol changes so it is displayed with the new
+    /// one
+    pub fn reload_images(&mut self) {
+        if picker().is_some() {
+            self.image_state = None;
+            self.local_event_tx.send(MangaPageEvents::SearchCover).ok();
+            if let Some(gallery) = self.cover_gallery.as_mut() {
+                gallery.image = None;
+                self.search_gallery_cover();
+            }
+        }
+    }
+
+    fn search_cover(&mut self) {
+        let Some(file_name) = self.manga.img_url.clone() else {
+            return;
+        };
+        let manga_id = self.manga.id.clone();
+        let tx = self.local_event_tx.clone();
+        self.tasks.spawn(async move {
+            let response = MangadexClient::global()
+                .get_cover_for_manga(&manga_id, &file_name)
+                .await;
+
+            match response {
+                Ok(bytes) => {
+                    let cover = decode_cover(bytes).map(new_image_protocol);
+                    tx.send(MangaPageEvents::LoadCover(cover)).ok();
+                }
+                Err(e) => {
+                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                    tx.send(MangaPageEvents::LoadCover(None)).ok();
+                }
+            };
+        });
+    }
+
     fn check_chapters_read(&mut self) {
         let history = get_chapters_history_status(&self.manga.id);
         match history {
@@ -666,15 +1540,246 @@ impl MangaPage {
                 write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
             }
         }
+
+        self.save_reading_stats();
+
+        if self.continue_reading {
+            self.continue_reading = false;
+            self.read_first_unread_chapter();
+        }
+
+        if let Some((chapter_id, page)) = self.resumed_chapter.take() {
+            self.resume_chapter(&chapter_id, page);
+        }
+    }
+
+    /// Mangas which finished publishing are completed once their latest chapter is read, it's
+    /// checked here so the mangas of the history from before the reading stats are counted too
+    fn save_reading_stats(&self) {
+        let tags: Vec<String> = self.manga.tags.iter().map(|tag| tag.name.clone()).collect();
+        if let Err(e) = save_manga_tags(&self.manga.id, &tags) {
+            write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+        }
+
+        if self.manga.status != "completed" {
+            return;
+        }
+
+        let Some(chapters) = self.chapters.as_ref() else {
+            return;
+        };
+
+        let is_last_page = chapters.page * ITEMS_PER_PAGE_CHAPTERS >= chapters.total_result;
+
+        let latest_chapter = match self.chapter_order {
+            ChapterOrder::Descending if chapters.page == 1 => chapters.widget.chapters.first(),
+            ChapterOrder::Ascending if is_last_page => chapters.widget.chapters.last(),
+            _ => None,
+        };
+
+        if latest_chapter.is_some_and(|chapter| chapter.is_read) {
+            if let Err(e) = set_manga_completed(&self.manga.id) {
+                write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+            }
+        }
+    }
+
+    /// The chapter can only be read from the chapter list, if it's not on the first page of the
+    /// list the manga page is shown instead
+    fn resume_chapter(&mut self, chapter_id: &str, page: usize) {
+        let chapter_index = self.chapters.as_ref().and_then(|chapters| {
+            chapters
+                .widget
+                .chapters
+                .iter()
+                .position(|chapter| chapter.id == chapter_id)
+        });
+
+        match chapter_index {
+            Some(index) => {
+                if let Some(chapters) = self.chapters.as_mut() {
+                    chapters.state.select(Some(index));
+                }
+                self.start_page = page;
+                self.read_chapter();
+            }
+            None => {
+                self.global_event_tx
+                    .send(Events::ShowNotification(
+                        "The chapter of the last session is not on this page of the chapter list"
+                            .to_string(),
+                    ))
+                    .ok();
+            }
+        }
+    }
+
+    /// Used when a session is restored, see `restore_session`
+    pub fn resume_reading(&mut self, chapter_id: String, page: usize) {
+        self.resumed_chapter = Some((chapter_id, page));
+    }
+
+    /// Used when the manga is opened to continue reading it, see `SelectAction::ContinueReading`
+    pub fn continue_reading(&mut self) {
+        self.continue_reading = true;
+    }
+
+    /// The chapters listed are checked in reading order against the reading history, so with the
+    /// descending order the list is walked from the bottom
+    fn read_first_unread_chapter(&mut self) {
+        let Some(chapters) = self.chapters.as_mut() else {
+            return;
+        };
+
+        let list = &chapters.widget.chapters;
+
+        let first_unread = match self.chapter_order {
+            ChapterOrder::Ascending => list.iter().position(|chapter| !chapter.is_read),
+            ChapterOrder::Descending => list.iter().rposition(|chapter| !chapter.is_read),
+        };
+
+        match first_unread {
+            Some(index) => {
+                let index = chapters.widget.preferred_chapter(
+                    index,
+                    MangaTuiConfig::get().preferred_groups_of(&self.manga.id),
+                );
+                chapters.state.select(Some(index));
+                if picker().is_some() {
+                    self.read_chapter();
+                }
+            }
+            None => {
+                self.global_event_tx
+                    .send(Events::ShowNotification(
+                        "Caught up! every chapter listed is read".to_string(),
+                    ))
+                    .ok();
+            }
+        }
+    }
+
+    /// Every chapter in the selected language is fetched, not only the ones listed, since the
+    /// chapters are paginated
+    fn mark_all_chapters(&mut self, mark_as: MarkAllAs) {
+        let manga_id = self.manga.id.clone();
+        let title = self.manga.title.clone();
+        let img_url = self.manga.img_url.clone();
+        let language = self.get_current_selected_language();
+        let tx = self.global_event_tx.clone();
+        let local_tx = self.local_event_tx.clone();
+
+        self.tasks.spawn(async move {
+            let result = match mark_as {
+                MarkAllAs::Read => match fetch_all_chapters(&manga_id, language).await {
+                    Ok(chapters) => set_manga_chapters_read(
+                        MangaInsert {
+                            id: &manga_id,
+                            title: &title,
+                            img_url: img_url.as_deref(),
+                        },
+                        &chapters,
+                    )
+                    .map_err(|e| error_log::ErrorType::FromError(Box::new(e))),
+                    Err(e) => Err(error_log::ErrorType::FromError(Box::new(e))),
+                },
+                MarkAllAs::Unread => set_manga_chapters_unread(&manga_id)
+                    .map_err(|e| error_log::ErrorType::FromError(Box::new(e))),
+            };
+
+            let notification = match result {
+                Ok(()) => format!(
+                    "Every chapter was marked as {}",
+                    mark_as.as_human_readable()
+                ),
+                Err(e) => {
+                    write_to_error_log(e);
+                    "The chapters could not be marked".to_string()
+                }
+            };
+
+            tx.send(Events::ShowNotification(notification)).ok();
+            local_tx.send(MangaPageEvents::CheckChapterStatus).ok();
+        });
+    }
+
+    fn mark_selected_chapters(&mut self, mark_as: MarkAllAs) {
+        let indexes = self.take_selected_chapters();
+        let Some(chapters) = self.chapters.as_ref() else {
+            return;
+        };
+
+        let selected: Vec<ChapterToMark> = indexes
+            .into_iter()
+            .filter_map(|index| chapters.widget.chapters.get(index))
+            .map(|chapter| ChapterToMark {
+                id: chapter.id.clone(),
+                title: chapter.title.clone(),
+            })
+            .collect();
+
+        let manga_id = self.manga.id.clone();
+        let title = self.manga.title.clone();
+        let img_url = self.manga.img_url.clone();
+        let tx = self.global_event_tx.clone();
+        let local_tx = self.local_event_tx.clone();
+
+        self.tasks.spawn(async move {
+            let result = set_chapters_read(
+                MangaInsert {
+                    id: &manga_id,
+                    title: &title,
+                    img_url: img_url.as_deref(),
+                },
+                &selected,
+                mark_as == MarkAllAs::Read,
+            );
+
+            let notification = match result {
+                Ok(()) => format!(
+                    "{} chapters were marked as {}",
+                    selected.len(),
+                    mark_as.as_human_readable()
+                ),
+                Err(e) => {
+                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                    "The chapters could not be marked".to_string()
+                }
+            };
+
+            tx.send(Events::ShowNotification(notification)).ok();
+            local_tx.send(MangaPageEvents::CheckChapterStatus).ok();
+        });
     }
 
     fn download_chapter_selected(&mut self) {
+        let indexes = if self.has_selected_chapters() {
+            self.take_selected_chapters()
+        } else {
+            self.chapters
+                .as_ref()
+                .and_then(|chapters| chapters.state.selected)
+                .into_iter()
+                .collect()
+        };
+
+        for index in indexes {
+            self.download_chapter_at(index);
+        }
+    }
+
+    fn download_chapter_at(&mut self, index: usize) {
         let manga_id = self.manga.id.clone();
         let manga_title = self.manga.title.clone();
         let tx = self.local_event_tx.clone();
 
         self.state = PageState::DownloadingChapters;
-        if let Some(chapter) = self.get_current_selected_chapter_mut() {
+        let chapter = self
+            .chapters
+            .as_mut()
+            .and_then(|chapters| chapters.widget.chapters.get_mut(index));
+
+        if let Some(chapter) = chapter {
             if chapter.download_loading_state.is_some() {
                 return;
             }
@@ -742,6 +1847,154 @@ impl MangaPage {
             }
         }
     }
+    /// Every chapter in the selected language is fetched and the ones which are already in the
+    /// library are skipped, the rest are downloaded one by one
+    fn download_missing_chapters(&mut self) {
+        let manga_id = self.manga.id.clone();
+        let manga_title = self.manga.title.clone();
+        let language = self.get_current_selected_language();
+        let tx = self.global_event_tx.clone();
+        let local_tx = self.local_event_tx.clone();
+
+        self.tasks.spawn(async move {
+            let chapters = match fetch_every_chapter(&manga_id, language).await {
+                Ok(chapters) => chapters,
+                Err(e) => {
+                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                    tx.send(Events::ShowNotification(
+                        "The chapters could not be fetched".to_string(),
+                    ))
+                    .ok();
+                    return;
+                }
+            };
+
+            let downloaded: HashSet<String> = match get_library_chapters(&manga_id) {
+                Ok(library) => library
+                    .into_iter()
+                    .map(|chapter| chapter.chapter_id)
+                    .collect(),
+                Err(e) => {
+                    write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                    HashSet::new()
+                }
+            };
+
+            let missing: Vec<ChapterData> = chapters
+                .into_iter()
+                .filter(|chapter| !downloaded.contains(&chapter.id))
+                .collect();
+
+            if missing.is_empty() {
+                tx.send(Events::ShowNotification(
+                    "Every chapter is already downloaded".to_string(),
+                ))
+                .ok();
+                return;
+            }
+
+            let total = missing.len();
+            let mut failed = 0;
+
+            for (index, chapter) in missing.into_iter().enumerate() {
+                tx.send(Events::ShowNotification(format!(
+                    "Downloading {} of {total} new chapters",
+                    index + 1
+                )))
+                .ok();
+
+                let title = chapter.attributes.title.clone().unwrap_or_default();
+                let number = chapter
+                    .attributes
+                    .chapter
+                    .clone()
+                    .unwrap_or("0".to_string());
+                let scanlator = chapter
+                    .relationships
+                    .iter()
+                    .find(|rel| rel.type_field == "scanlation_group")
+                    .and_then(|rel| rel.attributes.as_ref())
+                    .map(|attributes| attributes.name.to_string())
+                    .unwrap_or_default();
+                let lang = Languages::try_from_iso_code(&chapter.attributes.translated_language)
+                    .unwrap_or(language)
+                    .as_human_readable()
+                    .to_string();
+
+                let download = match MangadexClient::global()
+                    .get_chapter_pages(&chapter.id)
+                    .await
+                {
+                    Ok(res) => {
+                        let download = download_chapter(
+                            DownloadChapter {
+                                id_chapter: &chapter.id,
+                                manga_id: &manga_id,
+                                manga_title: &manga_title,
+                                title: &title,
+                                number: &number,
+                                scanlator: &scanlator,
+                                lang: &lang,
+                            },
+                            res,
+                            local_tx.clone(),
+                        );
+
+                        match download {
+                            Ok(download) => Some(download),
+                            Err(e) => {
+                                write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                                None
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                        None
+                    }
+                };
+
+                // Waiting for each chapter to finish, so they are downloaded one by one
+                let downloaded = match download {
+                    Some(download) => match download.await {
+                        Ok(downloaded) => downloaded,
+                        Err(e) => {
+                            write_to_error_log(error_log::ErrorType::FromError(Box::new(e)));
+                            local_tx
+                                .send(MangaPageEvents::DownloadError(chapter.id.clone()))
+                                .ok();
+                            false
+                        }
+                    },
+                    None => {
+                        local_tx
+                            .send(MangaPageEvents::DownloadError(chapter.id.clone()))
+                            .ok();
+                        false
+                    }
+                };
+
+                if downloaded {
+                    local_tx
+                        .send(MangaPageEvents::SaveChapterDownloadStatus(
+                            chapter.id, title,
+                        ))
+                        .ok();
+                } else {
+                    failed += 1;
+                }
+            }
+
+            let notification = if failed == 0 {
+                format!("{total} new chapters were downloaded")
+            } else {
+                format!("{} of {total} new chapters were downloaded", total - failed)
+            };
+
+            tx.send(Events::ShowNotification(notification)).ok();
+        });
+    }
+
     fn save_download_status(&mut self, id_chapter: String, title: String) {
         let save_download_operation = set_chapter_downloaded(SetChapterDownloaded {
             id: &id_chapter,
@@ -808,16 +2061,79 @@ impl MangaPage {
         }
     }
 
+    /// Fetch the current page of chapters again, for ongoing mangas whose new chapter came out
+    /// after the page was opened
+    fn refresh_chapters(&mut self) {
+        if self.state == PageState::SearchingChapters {
+            return;
+        }
+        let Some(chapters) = self.chapters.as_ref() else {
+            return;
+        };
+
+        let selected_index = chapters.state.selected;
+
+        self.chapters_refresh = Some(ChaptersRefresh {
+            selected_chapter_id: selected_index
+                .and_then(|index| chapters.widget.chapters.get(index))
+                .map(|chapter| chapter.id.clone()),
+            selected_index,
+            total_result: chapters.total_result,
+        });
+
+        self.search_chapters();
+    }
+
     fn load_chapters(&mut self, response: Option<ChapterResponse>) {
         self.state = PageState::DisplayingChapters;
+        let refresh = self.chapters_refresh.take();
+        // The selection is only kept when the same page is refreshed
+        let mut selection = match (refresh.as_ref(), self.chapters.as_mut()) {
+            (Some(_), Some(previous)) => std::mem::take(&mut previous.selection),
+            _ => HashSet::new(),
+        };
         match response {
             Some(response) => {
                 let mut list_state = tui_widget_list::ListState::default();
 
-                list_state.select(Some(0));
-
                 let chapter_widget = ChaptersListWidget::from_response(&response);
 
+                let selected = match refresh.as_ref() {
+                    Some(refresh) => refresh
+                        .selected_chapter_id
+                        .as_ref()
+                        .and_then(|id| {
+                            chapter_widget
+                                .chapters
+                                .iter()
+                                .position(|chapter| chapter.id == *id)
+                        })
+                        .or(refresh.selected_index)
+                        .map(|index| index.min(chapter_widget.chapters.len().saturating_sub(1))),
+                    None => Some(0),
+                };
+
+                list_state.select(selected);
+
+                selection.retain(|id| {
+                    chapter_widget
+                        .chapters
+                        .iter()
+                        .any(|chapter| chapter.id == *id)
+                });
+
+                if let Some(refresh) = refresh {
+                    let new_chapters = (response.total as u32).saturating_sub(refresh.total_result);
+                    let message = match new_chapters {
+                        0 => "Refreshed, no new chapters".to_string(),
+                        1 => "Refreshed, 1 new chapter".to_string(),
+                        new_chapters => format!("Refreshed, {new_chapters} new chapters"),
+                    };
+                    self.global_event_tx
+                        .send(Events::ShowNotification(message))
+                        .ok();
+                }
+
                 let page = if let Some(previous) = self.chapters.as_ref() {
                     previous.page
                 } else {
@@ -826,6 +2142,7 @@ impl MangaPage {
 
                 self.chapters = Some(ChaptersData {
                     state: list_state,
+                    selection,
                     widget: chapter_widget,
                     page,
                     total_result: response.total as u32,
@@ -908,6 +2225,13 @@ impl MangaPage {
                     }
                 }
                 MangaPageEvents::ReadSuccesful => self.state = PageState::DisplayingChapters,
+                MangaPageEvents::SearchCover => self.search_cover(),
+                MangaPageEvents::LoadCover(cover) => self.image_state = cover,
+                MangaPageEvents::LoadCoverList(covers) => self.load_cover_list(covers),
+                MangaPageEvents::CoverListError => self.is_cover_gallery_error = true,
+                MangaPageEvents::LoadGalleryCover(file_name, cover) => {
+                    self.load_gallery_cover(file_name, cover)
+                }
             }
         }
     }
@@ -924,6 +2248,20 @@ impl Component for MangaPage {
 
         self.render_cover(cover_area, frame.buffer_mut());
         self.render_manga_information(information_area, frame);
+
+        if self.is_external_links_open {
+            self.render_external_links(area, frame.buffer_mut());
+        }
+
+        if self.is_cover_gallery_open {
+            self.render_cover_gallery(area, frame.buffer_mut());
+        }
+
+        self.render_bookmarks(area, frame.buffer_mut());
+
+        if let Some(mark_as) = self.mark_all_confirmation {
+            self.render_mark_all_confirmation(mark_as, area, frame.buffer_mut());
+        }
     }
     fn update(&mut self, action: Self::Actions) {
         match action {
@@ -948,6 +2286,45 @@ impl Component for MangaPage {
             }
 
             MangaPageActions::DownloadChapter => self.download_chapter_selected(),
+            MangaPageActions::DownloadMissingChapters => self.download_missing_chapters(),
+            MangaPageActions::ToggleExternalLinks => self.toggle_external_links(),
+            MangaPageActions::ScrollDownExternalLinks => self.scroll_external_links_down(),
+            MangaPageActions::ScrollUpExternalLinks => self.scroll_external_links_up(),
+            MangaPageActions::OpenExternalLink => self.open_external_link(),
+            MangaPageActions::ReadFirstUnreadChapter => {
+                if self.state != PageState::SearchingChapterData {
+                    self.read_first_unread_chapter();
+                }
+            }
+            MangaPageActions::AskMarkAll(mark_as) => {
+                if self.chapters.is_some() {
+                    self.mark_all_confirmation = Some(mark_as);
+                }
+            }
+            MangaPageActions::ConfirmMarkAll => {
+                if let Some(mark_as) = self.mark_all_confirmation.take() {
+                    if self.has_selected_chapters() {
+                        self.mark_selected_chapters(mark_as);
+                    } else {
+                        self.mark_all_chapters(mark_as);
+                    }
+                }
+            }
+            MangaPageActions::CancelMarkAll => self.mark_all_confirmation = None,
+            MangaPageActions::ToggleCoverGallery => self.toggle_cover_gallery(),
+            MangaPageActions::ScrollDownCovers => self.scroll_covers(true),
+            MangaPageActions::ScrollUpCovers => self.scroll_covers(false),
+            MangaPageActions::SetCoverAsPreview => self.set_cover_as_preview(),
+            MangaPageActions::RefreshChapters => self.refresh_chapters(),
+            MangaPageActions::ToggleChapterSelection => self.toggle_chapter_selection(),
+            MangaPageActions::ToggleBookmarks => self.toggle_bookmarks(),
+            MangaPageActions::ScrollDownBookmarks => self.scroll_bookmarks_down(),
+            MangaPageActions::ScrollUpBookmarks => self.scroll_bookmarks_up(),
+            MangaPageActions::ReadBookmark => {
+                if self.state != PageState::SearchingChapterData {
+                    self.read_bookmark();
+                }
+            }
         }
     }
 
@@ -964,3 +2341,47 @@ impl Component for MangaPage {
         self.manga.description = String::new();
     }
 }
+
+async fn fetch_all_chapters(
+    manga_id: &str,
+    language: Languages,
+) -> Result<Vec<ChapterToMark>, FetchError> {
+    let chapters = fetch_every_chapter(manga_id, language).await?;
+
+    Ok(chapters
+        .into_iter()
+        .map(|chapter| ChapterToMark {
+            title: chapter.attributes.title.unwrap_or_default(),
+            id: chapter.id,
+        })
+        .collect())
+}
+
+async fn fetch_every_chapter(
+    manga_id: &str,
+    language: Languages,
+) -> Result<Vec<ChapterData>, FetchError> {
+    let mut chapters = vec![];
+    let mut page = 1;
+
+    loop {
+        let response = MangadexClient::global()
+            .get_manga_chapters(
+                manga_id.to_string(),
+                page,
+                language,
+                ChapterOrder::Ascending,
+            )
+            .await?;
+
+        let fetched = response.data.len();
+
+        chapters.extend(response.data);
+
+        if fetched == 0 || chapters.len() as i64 >= response.total {
+            return Ok(chapters);
+        }
+
+        page += 1;
+    }
+}
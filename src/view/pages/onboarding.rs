@@ -0,0 +1,346 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use ratatui_image::picker::ProtocolType;
+use strum::IntoEnumIterator;
+
+use crate::backend::filter::{ContentRating, Languages};
+use crate::config::{ImageProtocol, MangaTuiConfig};
+use crate::global::INSTRUCTIONS_STYLE;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum OnboardingStep {
+    #[default]
+    Images,
+    Language,
+    ContentRating,
+    Keys,
+}
+
+impl OnboardingStep {
+    fn next(self) -> Option<Self> {
+        match self {
+            Self::Images => Some(Self::Language),
+            Self::Language => Some(Self::ContentRating),
+            Self::ContentRating => Some(Self::Keys),
+            Self::Keys => None,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            Self::Images | Self::Language => Self::Images,
+            Self::ContentRating => Self::Language,
+            Self::Keys => Self::ContentRating,
+        }
+    }
+
+    fn number(self) -> u8 {
+        match self {
+            Self::Images => 1,
+            Self::Language => 2,
+            Self::ContentRating => 3,
+            Self::Keys => 4,
+        }
+    }
+}
+
+const KEYS: [(&str, &str); 8] = [
+    ("<F1>/<u>", "Go to the home page"),
+    (
+        "<F2>/<i>",
+        "Go to the search page, press <s> there to type what to search",
+    ),
+    ("<F3>/<o>", "Go to your feed, the mangas you read"),
+    ("<F5>", "See stats of your reading"),
+    (
+        "<j>/<k>",
+        "Move through the lists and the pages of a chapter",
+    ),
+    ("<Enter>", "Open the selected manga or chapter"),
+    (
+        "<F4>",
+        "Switch to another image protocol if images look wrong",
+    ),
+    ("<Ctrl+c>", "Quit"),
+];
+
+/// Shown the first time the app is started, when there is no config file yet, to choose the
+/// settings users usually look for right away. They are written to the config file when it's
+/// completed or skipped, quitting with `<Ctrl+c>` writes nothing so it's shown again next time
+pub struct Onboarding {
+    step: OnboardingStep,
+    /// `None` if the terminal supports no image protocol, in which case only block art can display
+    /// images
+    detected_protocol: Option<ProtocolType>,
+    image_protocol: ImageProtocol,
+    languages: Vec<Languages>,
+    languages_state: ListState,
+    content_rating: Vec<(ContentRating, bool)>,
+    content_rating_state: ListState,
+    pub is_done: bool,
+    /// Set when it's closed with `<Ctrl+c>`, the app quits instead of starting
+    pub is_quit: bool,
+}
+
+impl Onboarding {
+    pub fn new(detected_protocol: Option<ProtocolType>, language: Languages) -> Self {
+        let config = MangaTuiConfig::get();
+
+        let languages: Vec<Languages> = Languages::iter()
+            .filter(|lang| *lang != Languages::Unkown)
+            .collect();
+
+        let selected_language = languages.iter().position(|lang| *lang == language);
+
+        let content_rating = ContentRating::iter()
+            .map(|rating| {
+                let is_selected = config.content_rating.contains(&rating);
+                (rating, is_selected)
+            })
+            .collect();
+
+        let image_protocol = if detected_protocol.is_some() {
+            config.image_protocol
+        } else {
+            ImageProtocol::Blocks
+        };
+
+        Self {
+            step: OnboardingStep::default(),
+            detected_protocol,
+            image_protocol,
+            languages,
+            languages_state: ListState::default().with_selected(selected_language),
+            content_rating,
+            content_rating_state: ListState::default().with_selected(Some(0)),
+            is_done: false,
+            is_quit: false,
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let mut instructions = vec![
+            "Next ".into(),
+            Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+            " Back ".into(),
+            Span::raw("<Backspace>").style(*INSTRUCTIONS_STYLE),
+            " Skip ".into(),
+            Span::raw("<Esc>").style(*INSTRUCTIONS_STYLE),
+            " Quit ".into(),
+            Span::raw("<Ctrl+c>").style(*INSTRUCTIONS_STYLE),
+        ];
+
+        match self.step {
+            OnboardingStep::Images | OnboardingStep::Language => {
+                instructions.push(" Select ".into());
+                instructions.push(Span::raw("<j>/<k>").style(*INSTRUCTIONS_STYLE));
+            }
+            OnboardingStep::ContentRating => {
+                instructions.push(" Select ".into());
+                instructions.push(Span::raw("<j>/<k>").style(*INSTRUCTIONS_STYLE));
+                instructions.push(" Toggle ".into());
+                instructions.push(Span::raw("<Space>").style(*INSTRUCTIONS_STYLE));
+            }
+            OnboardingStep::Keys => {}
+        }
+
+        let block = Block::bordered()
+            .title(format!("Welcome to manga-tui ({}/4)", self.step.number()))
+            .title_bottom(Line::from(instructions));
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        match self.step {
+            OnboardingStep::Images => self.render_images(inner, buf),
+            OnboardingStep::Language => self.render_language(inner, buf),
+            OnboardingStep::ContentRating => self.render_content_rating(inner, buf),
+            OnboardingStep::Keys => Self::render_keys(inner, buf),
+        }
+    }
+
+    fn render_images(&self, area: Rect, buf: &mut Buffer) {
+        let detected = match self.detected_protocol {
+            Some(protocol) => Line::from(vec![
+                "Your terminal can display images with ".into(),
+                format!("{protocol:?}").bold(),
+            ]),
+            None => Line::from(
+                "No image protocol was detected, images can still be drawn with colored blocks",
+            ),
+        };
+
+        let option = |protocol: ImageProtocol, description: &'static str| {
+            let line = Line::from(vec![format!("{protocol}: ").bold(), description.into()]);
+            if protocol == self.image_protocol {
+                line.black().on_yellow()
+            } else {
+                line
+            }
+        };
+
+        Paragraph::new(vec![
+            detected,
+            Line::default(),
+            "How should images be drawn?".into(),
+            option(
+                ImageProtocol::Auto,
+                "with the best protocol your terminal supports, no images if there is none",
+            ),
+            option(
+                ImageProtocol::Blocks,
+                "with colored blocks, works on any terminal but the images look blurry",
+            ),
+        ])
+        .wrap(Wrap { trim: true })
+        .render(area, buf);
+    }
+
+    fn render_language(&mut self, area: Rect, buf: &mut Buffer) {
+        let [title_area, list_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(area);
+
+        Paragraph::new("Which language do you want to read mangas in?").render(title_area, buf);
+
+        let languages = List::new(
+            self.languages
+                .iter()
+                .map(|lang| format!("{} {}", lang.as_emoji(), lang.as_human_readable())),
+        )
+        .highlight_style(Style::default().black().on_yellow());
+
+        StatefulWidget::render(languages, list_area, buf, &mut self.languages_state);
+    }
+
+    fn render_content_rating(&mut self, area: Rect, buf: &mut Buffer) {
+        let [title_area, list_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(area);
+
+        Paragraph::new(
+            "Which content ratings should be shown? They can also be changed on the search filters",
+        )
+        .wrap(Wrap { trim: true })
+        .render(title_area, buf);
+
+        let ratings = List::new(self.content_rating.iter().map(|(rating, is_selected)| {
+            let check = if *is_selected { "[x]" } else { "[ ]" };
+            format!("{check} {rating}")
+        }))
+        .highlight_style(Style::default().black().on_yellow());
+
+        StatefulWidget::render(ratings, list_area, buf, &mut self.content_rating_state);
+    }
+
+    fn render_keys(area: Rect, buf: &mut Buffer) {
+        let mut lines: Vec<Line<'_>> = vec!["These are the basic keys:".into(), Line::default()];
+
+        lines.extend(KEYS.iter().map(|(key, description)| {
+            Line::from(vec![
+                Span::raw(*key).style(*INSTRUCTIONS_STYLE),
+                format!(" {description}").into(),
+            ])
+        }));
+
+        lines.push(Line::default());
+        lines.push(
+            "Each page shows its own keys at the bottom. Press <Enter> to start using manga-tui"
+                .into(),
+        );
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+
+    pub fn handle_key_events(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => {
+                self.is_quit = true;
+                self.is_done = true;
+            }
+            KeyCode::Enter => match self.step.next() {
+                Some(step) => self.step = step,
+                None => self.finish(),
+            },
+            KeyCode::Backspace => self.step = self.step.previous(),
+            KeyCode::Esc => self.finish(),
+            KeyCode::Char('j') | KeyCode::Down => self.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.select_previous(),
+            KeyCode::Char(' ') if self.step == OnboardingStep::ContentRating => {
+                if let Some(index) = self.content_rating_state.selected() {
+                    if let Some((_, is_selected)) = self.content_rating.get_mut(index) {
+                        *is_selected = !*is_selected;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn select_next(&mut self) {
+        match self.step {
+            OnboardingStep::Images => self.image_protocol = ImageProtocol::Blocks,
+            OnboardingStep::Language => {
+                let next = self
+                    .languages_state
+                    .selected()
+                    .map_or(0, |index| (index + 1).min(self.languages.len() - 1));
+                self.languages_state.select(Some(next));
+            }
+            OnboardingStep::ContentRating => {
+                let next = self
+                    .content_rating_state
+                    .selected()
+                    .map_or(0, |index| (index + 1).min(self.content_rating.len() - 1));
+                self.content_rating_state.select(Some(next));
+            }
+            OnboardingStep::Keys => {}
+        }
+    }
+
+    fn select_previous(&mut self) {
+        match self.step {
+            OnboardingStep::Images => self.image_protocol = ImageProtocol::Auto,
+            OnboardingStep::Language => {
+                let previous = self
+                    .languages_state
+                    .selected()
+                    .map_or(0, |index| index.saturating_sub(1));
+                self.languages_state.select(Some(previous));
+            }
+            OnboardingStep::ContentRating => {
+                let previous = self
+                    .content_rating_state
+                    .selected()
+                    .map_or(0, |index| index.saturating_sub(1));
+                self.content_rating_state.select(Some(previous));
+            }
+            OnboardingStep::Keys => {}
+        }
+    }
+
+    fn finish(&mut self) {
+        let language = self
+            .languages_state
+            .selected()
+            .and_then(|index| self.languages.get(index))
+            .map(|lang| lang.as_iso_code().to_string());
+
+        let content_rating = self
+            .content_rating
+            .iter()
+            .filter(|(_, is_selected)| *is_selected)
+            .map(|(rating, _)| rating.clone())
+            .collect();
+
+        let image_protocol = self.image_protocol;
+
+        MangaTuiConfig::update(|config| {
+            config.image_protocol = image_protocol;
+            config.language = language;
+            config.content_rating = content_rating;
+        });
+
+        self.is_done = true;
+    }
+}
@@ -1,38 +1,219 @@
+use crate::backend::cache::{cache_page, get_cached_page, EXTERNAL_VIEWER_DIR};
+use crate::backend::database::{
+    get_chapter_bookmarks, get_reading_direction, set_chapter_downloaded, set_reading_direction,
+    toggle_page_bookmark, PageBookmarkSave, SetChapterDownloaded,
+};
+use crate::backend::download::{download_chapter, DownloadChapter, DownloadHandler};
 use crate::backend::error_log::{write_to_error_log, ErrorType};
 use crate::backend::fetch::MangadexClient;
 use crate::backend::tui::Events;
+use crate::backend::ChapterPagesResponse;
+use crate::config::MangaTuiConfig;
 use crate::global::INSTRUCTIONS_STYLE;
+use crate::utils::centered_rect;
+use crate::utils::decode_image;
+use crate::utils::loaded_pages_window;
+use crate::utils::natural_cmp;
 use crate::view::widgets::reader::{PageItemState, PagesItem, PagesList};
 use crate::view::widgets::Component;
-use crate::PICKER;
+use crate::{new_image_protocol, picker};
+use bytes::Bytes;
 use crossterm::event::KeyCode;
-use image::io::Reader;
-use image::GenericImageView;
+use image::{GenericImageView, ImageFormat};
 use ratatui::{prelude::*, widgets::*};
 use ratatui_image::protocol::StatefulProtocol;
 use ratatui_image::{Resize, StatefulImage};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{create_dir_all, read, read_dir, write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use strum::Display;
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
 
 pub enum MangaReaderActions {
     NextPage,
     PreviousPage,
+    DownloadChapter,
+    ReadNextChapter,
+    CloseEndOfChapterPrompt,
+    CycleReadingDirection,
+    ToggleBookmark,
+    ToggleFullScreen,
+    ReportPage,
+    OpenInExternalViewer,
+    ToggleAutoPageTurn,
+    /// Changes `auto_page_turn_ms` by an amount of milliseconds, a negative one makes it faster
+    ChangeAutoPageTurnSpeed(i64),
 }
 
+/// Pages which failed one after another before a new at-home server is requested, mangadex may
+/// give another one if the server of the chapter went down
+const MAX_FAILED_PAGES_IN_A_ROW: u32 = 3;
+
+/// Bounds of `auto_page_turn_ms`
+const MIN_AUTO_PAGE_TURN_MS: u64 = 500;
+const MAX_AUTO_PAGE_TURN_MS: u64 = 60_000;
+const AUTO_PAGE_TURN_STEP_MS: i64 = 500;
+
+/// A reader is made for each chapter, so full screen is kept here to stay on until it's toggled
+/// off or the app is closed
+static IS_FULL_SCREEN: AtomicBool = AtomicBool::new(false);
+
 pub enum State {
     SearchingPages,
 }
 
+/// What is needed to get the image of a page, from the folder of a downloaded chapter, the cache or
+/// mangadex
+struct PageRequest {
+    index: usize,
+    file_name: String,
+    endpoint: String,
+    chapter_id: String,
+    page_type: String,
+    pages_dir: Option<PathBuf>,
+}
+
 pub struct PageData {
     pub protocol: Box<dyn StatefulProtocol>,
     pub index: usize,
     pub dimensions: (u32, u32),
+    pub format: Option<ImageFormat>,
 }
 
 pub enum MangaReaderEvents {
     FetchPages,
     LoadPage(Option<PageData>),
+    /// The page could not be fetched or decoded, it's requested again the next time it's in the
+    /// window of loaded pages
+    PageNotFound(usize),
+    SetDownloadProgress(f64),
+    ChapterFinishedDownloading,
+    DownloadError,
+    /// The at-home server was asked for again after `MAX_FAILED_PAGES_IN_A_ROW`, `None` if that
+    /// failed too
+    ServerRefreshed(Option<ChapterPagesResponse>),
+}
+
+impl DownloadHandler for MangaReaderEvents {
+    fn progress(progress: f64, _chapter_id: String) -> Self {
+        Self::SetDownloadProgress(progress)
+    }
+    fn finished(_chapter_id: String) -> Self {
+        Self::ChapterFinishedDownloading
+    }
+    fn failed(_chapter_id: String) -> Self {
+        Self::DownloadError
+    }
+}
+
+/// The manga and chapter being read, it's what is needed to download the chapter from the reader
+#[derive(Debug, Clone, Default)]
+pub struct ChapterToRead {
+    pub id: String,
+    pub title: String,
+    pub number: String,
+    pub scanlator: String,
+    pub lang: String,
+    pub manga_id: String,
+    pub manga_title: String,
+    pub manga_img_url: Option<String>,
+    /// Used to guess the reading direction the first time a chapter of the manga is read
+    pub manga_original_language: Option<String>,
+    /// Number and title of the chapter which comes after this one on the chapter list, it's shown
+    /// at the end of the chapter
+    pub next_chapter: Option<String>,
+    /// The page the chapter is opened at, it's set when a bookmark is opened
+    pub start_page: usize,
+}
+
+/// The ids of the chapters read from a folder given with `manga-tui read` start with it
+const LOCAL_FOLDER_PREFIX: &str = "local:";
+
+impl ChapterToRead {
+    /// A folder of images which was not downloaded by manga-tui, the folder it's in is taken as
+    /// the manga so chapters next to each other share the reading direction
+    pub fn from_local_folder(folder: &Path) -> Self {
+        let name = |path: Option<&Path>| {
+            path.and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+
+        let parent = folder.parent();
+
+        Self {
+            id: format!("{LOCAL_FOLDER_PREFIX}{}", folder.display()),
+            title: name(Some(folder)),
+            manga_id: format!(
+                "{LOCAL_FOLDER_PREFIX}{}",
+                parent
+                    .map(Path::display)
+                    .map(|path| path.to_string())
+                    .unwrap_or_default()
+            ),
+            manga_title: name(parent),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_local_folder(&self) -> bool {
+        self.id.starts_with(LOCAL_FOLDER_PREFIX)
+    }
+}
+
+/// What going past the last page of a chapter does
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndOfChapter {
+    /// Ask whether to read the next chapter or go back to the chapter list
+    #[default]
+    NextChapterPrompt,
+    ChapterList,
+    Nothing,
+}
+
+/// Which horizontal key goes to the next page, in the vertical direction (used for webtoons) only
+/// `<j>`/`<k>` turn the pages, which also works in the other directions
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadingDirection {
+    #[strum(to_string = "left to right")]
+    LeftToRight,
+    #[strum(to_string = "right to left")]
+    RightToLeft,
+    #[strum(to_string = "vertical")]
+    Vertical,
+}
+
+impl ReadingDirection {
+    /// Mangas are usually read right to left, manhwas are mostly webtoons and manhuas are read
+    /// left to right
+    pub fn from_original_language(original_language: Option<&str>) -> Self {
+        match original_language {
+            Some("ja") => Self::RightToLeft,
+            Some("ko") => Self::Vertical,
+            _ => Self::LeftToRight,
+        }
+    }
+
+    fn from_saved(direction: &str) -> Option<Self> {
+        [Self::LeftToRight, Self::RightToLeft, Self::Vertical]
+            .into_iter()
+            .find(|reading_direction| reading_direction.to_string() == direction)
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Self::LeftToRight => Self::RightToLeft,
+            Self::RightToLeft => Self::Vertical,
+            Self::Vertical => Self::LeftToRight,
+        }
+    }
 }
 
 #[derive(Display)]
@@ -48,6 +229,10 @@ pub struct Page {
     pub url: String,
     pub page_type: PageType,
     pub dimensions: Option<(u32, u32)>,
+    /// Format guessed from the bytes of the image, it's only known once the page is loaded
+    pub format: Option<ImageFormat>,
+    /// Whether the image of the page was requested and has not arrived yet
+    pub is_loading: bool,
 }
 
 impl Page {
@@ -55,6 +240,8 @@ impl Page {
         Self {
             image_state: None,
             dimensions: None,
+            format: None,
+            is_loading: false,
             url,
             page_type,
         }
@@ -62,16 +249,33 @@ impl Page {
 }
 
 pub struct MangaReader {
+    chapter: ChapterToRead,
+    chapter_pages: ChapterPagesResponse,
+    /// Progress of the download of the chapter being read, `None` if it's not being downloaded
+    download_progress: Option<f64>,
+    /// Set when a downloaded chapter is read, its pages are taken from this directory instead of
+    /// mangadex
+    pages_dir: Option<PathBuf>,
     chapter_id: String,
     base_url: String,
     pages: Vec<Page>,
     pages_list: PagesList,
+    is_end_of_chapter_prompt_open: bool,
+    reading_direction: ReadingDirection,
+    /// Pages of the chapter bookmarked with `<m>`
+    bookmarked_pages: HashSet<usize>,
     current_page_size: u16,
     page_list_state: tui_widget_list::ListState,
     _state: State,
     /// Handle fetching the images
     image_tasks: JoinSet<()>,
-    pub _global_event_tx: UnboundedSender<Events>,
+    /// Limits how many pages are downloaded at the same time, see `parallel_page_downloads`
+    page_downloads: Arc<Semaphore>,
+    failed_pages_in_a_row: u32,
+    is_refreshing_server: bool,
+    /// When the page being read was shown while auto page turn is on, `None` if it's off
+    auto_page_turn_since: Option<Instant>,
+    pub global_event_tx: UnboundedSender<Events>,
     pub local_action_tx: UnboundedSender<MangaReaderActions>,
     pub local_action_rx: UnboundedReceiver<MangaReaderActions>,
     pub local_event_tx: UnboundedSender<MangaReaderEvents>,
@@ -83,6 +287,16 @@ impl Component for MangaReader {
     fn render(&mut self, area: ratatui::prelude::Rect, frame: &mut Frame<'_>) {
         let buf = frame.buffer_mut();
 
+        if self.is_end_of_chapter_prompt_open {
+            self.render_end_of_chapter_prompt(area, buf);
+            return;
+        }
+
+        if IS_FULL_SCREEN.load(Ordering::Relaxed) {
+            self.render_current_page(area, buf);
+            return;
+        }
+
         let layout = Layout::horizontal([
             Constraint::Fill(1),
             Constraint::Fill(self.current_page_size),
@@ -94,50 +308,117 @@ impl Component for MangaReader {
         Block::bordered().render(left, buf);
         self.render_page_list(left, buf);
 
-        Paragraph::new(Line::from(vec![
-            "Go back: ".into(),
-            Span::raw("<Backspace>").style(*INSTRUCTIONS_STYLE),
-        ]))
-        .render(right, buf);
+        let download_status = match self.download_progress {
+            Some(progress) => format!("Downloading: {}%", (progress * 100.0).round()).into(),
+            None if self.chapter.is_local_folder() => "Reading a local folder".into(),
+            None if self.pages_dir.is_some() => "Reading from the downloads".into(),
+            None => Line::from(vec![
+                "Download chapter: ".into(),
+                Span::raw("<d>").style(*INSTRUCTIONS_STYLE),
+            ]),
+        };
 
-        match self
-            .pages
-            .get_mut(self.page_list_state.selected.unwrap_or(0))
-        {
-            Some(page) => match page.image_state.as_mut() {
-                Some(img_state) => {
-                    let (width, height) = page.dimensions.unwrap();
-                    if width > height {
-                        if width - height > 250 {
-                            self.current_page_size = 5;
-                        }
+        let mut lines = vec![
+            Line::from(vec![
+                "Go back: ".into(),
+                Span::raw("<Backspace>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            download_status,
+            Line::from(vec![
+                format!("Direction: {} ", self.reading_direction).into(),
+                Span::raw("<r>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            Line::from(vec![
+                "Bookmark page: ".into(),
+                Span::raw("<m>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            Line::from(vec![
+                "Full screen: ".into(),
+                Span::raw("<f>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            Line::from(vec![
+                format!(
+                    "Auto page turn: {} ",
+                    if self.auto_page_turn_since.is_some() {
+                        "on"
                     } else {
-                        self.current_page_size = 2;
+                        "off"
                     }
-                    let image = StatefulImage::new(None).resize(Resize::Fit(None));
-                    StatefulWidget::render(image, center, buf, img_state);
-                }
-                None => {
-                    Block::bordered()
-                        .title("Loading page")
-                        .render(center, frame.buffer_mut());
-                }
-            },
-            None => Block::bordered()
-                .title("Loading page")
-                .render(center, frame.buffer_mut()),
-        };
+                )
+                .into(),
+                Span::raw("<a>").style(*INSTRUCTIONS_STYLE),
+                " speed ".into(),
+                Span::raw("<+>/<->").style(*INSTRUCTIONS_STYLE),
+            ]),
+            Line::from(vec![
+                "Report page: ".into(),
+                Span::raw("<R>").style(*INSTRUCTIONS_STYLE),
+            ]),
+            Line::from(vec![
+                "Open in image viewer: ".into(),
+                Span::raw("<o>").style(*INSTRUCTIONS_STYLE),
+            ]),
+        ];
+
+        if !self.chapter.scanlator.is_empty() {
+            lines.insert(0, Line::from(format!("Group: {}", self.chapter.scanlator)));
+        }
+
+        Paragraph::new(lines).render(right, buf);
+
+        self.render_current_page(center, buf);
     }
 
     fn update(&mut self, action: Self::Actions) {
         match action {
             MangaReaderActions::NextPage => self.next_page(),
             MangaReaderActions::PreviousPage => self.previous_page(),
+            MangaReaderActions::DownloadChapter => self.download_chapter(),
+            MangaReaderActions::ReadNextChapter => {
+                if self.chapter.next_chapter.is_some() {
+                    self.global_event_tx.send(Events::ReadNextChapter).ok();
+                }
+            }
+            MangaReaderActions::CloseEndOfChapterPrompt => {
+                self.is_end_of_chapter_prompt_open = false;
+            }
+            MangaReaderActions::CycleReadingDirection => self.cycle_reading_direction(),
+            MangaReaderActions::ToggleBookmark => self.toggle_bookmark(),
+            MangaReaderActions::ToggleFullScreen => {
+                IS_FULL_SCREEN.fetch_xor(true, Ordering::Relaxed);
+            }
+            MangaReaderActions::ReportPage => self.report_page(),
+            MangaReaderActions::OpenInExternalViewer => self.open_in_external_viewer(),
+            MangaReaderActions::ToggleAutoPageTurn => {
+                self.auto_page_turn_since = match self.auto_page_turn_since {
+                    Some(_) => None,
+                    None => Some(Instant::now()),
+                };
+            }
+            MangaReaderActions::ChangeAutoPageTurnSpeed(amount) => {
+                self.change_auto_page_turn_speed(amount);
+            }
         }
     }
 
     fn handle_events(&mut self, events: crate::backend::tui::Events) {
         match events {
+            Events::Key(key_event) if self.is_end_of_chapter_prompt_open => match key_event.code {
+                KeyCode::Enter => {
+                    self.local_action_tx
+                        .send(MangaReaderActions::ReadNextChapter)
+                        .ok();
+                }
+                KeyCode::Esc => {
+                    self.global_event_tx.send(Events::CloseReader).ok();
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.local_action_tx
+                        .send(MangaReaderActions::CloseEndOfChapterPrompt)
+                        .ok();
+                }
+                _ => {}
+            },
             Events::Key(key_event) => match key_event.code {
                 KeyCode::Down | KeyCode::Char('j') => {
                     self.local_action_tx.send(MangaReaderActions::NextPage).ok();
@@ -147,6 +428,57 @@ impl Component for MangaReader {
                         .send(MangaReaderActions::PreviousPage)
                         .ok();
                 }
+                KeyCode::Right | KeyCode::Char('l') => self.turn_page_horizontally(true),
+                KeyCode::Left | KeyCode::Char('h') => self.turn_page_horizontally(false),
+                KeyCode::Char('d') => {
+                    self.local_action_tx
+                        .send(MangaReaderActions::DownloadChapter)
+                        .ok();
+                }
+                KeyCode::Char('r') => {
+                    self.local_action_tx
+                        .send(MangaReaderActions::CycleReadingDirection)
+                        .ok();
+                }
+                KeyCode::Char('m') => {
+                    self.local_action_tx
+                        .send(MangaReaderActions::ToggleBookmark)
+                        .ok();
+                }
+                KeyCode::Char('f') => {
+                    self.local_action_tx
+                        .send(MangaReaderActions::ToggleFullScreen)
+                        .ok();
+                }
+                KeyCode::Char('R') => {
+                    self.local_action_tx
+                        .send(MangaReaderActions::ReportPage)
+                        .ok();
+                }
+                KeyCode::Char('o') => {
+                    self.local_action_tx
+                        .send(MangaReaderActions::OpenInExternalViewer)
+                        .ok();
+                }
+                KeyCode::Char('a') => {
+                    self.local_action_tx
+                        .send(MangaReaderActions::ToggleAutoPageTurn)
+                        .ok();
+                }
+                KeyCode::Char('+') => {
+                    self.local_action_tx
+                        .send(MangaReaderActions::ChangeAutoPageTurnSpeed(
+                            -AUTO_PAGE_TURN_STEP_MS,
+                        ))
+                        .ok();
+                }
+                KeyCode::Char('-') => {
+                    self.local_action_tx
+                        .send(MangaReaderActions::ChangeAutoPageTurnSpeed(
+                            AUTO_PAGE_TURN_STEP_MS,
+                        ))
+                        .ok();
+                }
 
                 _ => {}
             },
@@ -176,11 +508,14 @@ impl Component for MangaReader {
 impl MangaReader {
     pub fn new(
         global_event_tx: UnboundedSender<Events>,
-        chapter_id: String,
-        base_url: String,
-        url_imgs: Vec<String>,
-        url_imgs_high_quality: Vec<String>,
+        chapter: ChapterToRead,
+        chapter_pages: ChapterPagesResponse,
     ) -> Self {
+        let chapter_id = chapter_pages.chapter.hash.clone();
+        let base_url = chapter_pages.base_url.clone();
+        let url_imgs = &chapter_pages.chapter.data_saver;
+        let url_imgs_high_quality = &chapter_pages.chapter.data;
+
         let set: JoinSet<()> = JoinSet::new();
         let (local_action_tx, local_action_rx) = mpsc::unbounded_channel::<MangaReaderActions>();
         let (local_event_tx, local_event_rx) = mpsc::unbounded_channel::<MangaReaderEvents>();
@@ -197,13 +532,33 @@ impl MangaReader {
 
         local_event_tx.send(MangaReaderEvents::FetchPages).ok();
 
-        Self {
-            _global_event_tx: global_event_tx,
+        let reading_direction = Self::load_reading_direction(&chapter);
+
+        let bookmarked_pages = match get_chapter_bookmarks(&chapter.id) {
+            Ok(pages) => pages.into_iter().collect(),
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                HashSet::new()
+            }
+        };
+
+        let mut reader = Self {
+            global_event_tx,
+            chapter,
+            chapter_pages,
+            download_progress: None,
+            pages_dir: None,
             chapter_id,
             base_url,
             pages,
             page_list_state: tui_widget_list::ListState::default(),
             image_tasks: set,
+            page_downloads: Arc::new(Semaphore::new(
+                MangaTuiConfig::get().parallel_page_downloads.max(1),
+            )),
+            failed_pages_in_a_row: 0,
+            is_refreshing_server: false,
+            auto_page_turn_since: None,
             local_action_tx,
             local_action_rx,
             local_event_tx,
@@ -211,15 +566,381 @@ impl MangaReader {
             _state: State::SearchingPages,
             current_page_size: 2,
             pages_list: PagesList::default(),
+            is_end_of_chapter_prompt_open: false,
+            reading_direction,
+            bookmarked_pages,
+        };
+
+        reader.select_start_page();
+
+        reader
+    }
+
+    /// The image of the page being read, or why it's not shown yet
+    fn render_current_page(&mut self, area: Rect, buf: &mut Buffer) {
+        match self
+            .pages
+            .get_mut(self.page_list_state.selected.unwrap_or(0))
+        {
+            Some(page) => match page.image_state.as_mut() {
+                Some(img_state) => {
+                    let (width, height) = page.dimensions.unwrap();
+                    if width > height {
+                        if width - height > 250 {
+                            self.current_page_size = 5;
+                        }
+                    } else {
+                        self.current_page_size = 2;
+                    }
+                    let image = StatefulImage::new(None).resize(Resize::Fit(None));
+                    StatefulWidget::render(image, area, buf, img_state);
+                }
+                None => {
+                    let is_not_found = self
+                        .pages_list
+                        .pages
+                        .get(self.page_list_state.selected.unwrap_or(0))
+                        .is_some_and(|page_item| page_item.state == PageItemState::NotFound);
+                    let title = if is_not_found {
+                        "The page could not be loaded"
+                    } else {
+                        "Loading page"
+                    };
+                    Block::bordered().title(title).render(area, buf);
+                }
+            },
+            None => Block::bordered().title("Loading page").render(area, buf),
+        };
+    }
+
+    /// The chapter and the page being read, `None` for the folders read with `manga-tui read`
+    pub fn reading_position(&self) -> Option<(&str, usize)> {
+        (!self.chapter.is_local_folder()).then(|| {
+            (
+                self.chapter.id.as_str(),
+                self.page_list_state.selected.unwrap_or(0),
+            )
+        })
+    }
+
+    fn select_start_page(&mut self) {
+        if self.chapter.start_page < self.pages.len() {
+            self.page_list_state.select(Some(self.chapter.start_page));
+        }
+    }
+
+    fn toggle_bookmark(&mut self) {
+        let page = self.page_list_state.selected.unwrap_or(0);
+        if page >= self.pages.len() {
+            return;
+        }
+
+        let toggle_operation = toggle_page_bookmark(PageBookmarkSave {
+            manga_id: &self.chapter.manga_id,
+            chapter_id: &self.chapter.id,
+            chapter_title: &self.chapter.title,
+            chapter_number: &self.chapter.number,
+            page,
+        });
+
+        match toggle_operation {
+            Ok(is_bookmarked) => {
+                if is_bookmarked {
+                    self.bookmarked_pages.insert(page);
+                } else {
+                    self.bookmarked_pages.remove(&page);
+                }
+                if let Some(page_item) = self.pages_list.pages.get_mut(page) {
+                    page_item.is_bookmarked = is_bookmarked;
+                }
+            }
+            Err(e) => write_to_error_log(ErrorType::FromError(Box::new(e))),
+        }
+    }
+
+    /// The direction is remembered for each manga, the first time one of its chapters is read it's
+    /// guessed from the language the manga was published in
+    fn load_reading_direction(chapter: &ChapterToRead) -> ReadingDirection {
+        match get_reading_direction(&chapter.manga_id) {
+            Ok(Some(saved)) => {
+                if let Some(direction) = ReadingDirection::from_saved(&saved) {
+                    return direction;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => write_to_error_log(ErrorType::FromError(Box::new(e))),
+        }
+
+        let direction =
+            ReadingDirection::from_original_language(chapter.manga_original_language.as_deref());
+
+        // Downloaded chapters don't know the original language, so nothing is remembered until the
+        // manga is read online
+        if chapter.manga_original_language.is_some() {
+            Self::save_reading_direction(&chapter.manga_id, direction);
+        }
+
+        direction
+    }
+
+    fn save_reading_direction(manga_id: &str, direction: ReadingDirection) {
+        if let Err(e) = set_reading_direction(manga_id, &direction.to_string()) {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
+    }
+
+    fn cycle_reading_direction(&mut self) {
+        self.reading_direction = self.reading_direction.next();
+        Self::save_reading_direction(&self.chapter.manga_id, self.reading_direction);
+    }
+
+    /// `towards_right` is whether the key pressed was `<Right>`/`<l>`
+    fn turn_page_horizontally(&mut self, towards_right: bool) {
+        let action = match (self.reading_direction, towards_right) {
+            (ReadingDirection::Vertical, _) => return,
+            (ReadingDirection::LeftToRight, true) | (ReadingDirection::RightToLeft, false) => {
+                MangaReaderActions::NextPage
+            }
+            _ => MangaReaderActions::PreviousPage,
+        };
+
+        self.local_action_tx.send(action).ok();
+    }
+
+    /// The images of the directory sorted by their name, files which are not images are skipped
+    pub fn page_files(pages_dir: &Path) -> Vec<String> {
+        let mut files: Vec<String> = read_dir(pages_dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|entry| entry.path().is_file())
+                    .filter(|entry| {
+                        ImageFormat::from_path(entry.path())
+                            .is_ok_and(|format| format.reading_enabled())
+                    })
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        files.sort_by(|a, b| natural_cmp(a, b));
+
+        files
+    }
+
+    /// Read a chapter from a directory, either one of the downloads, which is what is used in
+    /// offline mode, or a folder of images given with `manga-tui read`
+    pub fn from_downloaded_chapter(
+        global_event_tx: UnboundedSender<Events>,
+        chapter: ChapterToRead,
+        pages_dir: PathBuf,
+    ) -> Self {
+        let files = Self::page_files(&pages_dir);
+
+        let mut reader = Self::new(global_event_tx, chapter, ChapterPagesResponse::default());
+
+        reader.chapter_id = reader.chapter.id.clone();
+        reader.pages = files
+            .into_iter()
+            .map(|file_name| Page::new(file_name, PageType::HighQuality))
+            .collect();
+        reader.pages_dir = Some(pages_dir);
+        reader.select_start_page();
+
+        reader
+    }
+
+    /// Build the pages again, used when the image protocol changes so they are displayed with the
+    /// new one, the pages most likely come from the cache by now
+    pub fn reload_images(&mut self) {
+        self.image_tasks.abort_all();
+        self.pages.iter_mut().for_each(|page| {
+            page.image_state = None;
+            page.is_loading = false;
+        });
+        self.local_event_tx.send(MangaReaderEvents::FetchPages).ok();
+    }
+
+    /// Download the chapter being read, the pages which were already fetched are taken from the
+    /// cache instead of being downloaded again
+    fn download_chapter(&mut self) {
+        if self.download_progress.is_some() {
+            return;
+        }
+
+        if self.pages_dir.is_some() {
+            let message = if self.chapter.is_local_folder() {
+                "This chapter is read from a local folder"
+            } else {
+                "This chapter is already downloaded"
+            };
+            self.global_event_tx
+                .send(Events::ShowNotification(message.to_string()))
+                .ok();
+            return;
+        }
+
+        self.download_progress = Some(0.0);
+
+        let download_operation = download_chapter(
+            DownloadChapter {
+                id_chapter: &self.chapter.id,
+                manga_id: &self.chapter.manga_id,
+                manga_title: &self.chapter.manga_title,
+                title: &self.chapter.title,
+                number: &self.chapter.number,
+                scanlator: &self.chapter.scanlator,
+                lang: &self.chapter.lang,
+            },
+            self.chapter_pages.clone(),
+            self.local_event_tx.clone(),
+        );
+
+        if let Err(e) = download_operation {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+            self.local_event_tx
+                .send(MangaReaderEvents::DownloadError)
+                .ok();
         }
     }
 
+    fn finish_download(&mut self) {
+        self.download_progress = None;
+
+        let save_download_operation = set_chapter_downloaded(SetChapterDownloaded {
+            id: &self.chapter.id,
+            title: &self.chapter.title,
+            manga_id: &self.chapter.manga_id,
+            manga_title: &self.chapter.manga_title,
+            img_url: self.chapter.manga_img_url.as_deref(),
+        });
+
+        if let Err(e) = save_download_operation {
+            write_to_error_log(ErrorType::FromError(Box::new(e)));
+        }
+
+        self.global_event_tx
+            .send(Events::ShowNotification(format!(
+                "Downloaded: Ch. {} {}",
+                self.chapter.number, self.chapter.title
+            )))
+            .ok();
+    }
+
+    fn change_auto_page_turn_speed(&mut self, amount: i64) {
+        MangaTuiConfig::update(|config| {
+            config.auto_page_turn_ms = config
+                .auto_page_turn_ms
+                .saturating_add_signed(amount)
+                .clamp(MIN_AUTO_PAGE_TURN_MS, MAX_AUTO_PAGE_TURN_MS);
+        });
+
+        let seconds = MangaTuiConfig::get().auto_page_turn_ms as f64 / 1000.0;
+        self.global_event_tx
+            .send(Events::ShowNotification(format!(
+                "Auto page turn: a page every {seconds}s"
+            )))
+            .ok();
+    }
+
+    /// The time of a page only counts once its image is shown, so slow pages are not skipped. On
+    /// the last page auto page turn stops and `end_of_chapter` is applied like with `<j>`
+    fn auto_page_turn(&mut self) {
+        let Some(since) = self.auto_page_turn_since else {
+            return;
+        };
+
+        let selected = self.page_list_state.selected.unwrap_or(0);
+        let is_page_shown = self
+            .pages
+            .get(selected)
+            .is_some_and(|page| page.image_state.is_some());
+
+        if !is_page_shown || self.is_end_of_chapter_prompt_open {
+            self.auto_page_turn_since = Some(Instant::now());
+            return;
+        }
+
+        if since.elapsed() < Duration::from_millis(MangaTuiConfig::get().auto_page_turn_ms) {
+            return;
+        }
+
+        if selected + 1 >= self.pages.len() {
+            self.auto_page_turn_since = None;
+        }
+
+        self.next_page();
+    }
+
     fn next_page(&mut self) {
-        self.page_list_state.next()
+        let is_last_page = self.page_list_state.selected.unwrap_or(0) + 1 >= self.pages.len();
+
+        if is_last_page && !self.pages.is_empty() {
+            match MangaTuiConfig::get().end_of_chapter {
+                EndOfChapter::NextChapterPrompt => self.is_end_of_chapter_prompt_open = true,
+                EndOfChapter::ChapterList => {
+                    self.global_event_tx.send(Events::CloseReader).ok();
+                }
+                EndOfChapter::Nothing => {}
+            }
+            return;
+        }
+
+        self.page_list_state.next();
+        self.load_pages_around_selected();
+        self.restart_auto_page_turn();
     }
 
     fn previous_page(&mut self) {
         self.page_list_state.previous();
+        self.load_pages_around_selected();
+        self.restart_auto_page_turn();
+    }
+
+    /// Turning a page gives the new one the whole time of `auto_page_turn_ms`
+    fn restart_auto_page_turn(&mut self) {
+        if self.auto_page_turn_since.is_some() {
+            self.auto_page_turn_since = Some(Instant::now());
+        }
+    }
+
+    fn render_end_of_chapter_prompt(&mut self, area: Rect, buf: &mut Buffer) {
+        let prompt_area = centered_rect(area, 40, 20);
+
+        let (message, instructions) = match self.chapter.next_chapter.as_ref() {
+            Some(next_chapter) => (
+                format!("Next chapter: {next_chapter}"),
+                Line::from(vec![
+                    "Read it ".into(),
+                    Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+                    " Go back ".into(),
+                    Span::raw("<Esc>").style(*INSTRUCTIONS_STYLE),
+                    " Last page ".into(),
+                    Span::raw("<k>").style(*INSTRUCTIONS_STYLE),
+                ]),
+            ),
+            None => (
+                "This is the last chapter of the list".to_string(),
+                Line::from(vec![
+                    "Go back ".into(),
+                    Span::raw("<Esc>").style(*INSTRUCTIONS_STYLE),
+                    " Last page ".into(),
+                    Span::raw("<k>").style(*INSTRUCTIONS_STYLE),
+                ]),
+            ),
+        };
+
+        Paragraph::new(message)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::bordered()
+                    .title(format!(
+                        "End of Ch. {} {}",
+                        self.chapter.number, self.chapter.title
+                    ))
+                    .title_bottom(instructions),
+            )
+            .render(prompt_area, buf);
     }
 
     fn render_page_list(&mut self, area: Rect, buf: &mut Buffer) {
@@ -237,10 +958,25 @@ impl MangaReader {
 
     fn load_page(&mut self, maybe_data: Option<PageData>) {
         if let Some(data) = maybe_data {
+            self.failed_pages_in_a_row = 0;
+
+            let window = loaded_pages_window(
+                self.page_list_state.selected.unwrap_or(0),
+                self.pages.len(),
+                MangaTuiConfig::get().max_loaded_pages,
+            );
+
+            // The page was left behind while it was loading
+            if !window.contains(&data.index) {
+                return;
+            }
+
             match self.pages.get_mut(data.index) {
                 Some(page) => {
                     page.image_state = Some(data.protocol);
+                    page.is_loading = false;
                     page.dimensions = Some(data.dimensions);
+                    page.format = data.format;
                 }
                 None => {
                     // Todo! indicate that the page couldnot be loaded
@@ -255,54 +991,352 @@ impl MangaReader {
         }
     }
 
+    /// The pages are written to `EXTERNAL_VIEWER_DIR` and the page being read is opened with the
+    /// default image viewer, where the rest of the chapter can be browsed. Downloaded chapters are
+    /// opened from their own folder
+    fn open_in_external_viewer(&mut self) {
+        let selected = self.page_list_state.selected.unwrap_or(0);
+        let tx = self.global_event_tx.clone();
+
+        if let Some(dir) = self.pages_dir.as_ref() {
+            if let Some(page) = self.pages.get(selected) {
+                if let Err(e) = open::that(dir.join(&page.url)) {
+                    write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    tx.send(Events::ShowNotification(
+                        "The page could not be opened in the image viewer".to_string(),
+                    ))
+                    .ok();
+                }
+            }
+            return;
+        }
+
+        let requests: Vec<PageRequest> = (0..self.pages.len())
+            .filter_map(|index| self.page_request(index))
+            .collect();
+
+        if requests.is_empty() {
+            return;
+        }
+
+        tx.send(Events::ShowNotification(
+            "Preparing the chapter for the image viewer".to_string(),
+        ))
+        .ok();
+
+        let viewer_dir = EXTERNAL_VIEWER_DIR.join(&self.chapter_id);
+        let page_downloads = Arc::clone(&self.page_downloads);
+
+        self.image_tasks.spawn(async move {
+            let result: Result<(), ErrorType<'static>> = async {
+                create_dir_all(&viewer_dir).map_err(|e| ErrorType::FromError(Box::new(e)))?;
+
+                let mut selected_page = None;
+
+                for request in requests {
+                    let extension = Path::new(&request.file_name)
+                        .extension()
+                        .map_or("png".to_string(), |ext| ext.to_string_lossy().to_string());
+                    let path = viewer_dir.join(format!("{:03}.{extension}", request.index + 1));
+
+                    if request.index == selected {
+                        selected_page = Some(path.clone());
+                    }
+
+                    let bytes = get_page_bytes(request, Arc::clone(&page_downloads)).await?;
+                    write(&path, bytes).map_err(|e| ErrorType::FromError(Box::new(e)))?;
+                }
+
+                open::that(selected_page.unwrap_or(viewer_dir))
+                    .map_err(|e| ErrorType::FromError(Box::new(e)))
+            }
+            .await;
+
+            if let Err(e) = result {
+                write_to_error_log(e);
+                tx.send(Events::ShowNotification(
+                    "The chapter could not be opened in the image viewer".to_string(),
+                ))
+                .ok();
+            }
+        });
+    }
+
+    /// For pages which look wrong, what is known about the page being read is written to the error
+    /// log so it can be attached to a bug report
+    fn report_page(&mut self) {
+        let index = self.page_list_state.selected.unwrap_or(0);
+        let Some(page) = self.pages.get(index) else {
+            return;
+        };
+
+        let source = match self.pages_dir.as_ref() {
+            Some(dir) => dir.join(&page.url).display().to_string(),
+            None => format!(
+                "{}/{}/{}/{}",
+                self.base_url, page.page_type, self.chapter_id, page.url
+            ),
+        };
+
+        let format = page
+            .format
+            .map_or("unknown".to_string(), |format| format!("{format:?}"));
+
+        let dimensions = page
+            .dimensions
+            .map_or("unknown".to_string(), |(width, height)| {
+                format!("{width}x{height}")
+            });
+
+        let protocol = picker().map_or("none".to_string(), |picker| {
+            format!("{:?}", picker.protocol_type)
+        });
+
+        let report = format!(
+            "Page report: page {} of {} | source: {source} | format: {format} | dimensions: {dimensions} | protocol: {protocol} | loaded: {}",
+            index + 1,
+            self.pages.len(),
+            page.image_state.is_some(),
+        );
+
+        write_to_error_log(ErrorType::FromError(report.into()));
+
+        self.global_event_tx
+            .send(Events::ShowNotification(
+                "The details of the page were written to the error log".to_string(),
+            ))
+            .ok();
+    }
+
+    fn page_not_found(&mut self, index: usize) {
+        if let Some(page) = self.pages.get_mut(index) {
+            page.is_loading = false;
+        }
+        if let Some(page_item) = self.pages_list.pages.get_mut(index) {
+            page_item.state = PageItemState::NotFound;
+        }
+
+        self.failed_pages_in_a_row += 1;
+        if self.failed_pages_in_a_row >= MAX_FAILED_PAGES_IN_A_ROW {
+            self.refresh_server();
+        }
+    }
+
+    /// The pages which could not be loaded are requested again if mangadex gives another server,
+    /// with the same one they are only requested again once they are turned to
+    fn refresh_server(&mut self) {
+        if self.is_refreshing_server || self.pages_dir.is_some() {
+            return;
+        }
+        self.is_refreshing_server = true;
+
+        let tx = self.local_event_tx.clone();
+        let chapter_id = self.chapter.id.clone();
+        self.image_tasks.spawn(async move {
+            let response = MangadexClient::global()
+                .get_chapter_pages(&chapter_id)
+                .await
+                .map_err(|e| write_to_error_log(ErrorType::FromError(Box::new(e))))
+                .ok();
+            tx.send(MangaReaderEvents::ServerRefreshed(response)).ok();
+        });
+    }
+
+    fn load_refreshed_server(&mut self, response: Option<ChapterPagesResponse>) {
+        self.is_refreshing_server = false;
+        self.failed_pages_in_a_row = 0;
+
+        if let Some(response) = response.filter(|response| response.base_url != self.base_url) {
+            self.base_url = response.base_url.clone();
+            self.chapter_id = response.chapter.hash.clone();
+            self.chapter_pages = response;
+            self.load_pages_around_selected();
+        }
+    }
+
+    fn fetch_page(&mut self, index: usize) {
+        let Some(page) = self.pages.get_mut(index) else {
+            return;
+        };
+        page.is_loading = true;
+        if let Some(page_item) = self.pages_list.pages.get_mut(index) {
+            page_item.state = PageItemState::Loading;
+        }
+
+        let Some(request) = self.page_request(index) else {
+            return;
+        };
+        let tx = self.local_event_tx.clone();
+        let page_downloads = Arc::clone(&self.page_downloads);
+        self.image_tasks.spawn(async move {
+            let image_response = get_page_bytes(request, page_downloads).await;
+            match image_response {
+                Ok(bytes) => {
+                    let format = image::guess_format(&bytes).ok();
+                    match decode_image(bytes) {
+                        Some(decoded) => {
+                            let page_data = PageData {
+                                dimensions: decoded.dimensions(),
+                                protocol: new_image_protocol(decoded),
+                                index,
+                                format,
+                            };
+                            tx.send(MangaReaderEvents::LoadPage(Some(page_data))).ok();
+                        }
+                        None => {
+                            tx.send(MangaReaderEvents::PageNotFound(index)).ok();
+                        }
+                    }
+                }
+                Err(e) => {
+                    write_to_error_log(e);
+                    tx.send(MangaReaderEvents::PageNotFound(index)).ok();
+                }
+            };
+        });
+    }
+
+    fn page_request(&self, index: usize) -> Option<PageRequest> {
+        let page = self.pages.get(index)?;
+        Some(PageRequest {
+            index,
+            file_name: page.url.clone(),
+            endpoint: format!("{}/{}/{}", self.base_url, page.page_type, self.chapter_id),
+            chapter_id: self.chapter_id.clone(),
+            page_type: page.page_type.to_string(),
+            pages_dir: self.pages_dir.clone(),
+        })
+    }
+
+    /// Load the pages inside of the window of `max_loaded_pages` around the page being read and drop
+    /// the images of the ones outside of it, so long chapters don't keep every page in memory
+    fn load_pages_around_selected(&mut self) {
+        let selected = self.page_list_state.selected.unwrap_or(0);
+
+        let window = loaded_pages_window(
+            selected,
+            self.pages.len(),
+            MangaTuiConfig::get().max_loaded_pages,
+        );
+
+        // The page being read and the ones after it are requested first, so they are the first
+        // ones to be downloaded when the downloads are limited
+        let fetch_order = (selected..window.end).chain((window.start..selected).rev());
+
+        for index in fetch_order {
+            if let Some(page) = self.pages.get(index) {
+                if page.image_state.is_none() && !page.is_loading {
+                    self.fetch_page(index);
+                }
+            }
+        }
+
+        for index in (0..self.pages.len()).filter(|index| !window.contains(index)) {
+            let page = &mut self.pages[index];
+            if page.image_state.take().is_some() || page.is_loading {
+                page.is_loading = false;
+                if let Some(page_item) = self.pages_list.pages.get_mut(index) {
+                    page_item.state = PageItemState::Loading;
+                }
+            }
+        }
+    }
+
     fn tick(&mut self) {
         self.pages_list.on_tick();
+        self.auto_page_turn();
         if let Ok(background_event) = self.local_event_rx.try_recv() {
             match background_event {
                 MangaReaderEvents::FetchPages => {
-                    let mut pages_list: Vec<PagesItem> = vec![];
-                    for (index, page) in self.pages.iter().enumerate() {
-                        let file_name = page.url.clone();
-                        let endpoint =
-                            format!("{}/{}/{}", self.base_url, page.page_type, self.chapter_id);
-                        let tx = self.local_event_tx.clone();
-                        pages_list.push(PagesItem::new(index));
-                        self.image_tasks.spawn(async move {
-                            let image_response = MangadexClient::global()
-                                .get_chapter_page(&endpoint, &file_name)
-                                .await;
-                            match image_response {
-                                Ok(bytes) => {
-                                    let dyn_img = Reader::new(std::io::Cursor::new(bytes))
-                                        .with_guessed_format();
-
-                                    if let Err(err) = dyn_img {
-                                        return write_to_error_log(ErrorType::FromError(Box::new(
-                                            err,
-                                        )));
-                                    }
-
-                                    let maybe_decoded = dyn_img.unwrap().decode();
-
-                                    if let Ok(decoded) = maybe_decoded {
-                                        let page_data = PageData {
-                                            dimensions: decoded.dimensions(),
-                                            protocol: PICKER.unwrap().new_resize_protocol(decoded),
-                                            index,
-                                        };
-                                        tx.send(MangaReaderEvents::LoadPage(Some(page_data))).ok();
-                                    }
-                                }
-                                Err(e) => {
-                                    write_to_error_log(ErrorType::FromError(Box::new(e)));
-                                }
-                            };
-                        });
-                    }
-                    self.pages_list = PagesList::new(pages_list);
+                    self.pages_list = PagesList::new(
+                        (0..self.pages.len())
+                            .map(|number| {
+                                let mut page_item = PagesItem::new(number);
+                                page_item.is_bookmarked = self.bookmarked_pages.contains(&number);
+                                page_item
+                            })
+                            .collect(),
+                    );
+                    self.load_pages_around_selected();
                 }
                 MangaReaderEvents::LoadPage(maybe_data) => self.load_page(maybe_data),
+                MangaReaderEvents::PageNotFound(index) => self.page_not_found(index),
+                MangaReaderEvents::SetDownloadProgress(progress) => {
+                    self.download_progress = Some(progress);
+                }
+                MangaReaderEvents::ChapterFinishedDownloading => self.finish_download(),
+                MangaReaderEvents::ServerRefreshed(response) => {
+                    self.load_refreshed_server(response);
+                }
+                MangaReaderEvents::DownloadError => {
+                    self.download_progress = None;
+                    self.global_event_tx
+                        .send(Events::ShowNotification(
+                            "The chapter could not be downloaded".to_string(),
+                        ))
+                        .ok();
+                }
             }
         }
     }
 }
+
+async fn get_page_bytes(
+    request: PageRequest,
+    page_downloads: Arc<Semaphore>,
+) -> Result<Bytes, ErrorType<'static>> {
+    let PageRequest {
+        index,
+        file_name,
+        endpoint,
+        chapter_id,
+        page_type,
+        pages_dir,
+    } = request;
+
+    match pages_dir {
+        Some(dir) => read(dir.join(&file_name))
+            .map(Bytes::from)
+            .map_err(|e| ErrorType::FromError(Box::new(e))),
+        None => match get_cached_page(&chapter_id, &page_type, index) {
+            Some(bytes) => Ok(bytes),
+            None => {
+                let _permit = page_downloads.acquire_owned().await;
+                let response = MangadexClient::global()
+                    .get_chapter_page(&endpoint, &file_name)
+                    .await;
+                if let Ok(bytes) = response.as_ref() {
+                    if let Err(e) = cache_page(&chapter_id, &page_type, index, bytes) {
+                        write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    }
+                }
+                response.map_err(|e| ErrorType::FromError(Box::new(e)))
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reading_direction_is_guessed_from_the_original_language() {
+        assert_eq!(
+            ReadingDirection::RightToLeft,
+            ReadingDirection::from_original_language(Some("ja"))
+        );
+        assert_eq!(
+            ReadingDirection::Vertical,
+            ReadingDirection::from_original_language(Some("ko"))
+        );
+        assert_eq!(
+            ReadingDirection::LeftToRight,
+            ReadingDirection::from_original_language(Some("zh"))
+        );
+        assert_eq!(
+            ReadingDirection::LeftToRight,
+            ReadingDirection::from_original_language(None)
+        );
+    }
+}
@@ -1,23 +1,42 @@
+use crate::backend::blocklist::{BlockedItem, BlockedKind, Blocklist};
+use crate::backend::clipboard::{copy_to_clipboard, CopiedTo};
 use crate::backend::database::save_plan_to_read;
 use crate::backend::database::MangaPlanToReadSave;
 use crate::backend::error_log::write_to_error_log;
 use crate::backend::error_log::ErrorType;
-use crate::backend::fetch::MangadexClient;
+use crate::backend::fetch::{
+    searchable_pages, with_retries, FetchError, MangadexClient, ITEMS_PER_PAGE_SEARCH,
+};
+use crate::backend::filter::{ContentRating, Filters, IntoParam};
+use crate::backend::session::SearchResults;
 use crate::backend::tui::Events;
-use crate::backend::SearchMangaResponse;
+use crate::backend::{Data, SearchMangaResponse};
 use crate::common::Artist;
 use crate::common::Author;
+use crate::common::Manga;
+use crate::config::{CoverAspect, EmptySearch, MangaTuiConfig};
 use crate::global::ERROR_STYLE;
 use crate::global::INSTRUCTIONS_STYLE;
+use crate::picker;
+use crate::utils::centered_rect;
+use crate::utils::clamp_selection;
+use crate::utils::format_share_text;
+use crate::utils::from_manga_response;
+use crate::utils::indexes_by_distance;
+use crate::utils::paste_into_input;
+use crate::utils::preferred_description;
+use crate::utils::relaxed_search_term;
 use crate::utils::render_search_bar;
 use crate::utils::search_manga_cover;
+use crate::utils::set_tag_group_style;
+use crate::utils::truncate_input;
+use crate::view::pages::{MAX_EVENTS_PER_TICK, TICK_TIME_BUDGET};
 use crate::view::widgets::filter_widget::state::FilterState;
 use crate::view::widgets::filter_widget::FilterWidget;
 use crate::view::widgets::search::*;
 use crate::view::widgets::Component;
 use crate::view::widgets::ImageHandler;
 use crate::view::widgets::StatefulWidgetFrame;
-use crate::PICKER;
 use crossterm::event::KeyEvent;
 use crossterm::event::MouseButton;
 use crossterm::event::MouseEvent;
@@ -25,16 +44,51 @@ use crossterm::event::MouseEventKind;
 use crossterm::event::{self, KeyCode};
 use ratatui::{prelude::*, widgets::*};
 use ratatui_image::protocol::StatefulProtocol;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use strum::IntoEnumIterator;
 use throbber_widgets_tui::Throbber;
 use throbber_widgets_tui::ThrobberState;
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
-use tokio::task::JoinSet;
+use tokio::task::{AbortHandle, JoinSet};
 use tui_input::backend::crossterm::EventHandler;
 use tui_input::Input;
 use tui_widget_list::ListState;
 
 use self::text::ToSpan;
 
+const MIN_PREVIEW_DESCRIPTION_PERCENTAGE: u16 = 20;
+const MAX_PREVIEW_DESCRIPTION_PERCENTAGE: u16 = 80;
+
+/// Which part of the preview takes most of its space, it overrides the configured split until the
+/// description is resized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PreviewEmphasis {
+    Cover,
+    Text,
+}
+
+impl PreviewEmphasis {
+    fn toggle(self) -> Self {
+        match self {
+            Self::Cover => Self::Text,
+            Self::Text => Self::Cover,
+        }
+    }
+
+    fn description_percentage(self) -> u16 {
+        match self {
+            Self::Cover => MIN_PREVIEW_DESCRIPTION_PERCENTAGE,
+            Self::Text => MAX_PREVIEW_DESCRIPTION_PERCENTAGE,
+        }
+    }
+}
+
+/// How long to wait after the last keystroke before asking mangadex for suggestions
+const SUGGESTIONS_DEBOUNCE: Duration = Duration::from_millis(400);
+const MIN_CHARACTERS_FOR_SUGGESTIONS: usize = 3;
+
 /// Determine wheter or not mangas are being searched
 /// if so then this should not make a request until the most recent one finishes
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
@@ -42,7 +96,8 @@ enum PageState {
     SearchingMangas,
     DisplayingMangasFound,
     NotFound,
-    ErrorOcurred,
+    /// Holds how many attempts were made before giving up
+    ErrorOcurred(u32),
     #[default]
     Normal,
 }
@@ -51,7 +106,10 @@ enum PageState {
 pub enum SearchPageEvents {
     SearchCovers,
     LoadCover(Option<Box<dyn StatefulProtocol>>, String),
-    LoadMangasFound(Option<SearchMangaResponse>),
+    /// The error is how many attempts were made
+    LoadMangasFound(Result<SearchMangaResponse, u32>),
+    LoadSuggestions(Vec<Manga>),
+    LoadDidYouMean(String),
 }
 
 impl ImageHandler for SearchPageEvents {
@@ -75,6 +133,96 @@ pub enum SearchPageActions {
     PreviousPage,
     GoToMangaPage,
     PlanToRead,
+    EnlargePreviewDescription,
+    ShrinkPreviewDescription,
+    RetrySearch,
+    EnlargeMangaItems,
+    ShrinkMangaItems,
+    ToggleMangaItemTags,
+    TogglePreviewTags,
+    TogglePreviewEmphasis,
+    ShareManga,
+    RetryFailedCovers,
+    ScrollDescriptionDown,
+    ScrollDescriptionUp,
+    ToggleSearchScope,
+    SelectNextSuggestion,
+    SelectPreviousSuggestion,
+    FillQueryWithSuggestion,
+    GoToSuggestion,
+    HideManga,
+    ToggleBlocklist,
+    ScrollDownBlocklist,
+    ScrollUpBlocklist,
+    Unblock,
+    SearchDidYouMean,
+    ConfirmExplicitSearch,
+    DeclineExplicitSearch,
+    CycleResultsGrouping,
+    ClearResults,
+    TogglePreview,
+    ToggleExactTitleFirst,
+    CycleContentRating,
+    CloseDetails,
+    OpenDetailedManga,
+}
+
+/// What happens when a manga of the search results is opened with `<Enter>`/`<r>`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectAction {
+    /// Show the manga page with its chapters
+    #[default]
+    OpenChapterList,
+    /// Open the manga page and start reading the first chapter which is not read yet
+    ContinueReading,
+    /// Show the details of the manga on a popup, from which its manga page can be opened
+    OpenDetails,
+}
+
+/// What `<Enter>` does while the results are shown and nothing is being typed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnterOnResults {
+    /// Open the selected manga like `<r>`, see `SelectAction`
+    #[default]
+    OpenManga,
+    /// Focus the search bar to type a new search
+    Search,
+}
+
+/// What the current results were searched with, searching again with the same query would only
+/// bring the same results
+#[derive(Debug, PartialEq, Eq)]
+struct SearchQuery {
+    term: String,
+    /// The filters as they are sent to mangadex
+    filters: String,
+    scope: SearchScope,
+}
+
+/// Determines what the search term is matched against
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SearchScope {
+    #[default]
+    Titles,
+    TitlesAndDescriptions,
+}
+
+impl SearchScope {
+    fn toggle(self) -> Self {
+        match self {
+            Self::Titles => Self::TitlesAndDescriptions,
+            Self::TitlesAndDescriptions => Self::Titles,
+        }
+    }
+
+    fn as_human_readable(self) -> &'static str {
+        match self {
+            Self::Titles => "titles",
+            Self::TitlesAndDescriptions => "titles and descriptions",
+        }
+    }
 }
 
 #[derive(Default, PartialEq, Eq, PartialOrd, Ord)]
@@ -99,7 +247,54 @@ pub struct SearchPage {
     mangas_found_list: MangasFoundList,
     filter_state: FilterState,
     manga_added_to_plan_to_read: Option<String>,
+    search_scope: SearchScope,
+    suggestions: Vec<Manga>,
+    suggestions_state: ratatui::widgets::ListState,
+    /// Only the most recent suggestion request is kept, the previous one is aborted on each
+    /// keystroke
+    suggestions_task: Option<AbortHandle>,
+    is_blocklist_open: bool,
+    blocklist_state: ratatui::widgets::ListState,
+    /// Lines scrolled of the description of the selected manga, it goes back to the top when
+    /// another manga is selected
+    description_scroll: u16,
+    /// Shows every tag of the selected manga over the preview, since only the ones which fit are
+    /// shown on it
+    is_preview_tags_expanded: bool,
+    /// Only lasts for the session, `None` uses `preview_description_percentage` of the config
+    preview_emphasis: Option<PreviewEmphasis>,
+    /// Hidden with `<v>` so the list uses the whole width, it stays hidden for the session
+    is_preview_hidden: bool,
+    /// Results whose title is the same as the search term, or the closest to it, are moved to the
+    /// top. Only lasts for the session
+    is_exact_title_first: bool,
+    last_search: Option<SearchQuery>,
+    /// Set while `confirm_explicit_search` asks whether explicit results can be shown
+    is_explicit_confirmation_open: bool,
+    /// The selected manga is shown on a popup, see `SelectAction::OpenDetails`
+    is_details_open: bool,
+    /// Once explicit results are accepted they are not asked about again for the session
+    is_explicit_search_confirmed: bool,
+    /// The current search was declined to show explicit results, so it and its next pages only
+    /// show safe mangas
+    is_safe_only_search: bool,
+    /// Manga selected once the results of a restored session are loaded
+    restored_selection: Option<usize>,
+    /// Whether the last edit of the search bar went over `max_search_length` and was cut
+    is_search_term_truncated: bool,
+    /// Mangas of the current results whose cover has not been loaded or failed yet, along with
+    /// how many covers were requested
+    pending_covers: HashSet<String>,
+    total_covers: usize,
+    /// Title of a manga found with a looser version of a search term which found nothing
+    did_you_mean: Option<String>,
     tasks: JoinSet<()>,
+    /// The covers are requested apart from the other tasks so the ones of the previous results
+    /// can be aborted as soon as they are replaced
+    cover_tasks: JoinSet<()>,
+    /// Covers requested again after the first request of every cover, retried with `<C>` or
+    /// prefetched around the selection, so they are not requested twice
+    cover_requests: HashMap<String, AbortHandle>,
 }
 
 /// This contains the data the application gets when doing a search
@@ -111,28 +306,74 @@ struct MangasFoundList {
     page: u32,
 }
 
+impl MangasFoundList {
+    /// Must be called whenever mangas are taken out of the list, so the highlight and the preview
+    /// keep pointing to a manga
+    fn clamp_selection(&mut self) {
+        let selected = clamp_selection(self.state.selected, self.widget.mangas.len());
+        self.state.select(selected);
+    }
+}
+
 impl Component for SearchPage {
     type Actions = SearchPageActions;
     fn render(&mut self, area: Rect, frame: &mut Frame<'_>) {
+        let input_constraint = if self.is_search_bar_collapsed() {
+            Constraint::Length(1)
+        } else {
+            Constraint::Max(4)
+        };
+
         let search_page_layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Max(4), Constraint::Fill(1)]);
+            .constraints([input_constraint, Constraint::Fill(1)]);
 
         let [input_area, manga_area] = search_page_layout.areas(area);
 
-        self.render_input_area(input_area, frame);
+        if self.is_search_bar_collapsed() {
+            self.render_collapsed_input_area(input_area, frame.buffer_mut());
+        } else {
+            self.render_input_area(input_area, frame);
+        }
 
         self.render_manga_found_area(manga_area, frame);
+
+        self.render_suggestions(input_area, frame.buffer_mut());
+
+        if self.is_blocklist_open {
+            self.render_blocklist(area, frame.buffer_mut());
+        }
+
+        if self.is_explicit_confirmation_open {
+            self.render_explicit_confirmation(area, frame.buffer_mut());
+        }
+
+        if self.is_details_open {
+            self.render_details(area, frame.buffer_mut());
+        }
     }
 
     fn update(&mut self, action: SearchPageActions) {
         match action {
             SearchPageActions::ToggleFilters => self.open_advanced_filters(),
             SearchPageActions::StartTyping => self.focus_search_bar(),
-            SearchPageActions::StopTyping => self.input_mode = InputMode::Idle,
+            SearchPageActions::StopTyping => {
+                self.input_mode = InputMode::Idle;
+                self.clear_suggestions();
+            }
             SearchPageActions::Search => {
+                self.clear_suggestions();
+                if self.is_showing_results_of(&self.current_query()) {
+                    self.global_event_tx
+                        .send(Events::ShowNotification(
+                            "The results of this search are already shown, press <R> to search again"
+                                .to_string(),
+                        ))
+                        .ok();
+                    return;
+                }
                 self.mangas_found_list.page = 1;
-                self.search_mangas();
+                self.new_search();
             }
             SearchPageActions::ScrollUp => self.scroll_up(),
             SearchPageActions::ScrollDown => self.scroll_down(),
@@ -141,23 +382,114 @@ impl Component for SearchPage {
             SearchPageActions::GoToMangaPage => {
                 let manga_selected = self.get_current_manga_selected();
                 if let Some(manga) = manga_selected {
+                    let event = match MangaTuiConfig::get().select_action {
+                        SelectAction::OpenChapterList => Events::GoToMangaPage(manga.clone()),
+                        SelectAction::ContinueReading => Events::ContinueReading(manga.clone()),
+                        SelectAction::OpenDetails => {
+                            self.is_details_open = true;
+                            return;
+                        }
+                    };
+                    self.global_event_tx.send(event).ok();
+                }
+            }
+            SearchPageActions::CloseDetails => self.is_details_open = false,
+            SearchPageActions::OpenDetailedManga => {
+                self.is_details_open = false;
+                if let Some(item) = self.get_current_manga_selected() {
                     self.global_event_tx
-                        .send(Events::GoToMangaPage(manga.clone()))
+                        .send(Events::GoToMangaPage(item.clone()))
                         .ok();
                 }
             }
             SearchPageActions::PlanToRead => self.plan_to_read(),
+            SearchPageActions::EnlargePreviewDescription => self.resize_preview_description(10),
+            SearchPageActions::ShrinkPreviewDescription => self.resize_preview_description(-10),
+            SearchPageActions::RetrySearch => self.search_mangas(),
+            SearchPageActions::SearchDidYouMean => self.search_did_you_mean(),
+            SearchPageActions::EnlargeMangaItems => self.resize_manga_items(1),
+            SearchPageActions::ShrinkMangaItems => self.resize_manga_items(-1),
+            SearchPageActions::ToggleMangaItemTags => {
+                MangaTuiConfig::update(|config| {
+                    config.search_item_tags = !config.search_item_tags;
+                });
+            }
+            SearchPageActions::TogglePreviewTags => {
+                self.is_preview_tags_expanded = !self.is_preview_tags_expanded;
+            }
+            SearchPageActions::TogglePreviewEmphasis => self.toggle_preview_emphasis(),
+            SearchPageActions::CycleResultsGrouping => self.cycle_results_grouping(),
+            SearchPageActions::ClearResults => self.clear_results(),
+            SearchPageActions::TogglePreview => self.is_preview_hidden = !self.is_preview_hidden,
+            SearchPageActions::ToggleExactTitleFirst => self.toggle_exact_title_first(),
+            SearchPageActions::CycleContentRating => self.cycle_content_rating(),
+            SearchPageActions::ShareManga => self.share_manga(),
+            SearchPageActions::RetryFailedCovers => self.retry_failed_covers(),
+            SearchPageActions::ScrollDescriptionDown => {
+                self.description_scroll = self.description_scroll.saturating_add(1);
+            }
+            SearchPageActions::ScrollDescriptionUp => {
+                self.description_scroll = self.description_scroll.saturating_sub(1);
+            }
+            SearchPageActions::ToggleSearchScope => self.search_scope = self.search_scope.toggle(),
+            SearchPageActions::SelectNextSuggestion => self.select_next_suggestion(),
+            SearchPageActions::SelectPreviousSuggestion => self.select_previous_suggestion(),
+            SearchPageActions::FillQueryWithSuggestion => self.fill_query_with_suggestion(),
+            SearchPageActions::GoToSuggestion => self.go_to_suggestion(),
+            SearchPageActions::HideManga => self.hide_manga(),
+            SearchPageActions::ToggleBlocklist => {
+                self.is_blocklist_open = !self.is_blocklist_open;
+                self.blocklist_state.select(Some(0));
+            }
+            SearchPageActions::ScrollDownBlocklist => {
+                let last = Blocklist::get().items.len().saturating_sub(1);
+                let next = self.blocklist_state.selected().map_or(0, |index| index + 1);
+                self.blocklist_state.select(Some(next.min(last)));
+            }
+            SearchPageActions::ScrollUpBlocklist => {
+                let previous = self
+                    .blocklist_state
+                    .selected()
+                    .map_or(0, |index| index.saturating_sub(1));
+                self.blocklist_state.select(Some(previous));
+            }
+            SearchPageActions::Unblock => self.unblock(),
+            SearchPageActions::ConfirmExplicitSearch => {
+                self.is_explicit_confirmation_open = false;
+                self.is_explicit_search_confirmed = true;
+                self.search_mangas();
+            }
+            SearchPageActions::DeclineExplicitSearch => {
+                self.is_explicit_confirmation_open = false;
+                self.is_safe_only_search = true;
+                self.search_mangas();
+            }
         }
     }
     fn handle_events(&mut self, events: Events) {
         if self.filter_state.is_open {
-            self.filter_state.handle_events(events);
+            match events {
+                // Searching from the filters doesn't need a title, so mangas can be browsed by
+                // their tags alone
+                Events::Key(key_event)
+                    if key_event.code == KeyCode::Enter && !self.filter_state.is_typing =>
+                {
+                    self.filter_state.toggle();
+                    self.local_action_tx.send(SearchPageActions::Search).ok();
+                }
+                events => self.filter_state.handle_events(events),
+            }
         } else {
             match events {
                 Events::Key(key_event) => {
                     self.handle_key_events(key_event);
                 }
                 Events::Mouse(mouse_event) => self.handle_mouse_events(mouse_event),
+                Events::Paste(text) if self.input_mode == InputMode::Typing => {
+                    paste_into_input(&mut self.search_bar, &text);
+                    self.truncate_search_term();
+                    self.search_suggestions();
+                }
                 Events::Tick => self.tick(),
                 _ => {}
             }
@@ -168,7 +500,12 @@ impl Component for SearchPage {
         self.state = PageState::default();
         self.manga_added_to_plan_to_read = None;
         self.input_mode = InputMode::Idle;
+        self.clear_suggestions();
         self.mangas_found_list.state = ListState::default();
+        self.description_scroll = 0;
+        self.is_preview_tags_expanded = false;
+        self.pending_covers.clear();
+        self.did_you_mean = None;
         if !self.mangas_found_list.widget.mangas.is_empty() {
             self.mangas_found_list.widget.mangas = vec![];
         }
@@ -191,23 +528,108 @@ impl SearchPage {
             state: PageState::default(),
             mangas_found_list: MangasFoundList::default(),
             tasks: JoinSet::new(),
+            cover_tasks: JoinSet::new(),
+            cover_requests: HashMap::new(),
             filter_state: FilterState::new(),
             loader_state: ThrobberState::default(),
             manga_added_to_plan_to_read: None,
+            search_scope: SearchScope::default(),
+            suggestions: vec![],
+            suggestions_state: ratatui::widgets::ListState::default(),
+            suggestions_task: None,
+            is_blocklist_open: false,
+            blocklist_state: ratatui::widgets::ListState::default(),
+            description_scroll: 0,
+            is_preview_tags_expanded: false,
+            preview_emphasis: None,
+            is_preview_hidden: false,
+            is_exact_title_first: false,
+            last_search: None,
+            is_explicit_confirmation_open: false,
+            is_details_open: false,
+            is_explicit_search_confirmed: false,
+            is_safe_only_search: false,
+            restored_selection: None,
+            is_search_term_truncated: false,
+            pending_covers: HashSet::new(),
+            total_covers: 0,
+            did_you_mean: None,
+        }
+    }
+
+    /// With `collapse_search_bar` the search bar takes a single line while the results are browsed,
+    /// it's expanded again as soon as `<s>` is pressed to type
+    fn is_search_bar_collapsed(&self) -> bool {
+        self.input_mode == InputMode::Idle
+            && self.state == PageState::DisplayingMangasFound
+            && MangaTuiConfig::get().collapse_search_bar
+    }
+
+    fn render_collapsed_input_area(&self, area: Rect, buf: &mut Buffer) {
+        let mut line = Line::from(" Results");
+
+        if !self.search_bar.value().is_empty() {
+            line.push_span(" for ");
+            line.push_span(self.search_bar.value().to_string().bold());
+        }
+
+        line.spans.extend([
+            " Search again ".into(),
+            "<s>".to_span().style(*INSTRUCTIONS_STYLE),
+            " Filters ".into(),
+            "<f>".to_span().style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        if let Some(name) = self.manga_added_to_plan_to_read.as_ref() {
+            line.push_span(format!(" Added: {} to plan to read 📖", name).underlined());
         }
+
+        line.render(area, buf);
     }
 
     fn render_input_area(&self, area: Rect, frame: &mut Frame<'_>) {
         let [input_area, information_area] =
             Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
 
-        let input_help = match self.input_mode {
+        let mut input_help = match self.input_mode {
             InputMode::Idle => Line::from(vec![
                 "Press ".into(),
                 "<s>".to_span().style(*INSTRUCTIONS_STYLE),
                 " to search mangas ".into(),
                 "<f>".to_span().style(*INSTRUCTIONS_STYLE),
-                " to open advanced filters".into(),
+                " to open advanced filters ".into(),
+                format!("Searching in {} ", self.search_scope.as_human_readable()).into(),
+                "<t>".to_span().style(*INSTRUCTIONS_STYLE),
+                format!(
+                    " Exact title first: {} ",
+                    if self.is_exact_title_first {
+                        "on"
+                    } else {
+                        "off"
+                    }
+                )
+                .into(),
+                "<E>".to_span().style(*INSTRUCTIONS_STYLE),
+                format!(" Ratings: {} ", self.content_rating_level()).into(),
+                "<z>".to_span().style(*INSTRUCTIONS_STYLE),
+                " Blocklist ".into(),
+                "<B>".to_span().style(*INSTRUCTIONS_STYLE),
+                " Duplicate ".into(),
+                "<D>".to_span().style(*INSTRUCTIONS_STYLE),
+                " Switch ".into(),
+                "<Tab>".to_span().style(*INSTRUCTIONS_STYLE),
+                " Close ".into(),
+                "<X>".to_span().style(*INSTRUCTIONS_STYLE),
+            ]),
+            InputMode::Typing if !self.suggestions.is_empty() => Line::from(vec![
+                "Suggestions ".into(),
+                "<Up>/<Down>".to_span().style(*INSTRUCTIONS_STYLE),
+                " use suggestion ".into(),
+                "<Tab>".to_span().style(*INSTRUCTIONS_STYLE),
+                " <Enter>".to_span().style(*INSTRUCTIONS_STYLE),
+                " to search or read the suggestion ".into(),
+                "<Esc>".to_span().style(*INSTRUCTIONS_STYLE),
+                " to stop typing".into(),
             ]),
             InputMode::Typing => Line::from(vec![
                 "Press ".into(),
@@ -218,6 +640,16 @@ impl SearchPage {
             ]),
         };
 
+        if self.is_search_term_truncated && self.input_mode == InputMode::Typing {
+            input_help.push_span(
+                Span::raw(format!(
+                    " Cut to {} characters",
+                    MangaTuiConfig::get().max_search_length
+                ))
+                .style(*ERROR_STYLE),
+            );
+        }
+
         render_search_bar(
             self.input_mode == InputMode::Typing,
             input_help,
@@ -245,9 +677,11 @@ impl SearchPage {
 
     fn render_manga_found_area(&mut self, area: Rect, frame: &mut Frame<'_>) {
         let buf = frame.buffer_mut();
-        let [manga_list_area, preview_area] =
-            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
-                .areas(area);
+        let [manga_list_area, preview_area] = if self.is_preview_hidden {
+            [area, Rect::default()]
+        } else {
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(area)
+        };
 
         match self.state {
             PageState::Normal => {
@@ -263,21 +697,33 @@ impl SearchPage {
                 StatefulWidget::render(loader, area, buf, &mut self.loader_state);
             }
             PageState::NotFound => {
-                Block::bordered()
-                    .title("No mangas were found")
-                    .render(area, buf);
+                let block = Block::bordered().title("No mangas were found");
+
+                match self.did_you_mean.as_ref() {
+                    Some(title) => Paragraph::new(Line::from(vec![
+                        format!("Did you mean {title}? Search it ").into(),
+                        Span::raw("<y>").style(*INSTRUCTIONS_STYLE),
+                    ]))
+                    .block(block)
+                    .wrap(Wrap { trim: true })
+                    .render(area, buf),
+                    None => block.render(area, buf),
+                }
             }
-            PageState::ErrorOcurred => {
+            PageState::ErrorOcurred(attempts) => {
                 Block::bordered()
-                    .title(
-                        "An error ocurred when searching mangas, please try again"
-                            .to_span()
-                            .style(*ERROR_STYLE),
-                    )
+                    .title(Line::from(vec![
+                        format!(
+                            "An error ocurred when searching mangas, tried {attempts} times. Try again "
+                        )
+                        .to_span()
+                        .style(*ERROR_STYLE),
+                        Span::raw("<r>").style(*INSTRUCTIONS_STYLE),
+                    ]))
                     .render(area, buf);
             }
             PageState::DisplayingMangasFound => {
-                let total_pages = self.mangas_found_list.total_result as f64 / 10_f64;
+                let total_pages = searchable_pages(self.mangas_found_list.total_result);
 
                 let list_instructions = Line::from(vec![
                     "Go down ".into(),
@@ -287,14 +733,22 @@ impl SearchPage {
                     " Plan to read ".into(),
                     Span::raw("<p>").style(*INSTRUCTIONS_STYLE),
                     " Read ".into(),
-                    Span::raw("<r>").style(*INSTRUCTIONS_STYLE),
+                    Span::raw(match MangaTuiConfig::get().enter_on_results {
+                        EnterOnResults::OpenManga => "<r>/<Enter>",
+                        EnterOnResults::Search => "<r>",
+                    })
+                    .style(*INSTRUCTIONS_STYLE),
+                    " Hide ".into(),
+                    Span::raw("<x>").style(*INSTRUCTIONS_STYLE),
+                    " Share ".into(),
+                    Span::raw("<c>").style(*INSTRUCTIONS_STYLE),
                 ]);
 
-                let pagination_instructions = Line::from(vec![
+                let mut pagination_instructions = Line::from(vec![
                     format!(
                         "Page : {} of {}, total : {} ",
                         self.mangas_found_list.page,
-                        total_pages.ceil(),
+                        total_pages,
                         self.mangas_found_list.total_result
                     )
                     .into(),
@@ -302,8 +756,38 @@ impl SearchPage {
                     Span::raw("<w>").style(*INSTRUCTIONS_STYLE),
                     " Previous ".into(),
                     Span::raw("<b>").style(*INSTRUCTIONS_STYLE),
+                    " Refresh ".into(),
+                    Span::raw("<R>").style(*INSTRUCTIONS_STYLE),
+                    " Item height ".into(),
+                    Span::raw("<[>/<]>").style(*INSTRUCTIONS_STYLE),
+                    " Tags ".into(),
+                    Span::raw("<g>").style(*INSTRUCTIONS_STYLE),
+                    " Group ".into(),
+                    Span::raw("<G>").style(*INSTRUCTIONS_STYLE),
+                    " Clear ".into(),
+                    Span::raw("<Backspace>").style(*INSTRUCTIONS_STYLE),
+                    " Preview ".into(),
+                    Span::raw("<v>").style(*INSTRUCTIONS_STYLE),
                 ]);
 
+                if self.mangas_found_list.page == total_pages
+                    && total_pages * ITEMS_PER_PAGE_SEARCH < self.mangas_found_list.total_result
+                {
+                    pagination_instructions
+                        .push_span(" End of the searchable results, refine your search ");
+                }
+
+                if !self.pending_covers.is_empty() {
+                    pagination_instructions.push_span(format!(
+                        " Loading covers {}/{}",
+                        self.total_covers - self.pending_covers.len(),
+                        self.total_covers
+                    ));
+                } else if picker().is_some() && !self.failed_covers().is_empty() {
+                    pagination_instructions.push_span(" Retry failed covers ");
+                    pagination_instructions.push_span(Span::raw("<C>").style(*INSTRUCTIONS_STYLE));
+                }
+
                 Block::bordered()
                     .title_top(list_instructions)
                     .title_bottom(pagination_instructions)
@@ -322,7 +806,17 @@ impl SearchPage {
                         &mut self.mangas_found_list.state,
                     );
                     let loader_state = self.loader_state.clone();
-                    if let Some(manga_selected) = self.get_current_manga_selected_mut() {
+                    let description_percentage = self.preview_description_percentage();
+                    // The fields are borrowed separately so the description scroll can be clamped
+                    // while the selected manga is borrowed
+                    let manga_selected = self
+                        .mangas_found_list
+                        .state
+                        .selected
+                        .and_then(|index| self.mangas_found_list.widget.mangas.get_mut(index));
+
+                    if let Some(manga_selected) = manga_selected.filter(|_| !self.is_preview_hidden)
+                    {
                         StatefulWidget::render(
                             MangaPreview::new(
                                 &manga_selected.manga.title,
@@ -330,12 +824,18 @@ impl SearchPage {
                                 &manga_selected.manga.tags,
                                 &manga_selected.manga.content_rating,
                                 &manga_selected.manga.status,
+                                description_percentage,
                                 loader_state,
-                            ),
+                            )
+                            .description_scroll(&mut self.description_scroll),
                             preview_area,
                             buf,
                             &mut manga_selected.image_state,
-                        )
+                        );
+
+                        if self.is_preview_tags_expanded {
+                            Self::render_preview_tags(&manga_selected.manga, preview_area, buf);
+                        }
                     }
                 }
             }
@@ -345,12 +845,34 @@ impl SearchPage {
         }
     }
 
+    fn render_preview_tags(manga: &Manga, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let instructions = Line::from(vec![
+            "Close ".into(),
+            Span::raw("<T>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        let tags: Vec<Span<'_>> = manga.tags.iter().map(set_tag_group_style).collect();
+
+        Paragraph::new(Line::from(tags))
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::bordered()
+                    .title(format!("Tags of {}", manga.title))
+                    .title_bottom(instructions),
+            )
+            .render(area, buf);
+    }
+
     fn render_filters(&mut self, area: Rect, frame: &mut Frame<'_>) {
         let filter_instructions = Line::from(vec![
             "Close ".into(),
             Span::raw("<f>").style(*INSTRUCTIONS_STYLE),
             " Reset filters ".into(),
             Span::raw("<r>").style(*INSTRUCTIONS_STYLE),
+            " Search ".into(),
+            Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
         ]);
 
         FilterWidget::new()
@@ -364,23 +886,20 @@ impl SearchPage {
 
     fn scroll_down(&mut self) {
         self.mangas_found_list.state.next();
+        self.description_scroll = 0;
+        self.prefetch_covers_around_selection();
     }
 
     fn scroll_up(&mut self) {
         self.mangas_found_list.state.previous();
+        self.description_scroll = 0;
+        self.prefetch_covers_around_selection();
     }
 
     fn open_advanced_filters(&mut self) {
         self.filter_state.toggle();
     }
 
-    fn get_current_manga_selected_mut(&mut self) -> Option<&mut MangaItem> {
-        if let Some(index) = self.mangas_found_list.state.selected {
-            return self.mangas_found_list.widget.mangas.get_mut(index);
-        }
-        None
-    }
-
     fn get_current_manga_selected(&self) -> Option<&MangaItem> {
         if let Some(index) = self.mangas_found_list.state.selected {
             return self.mangas_found_list.widget.mangas.get(index);
@@ -388,6 +907,30 @@ impl SearchPage {
         None
     }
 
+    /// Copy the selected manga as the text of `share_template`
+    fn share_manga(&mut self) {
+        let Some(item) = self.get_current_manga_selected() else {
+            return;
+        };
+
+        let text = format_share_text(&MangaTuiConfig::get().share_template, &item.manga);
+        let tx = self.global_event_tx.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let message = match copy_to_clipboard(&text) {
+                Ok(CopiedTo::Clipboard) => "Copied to the clipboard".to_string(),
+                Ok(CopiedTo::File(file)) => {
+                    format!("No clipboard available, saved to {}", file.display())
+                }
+                Err(e) => {
+                    write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    "The manga could not be copied".to_string()
+                }
+            };
+            tx.send(Events::ShowNotification(message)).ok();
+        });
+    }
+
     fn plan_to_read(&mut self) {
         if let Some(item) = self.get_current_manga_selected() {
             let plan_to_read_operation = save_plan_to_read(MangaPlanToReadSave {
@@ -405,193 +948,1114 @@ impl SearchPage {
         }
     }
 
-    fn abort_tasks(&mut self) {
-        self.tasks.abort_all();
-    }
+    /// Add the selected manga to the blocklist so it's never displayed again
+    fn hide_manga(&mut self) {
+        let Some(index) = self.mangas_found_list.state.selected else {
+            return;
+        };
 
-    fn handle_key_events(&mut self, key_event: KeyEvent) {
-        match self.input_mode {
-            InputMode::Idle => match key_event.code {
-                KeyCode::Char('s') => {
-                    self.local_action_tx
-                        .send(SearchPageActions::StartTyping)
-                        .ok();
-                }
-                KeyCode::Char('j') | KeyCode::Down => {
-                    self.local_action_tx
-                        .send(SearchPageActions::ScrollDown)
-                        .ok();
-                }
+        if index >= self.mangas_found_list.widget.mangas.len() {
+            return;
+        }
 
-                KeyCode::Char('k') | KeyCode::Up => {
-                    self.local_action_tx.send(SearchPageActions::ScrollUp).ok();
-                }
-                KeyCode::Char('w') => {
-                    self.local_action_tx.send(SearchPageActions::NextPage).ok();
-                }
-                KeyCode::Char('p') => {
-                    self.local_action_tx
-                        .send(SearchPageActions::PlanToRead)
-                        .ok();
-                }
-                KeyCode::Char('b') => {
-                    self.local_action_tx
-                        .send(SearchPageActions::PreviousPage)
-                        .ok();
-                }
-                KeyCode::Char('f') => {
-                    self.local_action_tx
-                        .send(SearchPageActions::ToggleFilters)
-                        .ok();
-                }
-                KeyCode::Char('r') | KeyCode::Enter => {
-                    self.local_action_tx
-                        .send(SearchPageActions::GoToMangaPage)
-                        .ok();
-                }
+        let manga = &self.mangas_found_list.widget.mangas[index].manga;
 
-                _ => {}
-            },
-            InputMode::Typing => match key_event.code {
-                KeyCode::Enter => {
-                    if self.state != PageState::SearchingMangas {
-                        self.local_action_tx.send(SearchPageActions::Search).ok();
-                    }
-                }
-                KeyCode::Esc => {
-                    self.local_action_tx
-                        .send(SearchPageActions::StopTyping)
-                        .ok();
-                }
-                _ => {
-                    self.search_bar.handle_event(&event::Event::Key(key_event));
-                }
-            },
-        }
-    }
+        let block_operation = Blocklist::block(BlockedItem {
+            id: manga.id.clone(),
+            kind: BlockedKind::Manga,
+            name: manga.title.clone(),
+        });
 
-    fn handle_mouse_events(&mut self, mouse_event: MouseEvent) {
-        match mouse_event.kind {
-            MouseEventKind::ScrollDown => {
-                self.local_action_tx
-                    .send(SearchPageActions::ScrollDown)
+        match block_operation {
+            Ok(()) => {
+                let item = self.mangas_found_list.widget.mangas.remove(index);
+                self.global_event_tx
+                    .send(Events::ShowNotification(format!(
+                        "Hidden: {}, restore it from the blocklist <B>",
+                        item.manga.title
+                    )))
                     .ok();
             }
-            MouseEventKind::ScrollUp => {
-                self.local_action_tx.send(SearchPageActions::ScrollUp).ok();
+            Err(e) => {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+                self.global_event_tx
+                    .send(Events::ShowNotification(
+                        "The manga could not be added to the blocklist".to_string(),
+                    ))
+                    .ok();
             }
-            MouseEventKind::Down(button) => {
-                if button == MouseButton::Left {
-                    self.local_action_tx
-                        .send(SearchPageActions::GoToMangaPage)
-                        .ok();
+        }
+
+        self.mangas_found_list.clamp_selection();
+    }
+
+    fn unblock(&mut self) {
+        let Some(item) = self
+            .blocklist_state
+            .selected()
+            .and_then(|index| Blocklist::get().items.get(index).cloned())
+        else {
+            return;
+        };
+
+        match Blocklist::unblock(&item.id, item.kind) {
+            Ok(()) => {
+                if item.kind == BlockedKind::Tag {
+                    self.filter_state.reload_tags();
                 }
+                let total = Blocklist::get().items.len();
+                self.blocklist_state
+                    .select(clamp_selection(self.blocklist_state.selected(), total));
             }
-            _ => {}
+            Err(e) => write_to_error_log(ErrorType::FromError(Box::new(e))),
         }
     }
-    pub fn is_typing_filter(&mut self) -> bool {
-        self.filter_state.is_typing
-    }
 
-    fn search_mangas(&mut self) {
-        self.clean_up();
+    fn render_blocklist(&mut self, area: Rect, buf: &mut Buffer) {
+        let popup_area = centered_rect(area, 50, 60);
 
-        self.state = PageState::SearchingMangas;
+        Clear.render(popup_area, buf);
 
-        let page = self.mangas_found_list.page;
+        let instructions = Line::from(vec![
+            "Close ".into(),
+            Span::raw("<B>").style(*INSTRUCTIONS_STYLE),
+            " Up/Down ".into(),
+            Span::raw("<k>/<j>").style(*INSTRUCTIONS_STYLE),
+            " Restore ".into(),
+            Span::raw("<d>").style(*INSTRUCTIONS_STYLE),
+        ]);
 
-        let tx = self.local_event_tx.clone();
+        let block = Block::bordered()
+            .title("Blocklist")
+            .title_bottom(instructions);
 
-        let manga_to_search = self.search_bar.value().to_string();
+        let blocklist = Blocklist::get();
 
-        let filters = self.filter_state.filters.clone();
+        if blocklist.items.is_empty() {
+            Paragraph::new("Nothing is hidden, hide a manga with <x> on the results or a tag with <x> on the filters")
+                .wrap(Wrap { trim: true })
+                .block(block)
+                .render(popup_area, buf);
+            return;
+        }
 
-        self.tasks.spawn(async move {
-            let search_response = MangadexClient::global()
-                .search_mangas(&manga_to_search, page, filters)
-                .await;
+        let items = List::new(blocklist.items.iter().map(|item| match item.kind {
+            BlockedKind::Manga => format!("📖 {}", item.name),
+            BlockedKind::Tag => format!("🏷 {} (tag)", item.name),
+        }))
+        .block(block)
+        .highlight_style(Style::default().on_blue());
 
-            match search_response {
-                Ok(mangas_found) => {
-                    tx.send(SearchPageEvents::LoadMangasFound(Some(mangas_found)))
-                        .ok();
-                }
-                Err(e) => {
-                    write_to_error_log(ErrorType::FromError(Box::new(e)));
-                    tx.send(SearchPageEvents::LoadMangasFound(None)).ok();
-                }
-            }
-        });
+        StatefulWidget::render(items, popup_area, buf, &mut self.blocklist_state);
     }
 
-    fn search_next_page(&mut self) {
-        if self.state == PageState::DisplayingMangasFound
-            && self.state != PageState::SearchingMangas
-            && self.mangas_found_list.page * 10 < self.mangas_found_list.total_result
-        {
-            self.mangas_found_list.page += 1;
-            self.search_mangas();
-        }
-    }
+    fn render_explicit_confirmation(&mut self, area: Rect, buf: &mut Buffer) {
+        let popup_area = centered_rect(area, 40, 20);
 
-    fn search_previous_page(&mut self) {
-        if self.state == PageState::DisplayingMangasFound
-            && self.state != PageState::SearchingMangas
-            && self.mangas_found_list.page != 1
-        {
-            self.mangas_found_list.page -= 1;
-            self.search_mangas();
-        }
-    }
+        Clear.render(popup_area, buf);
 
-    pub fn search_mangas_of_author(&mut self, author: Author) {
-        self.filter_state.set_author(author);
-        self.search_bar.reset();
-        self.mangas_found_list.page = 1;
-        self.search_mangas();
+        let instructions = Line::from(vec![
+            "Yes ".into(),
+            Span::raw("<y>").style(*INSTRUCTIONS_STYLE),
+            " No ".into(),
+            Span::raw("<n>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        Paragraph::new(
+            "Show explicit results for this search? Otherwise only safe mangas are searched",
+        )
+        .wrap(Wrap { trim: true })
+        .block(Block::bordered().title_bottom(instructions))
+        .render(popup_area, buf);
     }
 
-    pub fn search_mangas_of_artist(&mut self, artist: Artist) {
+    fn render_details(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(item) = self.get_current_manga_selected() else {
+            return;
+        };
+        let manga = &item.manga;
+
+        let popup_area = centered_rect(area, 60, 70);
+
+        Clear.render(popup_area, buf);
+
+        let instructions = Line::from(vec![
+            "Open ".into(),
+            Span::raw("<Enter>").style(*INSTRUCTIONS_STYLE),
+            " Close ".into(),
+            Span::raw("<Esc>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        let year = manga
+            .year
+            .map_or_else(|| "unknown".to_string(), |year| year.to_string());
+
+        let tags = manga
+            .tags
+            .iter()
+            .map(|tag| tag.name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+
+        let details = vec![
+            Line::from(vec!["Author: ".bold(), manga.author.name.as_str().into()]),
+            Line::from(vec!["Artist: ".bold(), manga.artist.name.as_str().into()]),
+            Line::from(vec!["Status: ".bold(), manga.status.as_str().into()]),
+            Line::from(vec!["Year: ".bold(), year.into()]),
+            Line::from(vec![
+                "Content rating: ".bold(),
+                manga.content_rating.as_str().into(),
+            ]),
+            Line::from(vec!["Tags: ".bold(), tags.into()]),
+            Line::default(),
+            Line::from(manga.description.as_str()),
+        ];
+
+        Paragraph::new(details)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::bordered()
+                    .title(manga.title.as_str())
+                    .title_bottom(instructions),
+            )
+            .render(popup_area, buf);
+    }
+
+    fn preview_description_percentage(&self) -> u16 {
+        self.preview_emphasis.map_or_else(
+            || {
+                MangaTuiConfig::get().preview_description_percentage.clamp(
+                    MIN_PREVIEW_DESCRIPTION_PERCENTAGE,
+                    MAX_PREVIEW_DESCRIPTION_PERCENTAGE,
+                )
+            },
+            PreviewEmphasis::description_percentage,
+        )
+    }
+
+    /// Used after the results are sorted again, so the same manga stays selected
+    fn select_manga(&mut self, id: &str) {
+        let index = self
+            .mangas_found_list
+            .widget
+            .mangas
+            .iter()
+            .position(|item| item.manga.id == id);
+        self.mangas_found_list.state.select(index);
+    }
+
+    /// The term the results were searched with, while exact titles come first
+    fn exact_title(&self) -> Option<String> {
+        self.last_search
+            .as_ref()
+            .filter(|_| self.is_exact_title_first)
+            .map(|search| search.term.clone())
+    }
+
+    /// The current results are sorted again right away, the selected manga stays selected
+    fn toggle_exact_title_first(&mut self) {
+        self.is_exact_title_first = !self.is_exact_title_first;
+
+        let selected_id = self
+            .get_current_manga_selected()
+            .map(|item| item.manga.id.clone());
+
+        let exact_title = self.exact_title();
+        self.mangas_found_list
+            .widget
+            .put_exact_title_first(exact_title);
+
+        if let Some(id) = selected_id {
+            self.select_manga(&id);
+        }
+    }
+
+    /// Only for this run, the next time the app starts with the content ratings of the config. The
+    /// search being shown is made again with the new ratings
+    fn cycle_content_rating(&mut self) {
+        self.filter_state.cycle_content_rating();
+
+        self.global_event_tx
+            .send(Events::ShowNotification(format!(
+                "Showing mangas rated {}",
+                self.content_rating_level()
+            )))
+            .ok();
+
+        if matches!(
+            self.state,
+            PageState::DisplayingMangasFound | PageState::NotFound
+        ) {
+            self.mangas_found_list.page = 1;
+            self.new_search();
+        }
+    }
+
+    /// The highest content rating selected, the ratings below it are usually selected too
+    fn content_rating_level(&self) -> String {
+        let ratings = &self.filter_state.filters.content_rating;
+        match ContentRating::iter()
+            .rev()
+            .find(|rating| ratings.contains(rating))
+        {
+            Some(ContentRating::Safe) => "safe".to_string(),
+            Some(highest) => format!("up to {highest}"),
+            None => "any".to_string(),
+        }
+    }
+
+    /// The grouping is kept in the config, the selected manga stays selected in its new place
+    fn cycle_results_grouping(&mut self) {
+        let selected_id = self
+            .get_current_manga_selected()
+            .map(|item| item.manga.id.clone());
+
+        MangaTuiConfig::update(|config| config.results_grouping = config.results_grouping.next());
+        let grouping = MangaTuiConfig::get().results_grouping;
+
+        self.mangas_found_list.widget.group(grouping);
+
+        if let Some(id) = selected_id {
+            self.select_manga(&id);
+        }
+
+        let notification = match grouping {
+            ResultsGrouping::None => "The results are not grouped",
+            ResultsGrouping::Status => "The results are grouped by status",
+            ResultsGrouping::Year => "The results are grouped by year",
+        };
+        self.global_event_tx
+            .send(Events::ShowNotification(notification.to_string()))
+            .ok();
+    }
+
+    /// The first time it switches to whichever part is smaller at the moment
+    fn toggle_preview_emphasis(&mut self) {
+        let emphasis = match self.preview_emphasis {
+            Some(emphasis) => emphasis.toggle(),
+            None if self.preview_description_percentage() > 50 => PreviewEmphasis::Cover,
+            None => PreviewEmphasis::Text,
+        };
+        self.preview_emphasis = Some(emphasis);
+        self.description_scroll = 0;
+    }
+
+    /// The split between the cover and the description of the preview is kept in the config so
+    /// it's the same the next time the app is opened, resizing starts from the emphasized split
+    /// if there is one
+    fn resize_preview_description(&mut self, amount: i16) {
+        let current = self.preview_description_percentage();
+        self.preview_emphasis = None;
+        MangaTuiConfig::update(|config| {
+            config.preview_description_percentage = current.saturating_add_signed(amount).clamp(
+                MIN_PREVIEW_DESCRIPTION_PERCENTAGE,
+                MAX_PREVIEW_DESCRIPTION_PERCENTAGE,
+            );
+        });
+    }
+
+    fn resize_manga_items(&mut self, amount: i16) {
+        MangaTuiConfig::update(|config| {
+            config.search_item_height = config
+                .search_item_height
+                .saturating_add_signed(amount)
+                .clamp(MIN_MANGA_ITEM_HEIGHT, MAX_MANGA_ITEM_HEIGHT);
+        });
+    }
+
+    fn truncate_search_term(&mut self) {
+        self.is_search_term_truncated = truncate_input(
+            &mut self.search_bar,
+            MangaTuiConfig::get().max_search_length,
+        );
+    }
+
+    /// Unlike `clean_up` the search bar and the filters are kept, so the search can be refined
+    /// and done again
+    fn clear_results(&mut self) {
+        self.abort_tasks();
+        self.state = PageState::Normal;
+        self.mangas_found_list = MangasFoundList::default();
+        self.description_scroll = 0;
+        self.is_preview_tags_expanded = false;
+        self.pending_covers.clear();
+        self.did_you_mean = None;
+    }
+
+    fn abort_tasks(&mut self) {
+        self.tasks.abort_all();
+        self.cover_tasks.abort_all();
+        self.cover_requests.clear();
+    }
+
+    fn handle_key_events(&mut self, key_event: KeyEvent) {
+        if self.is_details_open {
+            match key_event.code {
+                KeyCode::Enter | KeyCode::Char('r') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::OpenDetailedManga)
+                        .ok();
+                }
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::CloseDetails)
+                        .ok();
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        if self.is_explicit_confirmation_open {
+            match key_event.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ConfirmExplicitSearch)
+                        .ok();
+                }
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    self.local_action_tx
+                        .send(SearchPageActions::DeclineExplicitSearch)
+                        .ok();
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        if self.is_blocklist_open {
+            match key_event.code {
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ScrollDownBlocklist)
+                        .ok();
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ScrollUpBlocklist)
+                        .ok();
+                }
+                KeyCode::Char('d') | KeyCode::Delete => {
+                    self.local_action_tx.send(SearchPageActions::Unblock).ok();
+                }
+                KeyCode::Char('B') | KeyCode::Esc => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ToggleBlocklist)
+                        .ok();
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        match self.input_mode {
+            InputMode::Idle => match key_event.code {
+                KeyCode::Char('s') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::StartTyping)
+                        .ok();
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ScrollDown)
+                        .ok();
+                }
+
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.local_action_tx.send(SearchPageActions::ScrollUp).ok();
+                }
+                KeyCode::Char('w') => {
+                    self.local_action_tx.send(SearchPageActions::NextPage).ok();
+                }
+                KeyCode::Char('p') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::PlanToRead)
+                        .ok();
+                }
+                KeyCode::Char('b') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::PreviousPage)
+                        .ok();
+                }
+                KeyCode::Char('f') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ToggleFilters)
+                        .ok();
+                }
+                KeyCode::Char('y') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::SearchDidYouMean)
+                        .ok();
+                }
+                KeyCode::Char('R') if self.state == PageState::DisplayingMangasFound => {
+                    self.local_action_tx
+                        .send(SearchPageActions::RetrySearch)
+                        .ok();
+                }
+                KeyCode::Char('r') => {
+                    let action = if matches!(self.state, PageState::ErrorOcurred(_)) {
+                        SearchPageActions::RetrySearch
+                    } else {
+                        SearchPageActions::GoToMangaPage
+                    };
+                    self.local_action_tx.send(action).ok();
+                }
+                // Enter opens the selected manga when the list has results, if there is nothing
+                // to open it focuses the search bar instead
+                KeyCode::Enter => {
+                    let opens_manga =
+                        MangaTuiConfig::get().enter_on_results == EnterOnResults::OpenManga;
+
+                    if self.state == PageState::DisplayingMangasFound && opens_manga {
+                        self.local_action_tx
+                            .send(SearchPageActions::GoToMangaPage)
+                            .ok();
+                    } else {
+                        self.local_action_tx
+                            .send(SearchPageActions::StartTyping)
+                            .ok();
+                    }
+                }
+                KeyCode::Char('t') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ToggleSearchScope)
+                        .ok();
+                }
+                KeyCode::Char('x') => {
+                    self.local_action_tx.send(SearchPageActions::HideManga).ok();
+                }
+                KeyCode::Char('c') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ShareManga)
+                        .ok();
+                }
+                KeyCode::Char('C') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::RetryFailedCovers)
+                        .ok();
+                }
+                KeyCode::Char('B') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ToggleBlocklist)
+                        .ok();
+                }
+                KeyCode::Char('e') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::TogglePreviewEmphasis)
+                        .ok();
+                }
+                KeyCode::Char('G') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::CycleResultsGrouping)
+                        .ok();
+                }
+                KeyCode::Backspace => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ClearResults)
+                        .ok();
+                }
+                KeyCode::Char('v') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::TogglePreview)
+                        .ok();
+                }
+                KeyCode::Char('D') => {
+                    self.global_event_tx.send(Events::DuplicateSearch).ok();
+                }
+                KeyCode::Tab => {
+                    self.global_event_tx.send(Events::SwitchSearch).ok();
+                }
+                KeyCode::Char('X') => {
+                    self.global_event_tx.send(Events::CloseSearch).ok();
+                }
+                KeyCode::Char('E') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ToggleExactTitleFirst)
+                        .ok();
+                }
+                KeyCode::Char('z') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::CycleContentRating)
+                        .ok();
+                }
+                KeyCode::Char('+') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::EnlargePreviewDescription)
+                        .ok();
+                }
+                KeyCode::Char('-') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ShrinkPreviewDescription)
+                        .ok();
+                }
+                KeyCode::Char(']') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::EnlargeMangaItems)
+                        .ok();
+                }
+                KeyCode::Char('g') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ToggleMangaItemTags)
+                        .ok();
+                }
+                KeyCode::Char('T') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::TogglePreviewTags)
+                        .ok();
+                }
+                KeyCode::Char('[') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ShrinkMangaItems)
+                        .ok();
+                }
+                KeyCode::Char('J') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ScrollDescriptionDown)
+                        .ok();
+                }
+                KeyCode::Char('K') => {
+                    self.local_action_tx
+                        .send(SearchPageActions::ScrollDescriptionUp)
+                        .ok();
+                }
+
+                _ => {}
+            },
+            InputMode::Typing => match key_event.code {
+                KeyCode::Down if !self.suggestions.is_empty() => {
+                    self.local_action_tx
+                        .send(SearchPageActions::SelectNextSuggestion)
+                        .ok();
+                }
+                KeyCode::Up if !self.suggestions.is_empty() => {
+                    self.local_action_tx
+                        .send(SearchPageActions::SelectPreviousSuggestion)
+                        .ok();
+                }
+                KeyCode::Tab if self.suggestions_state.selected().is_some() => {
+                    self.local_action_tx
+                        .send(SearchPageActions::FillQueryWithSuggestion)
+                        .ok();
+                }
+                KeyCode::Enter if self.suggestions_state.selected().is_some() => {
+                    self.local_action_tx
+                        .send(SearchPageActions::GoToSuggestion)
+                        .ok();
+                }
+                KeyCode::Enter if self.search_bar.value().trim().is_empty() => {
+                    self.submit_empty_search();
+                }
+                KeyCode::Enter => {
+                    if self.state != PageState::SearchingMangas {
+                        self.local_action_tx.send(SearchPageActions::Search).ok();
+                    }
+                }
+                KeyCode::Esc => {
+                    self.local_action_tx
+                        .send(SearchPageActions::StopTyping)
+                        .ok();
+                }
+                _ => {
+                    let changed = self
+                        .search_bar
+                        .handle_event(&event::Event::Key(key_event))
+                        .is_some_and(|state| state.value);
+                    if changed {
+                        self.truncate_search_term();
+                        self.search_suggestions();
+                    }
+                }
+            },
+        }
+    }
+
+    fn handle_mouse_events(&mut self, mouse_event: MouseEvent) {
+        match mouse_event.kind {
+            MouseEventKind::ScrollDown => {
+                self.local_action_tx
+                    .send(SearchPageActions::ScrollDown)
+                    .ok();
+            }
+            MouseEventKind::ScrollUp => {
+                self.local_action_tx.send(SearchPageActions::ScrollUp).ok();
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.local_action_tx
+                    .send(SearchPageActions::GoToMangaPage)
+                    .ok();
+            }
+            _ => {}
+        }
+    }
+    pub fn is_typing_filter(&mut self) -> bool {
+        self.filter_state.is_typing
+    }
+
+    fn current_query(&self) -> SearchQuery {
+        SearchQuery {
+            term: self.search_bar.value().to_string(),
+            filters: self.filter_state.filters.clone().into_param(),
+            scope: self.search_scope,
+        }
+    }
+
+    /// Only the first page counts, since a new search goes back to it
+    fn is_showing_results_of(&self, query: &SearchQuery) -> bool {
+        self.state == PageState::DisplayingMangasFound
+            && self.mangas_found_list.page == 1
+            && self.last_search.as_ref() == Some(query)
+    }
+
+    /// Searches the current page, unless `confirm_explicit_search` has to ask first
+    fn submit_empty_search(&mut self) {
+        let empty_search = MangaTuiConfig::get().empty_search;
+
+        match empty_search {
+            EmptySearch::Tags if self.filter_state.filters.has_tags() => {
+                if self.state != PageState::SearchingMangas {
+                    self.local_action_tx.send(SearchPageActions::Search).ok();
+                }
+            }
+            EmptySearch::Home => {
+                self.global_event_tx.send(Events::GoToHome).ok();
+            }
+            EmptySearch::Hint | EmptySearch::Tags => {
+                self.global_event_tx
+                    .send(Events::ShowNotification(
+                        "Type something to search, or select some tags on the filters <f>"
+                            .to_string(),
+                    ))
+                    .ok();
+            }
+        }
+    }
+
+    fn new_search(&mut self) {
+        self.is_safe_only_search = false;
+
+        let has_explicit_ratings = self
+            .filter_state
+            .filters
+            .content_rating
+            .iter()
+            .any(ContentRating::is_explicit);
+
+        if MangaTuiConfig::get().confirm_explicit_search
+            && has_explicit_ratings
+            && !self.is_explicit_search_confirmed
+        {
+            self.is_explicit_confirmation_open = true;
+            return;
+        }
+
+        self.search_mangas();
+    }
+
+    /// What is needed to show the same results again, `None` when no results are shown
+    pub fn shown_results(&self) -> Option<SearchResults> {
+        (self.state == PageState::DisplayingMangasFound).then(|| SearchResults {
+            term: self.search_bar.value().to_string(),
+            page: self.mangas_found_list.page,
+            selected: self.mangas_found_list.state.selected,
+        })
+    }
+
+    /// Another search with the same term, filters and settings, the results shown are searched
+    /// again on it so each search keeps its own covers and selection from then on
+    pub fn duplicate(&self) -> Self {
+        let mut search = Self::init(self.global_event_tx.clone());
+        search.search_bar = self.search_bar.clone();
+        search.filter_state = self.filter_state.duplicate();
+        search.search_scope = self.search_scope;
+        search.preview_emphasis = self.preview_emphasis;
+        search.is_preview_hidden = self.is_preview_hidden;
+        search.is_exact_title_first = self.is_exact_title_first;
+        search.is_explicit_search_confirmed = self.is_explicit_search_confirmed;
+
+        if let Some(results) = self.shown_results() {
+            search.restore_results(results);
+        }
+
+        search
+    }
+
+    pub fn restore_results(&mut self, results: SearchResults) {
+        self.search_bar = Input::new(results.term);
+        self.restored_selection = results.selected;
+        self.mangas_found_list.page = results.page.max(1);
+        self.new_search();
+    }
+
+    fn search_mangas(&mut self) {
+        self.clean_up();
+
+        self.state = PageState::SearchingMangas;
+
+        self.last_search = Some(self.current_query());
+
+        let page = self.mangas_found_list.page;
+
+        let tx = self.local_event_tx.clone();
+
+        let manga_to_search = self.search_bar.value().to_string();
+
+        let mut filters = self.filter_state.filters.clone();
+        if self.is_safe_only_search {
+            filters.set_content_rating(vec![ContentRating::Safe]);
+        }
+
+        let search_descriptions = self.search_scope == SearchScope::TitlesAndDescriptions
+            && !manga_to_search.trim().is_empty();
+
+        let retries = MangaTuiConfig::get().search_retries;
+
+        self.tasks.spawn(async move {
+            let search_response = with_retries(retries, || {
+                let manga_to_search = manga_to_search.clone();
+                let filters = filters.clone();
+                async move {
+                    if search_descriptions {
+                        search_titles_and_descriptions(&manga_to_search, page, filters).await
+                    } else {
+                        MangadexClient::global()
+                            .search_mangas(&manga_to_search, page, filters)
+                            .await
+                    }
+                }
+            })
+            .await;
+
+            match search_response {
+                Ok(mangas_found) => {
+                    tx.send(SearchPageEvents::LoadMangasFound(Ok(mangas_found)))
+                        .ok();
+                }
+                Err(e) => {
+                    let attempts = e.attempts;
+                    write_to_error_log(ErrorType::FromError(Box::new(e)));
+                    tx.send(SearchPageEvents::LoadMangasFound(Err(attempts)))
+                        .ok();
+                }
+            }
+        });
+    }
+
+    fn search_next_page(&mut self) {
+        if self.state != PageState::DisplayingMangasFound {
+            return;
+        }
+
+        if self.mangas_found_list.page < searchable_pages(self.mangas_found_list.total_result) {
+            self.mangas_found_list.page += 1;
+            self.search_mangas();
+        } else if self.mangas_found_list.page * ITEMS_PER_PAGE_SEARCH
+            < self.mangas_found_list.total_result
+        {
+            self.global_event_tx
+                .send(Events::ShowNotification(
+                    "End of the searchable results, refine your search to find the rest"
+                        .to_string(),
+                ))
+                .ok();
+        }
+    }
+
+    fn search_previous_page(&mut self) {
+        if self.state == PageState::DisplayingMangasFound
+            && self.state != PageState::SearchingMangas
+            && self.mangas_found_list.page != 1
+        {
+            self.mangas_found_list.page -= 1;
+            self.search_mangas();
+        }
+    }
+
+    pub fn search_mangas_of_author(&mut self, author: Author) {
+        self.filter_state.set_author(author);
+        self.search_bar.reset();
+        self.mangas_found_list.page = 1;
+        self.new_search();
+    }
+
+    pub fn search_mangas_of_artist(&mut self, artist: Artist) {
         self.filter_state.set_artist(artist);
         self.search_bar.reset();
         self.mangas_found_list.page = 1;
-        self.search_mangas();
+        self.new_search();
     }
 
-    fn load_mangas_found(&mut self, response: Option<SearchMangaResponse>) {
+    fn load_mangas_found(&mut self, response: Result<SearchMangaResponse, u32>) {
         match response {
-            Some(response) => {
+            Ok(response) => {
                 if response.data.is_empty() {
                     self.state = PageState::NotFound;
                     self.mangas_found_list.total_result = 0;
+                    self.look_for_did_you_mean();
                     return;
                 }
                 self.mangas_found_list.widget = ListMangasFoundWidget::from_response(response.data);
+                self.mangas_found_list
+                    .widget
+                    .group(MangaTuiConfig::get().results_grouping);
+                self.mangas_found_list
+                    .widget
+                    .put_exact_title_first(self.exact_title());
                 self.mangas_found_list.total_result = response.total;
                 self.state = PageState::DisplayingMangasFound;
-                if PICKER.is_some() {
+                if let Some(index) = self.restored_selection.take() {
+                    self.mangas_found_list.state.select(Some(index));
+                }
+                self.mangas_found_list.clamp_selection();
+                if picker().is_some() {
                     self.local_event_tx
                         .send(SearchPageEvents::SearchCovers)
                         .ok();
                 }
             }
-            None => {
-                self.state = PageState::ErrorOcurred;
+            Err(attempts) => {
+                self.state = PageState::ErrorOcurred(attempts);
                 self.mangas_found_list.total_result = 0;
             }
         }
     }
 
+    /// Search again with a looser version of the search term, if that finds something its first
+    /// result is offered as what the user may have meant to search
+    fn look_for_did_you_mean(&mut self) {
+        let search_term = self.search_bar.value().trim().to_string();
+
+        let Some(relaxed) = relaxed_search_term(&search_term) else {
+            return;
+        };
+
+        let filters = self.filter_state.filters.clone();
+        let tx = self.local_event_tx.clone();
+
+        self.tasks.spawn(async move {
+            let response = MangadexClient::global()
+                .search_title_suggestions(&relaxed, filters)
+                .await;
+
+            match response {
+                Ok(response) => {
+                    let title = response
+                        .data
+                        .into_iter()
+                        .map(from_manga_response)
+                        .map(|manga| manga.title)
+                        .find(|title| !title.eq_ignore_ascii_case(&search_term));
+
+                    if let Some(title) = title {
+                        tx.send(SearchPageEvents::LoadDidYouMean(title)).ok();
+                    }
+                }
+                Err(e) => write_to_error_log(ErrorType::FromError(Box::new(e))),
+            }
+        });
+    }
+
+    fn search_did_you_mean(&mut self) {
+        if let Some(title) = self.did_you_mean.take() {
+            self.search_bar = Input::new(title);
+            self.mangas_found_list.page = 1;
+            self.search_mangas();
+        }
+    }
+
+    /// Build the covers again, used when the image protocol changes so they are displayed with the
+    /// new one
+    pub fn reload_images(&mut self) {
+        if self.state != PageState::DisplayingMangasFound || picker().is_none() {
+            return;
+        }
+
+        self.mangas_found_list
+            .widget
+            .mangas
+            .iter_mut()
+            .for_each(|item| item.image_state = None);
+
+        self.local_event_tx
+            .send(SearchPageEvents::SearchCovers)
+            .ok();
+    }
+
+    fn search_suggestions(&mut self) {
+        if let Some(task) = self.suggestions_task.take() {
+            task.abort();
+        }
+        self.suggestions_state.select(None);
+
+        let search_term = self.search_bar.value().trim().to_string();
+
+        if !MangaTuiConfig::get().search_suggestions
+            || search_term.chars().count() < MIN_CHARACTERS_FOR_SUGGESTIONS
+        {
+            self.suggestions = vec![];
+            return;
+        }
+
+        let filters = self.filter_state.filters.clone();
+        let tx = self.local_event_tx.clone();
+
+        self.suggestions_task = Some(self.tasks.spawn(async move {
+            tokio::time::sleep(SUGGESTIONS_DEBOUNCE).await;
+
+            let response = MangadexClient::global()
+                .search_title_suggestions(&search_term, filters)
+                .await;
+
+            match response {
+                Ok(response) => {
+                    let suggestions = response.data.into_iter().map(from_manga_response).collect();
+                    tx.send(SearchPageEvents::LoadSuggestions(suggestions)).ok();
+                }
+                Err(e) => write_to_error_log(ErrorType::FromError(Box::new(e))),
+            }
+        }));
+    }
+
+    fn load_suggestions(&mut self, suggestions: Vec<Manga>) {
+        if self.input_mode == InputMode::Typing {
+            self.suggestions = suggestions;
+            self.suggestions_state.select(None);
+        }
+    }
+
+    fn clear_suggestions(&mut self) {
+        if let Some(task) = self.suggestions_task.take() {
+            task.abort();
+        }
+        self.suggestions = vec![];
+        self.suggestions_state.select(None);
+    }
+
+    fn select_next_suggestion(&mut self) {
+        let next = match self.suggestions_state.selected() {
+            Some(index) => (index + 1).min(self.suggestions.len().saturating_sub(1)),
+            None => 0,
+        };
+        self.suggestions_state.select(Some(next));
+    }
+
+    fn select_previous_suggestion(&mut self) {
+        let previous = match self.suggestions_state.selected() {
+            Some(0) | None => None,
+            Some(index) => Some(index - 1),
+        };
+        self.suggestions_state.select(previous);
+    }
+
+    fn fill_query_with_suggestion(&mut self) {
+        if let Some(suggestion) = self
+            .suggestions_state
+            .selected()
+            .and_then(|index| self.suggestions.get(index))
+        {
+            self.search_bar = Input::new(suggestion.title.clone());
+            self.clear_suggestions();
+        }
+    }
+
+    fn go_to_suggestion(&mut self) {
+        if let Some(suggestion) = self
+            .suggestions_state
+            .selected()
+            .and_then(|index| self.suggestions.get(index))
+        {
+            self.global_event_tx
+                .send(Events::GoToMangaPage(MangaItem::new(
+                    suggestion.clone(),
+                    None,
+                )))
+                .ok();
+            self.input_mode = InputMode::Idle;
+            self.clear_suggestions();
+        }
+    }
+
+    /// The suggestions are displayed as a dropdown below the search bar
+    fn render_suggestions(&mut self, input_area: Rect, buf: &mut Buffer) {
+        if self.input_mode != InputMode::Typing || self.suggestions.is_empty() {
+            return;
+        }
+
+        let [search_bar_area, _] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(input_area);
+
+        let suggestions_area = Rect::new(
+            search_bar_area.x,
+            search_bar_area.bottom(),
+            search_bar_area.width,
+            self.suggestions.len() as u16 + 2,
+        )
+        .intersection(buf.area);
+
+        Clear.render(suggestions_area, buf);
+
+        let suggestions = List::new(
+            self.suggestions
+                .iter()
+                .map(|suggestion| suggestion.title.as_str()),
+        )
+        .block(Block::bordered().title("Suggestions"))
+        .highlight_style(Style::default().on_blue());
+
+        StatefulWidget::render(
+            suggestions,
+            suggestions_area,
+            buf,
+            &mut self.suggestions_state,
+        );
+    }
+
     fn search_covers(&mut self) {
-        for item in self.mangas_found_list.widget.mangas.iter() {
+        self.cover_tasks.abort_all();
+        self.cover_requests.clear();
+
+        self.pending_covers = self
+            .mangas_found_list
+            .widget
+            .mangas
+            .iter()
+            .map(|item| item.manga.id.clone())
+            .collect();
+        self.total_covers = self.pending_covers.len();
+
+        let (prioritize_selected_cover, prefetch_covers) = {
+            let config = MangaTuiConfig::get();
+            (config.prioritize_selected_cover, config.prefetch_covers)
+        };
+
+        let total = self.mangas_found_list.widget.mangas.len();
+        let selected = self.mangas_found_list.state.selected.unwrap_or(0);
+        let mut order = if prioritize_selected_cover {
+            indexes_by_distance(selected, total)
+        } else {
+            (0..total).collect()
+        };
+
+        if prefetch_covers {
+            order.sort_by_key(|index| index.abs_diff(selected) > 1);
+        }
+
+        for item in order
+            .into_iter()
+            .filter_map(|index| self.mangas_found_list.widget.mangas.get(index))
+        {
             let manga_id = item.manga.id.clone();
             let tx = self.local_event_tx.clone();
 
             match item.manga.img_url.as_ref() {
                 Some(file_name) => {
                     let file_name = file_name.clone();
-                    search_manga_cover(file_name, manga_id, &mut self.tasks, tx);
+                    search_manga_cover(
+                        file_name,
+                        manga_id,
+                        CoverAspect::Natural,
+                        &mut self.cover_tasks,
+                        tx,
+                    );
                 }
                 None => {
                     tx.send(SearchPageEvents::LoadCover(None, manga_id)).ok();
@@ -600,7 +2064,103 @@ impl SearchPage {
         }
     }
 
+    /// With `prefetch_covers` the covers of the mangas right above and below the selected one are
+    /// requested if they are not loaded nor on their way, so moving to them is instant
+    fn prefetch_covers_around_selection(&mut self) {
+        if !MangaTuiConfig::get().prefetch_covers {
+            return;
+        }
+
+        let Some(selected) = self.mangas_found_list.state.selected else {
+            return;
+        };
+
+        let around = self
+            .mangas_found_list
+            .widget
+            .mangas
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| index.abs_diff(selected) <= 1)
+            .map(|(_, item)| item);
+
+        for item in around {
+            if item.image_state.is_some()
+                || self.pending_covers.contains(&item.manga.id)
+                || self.cover_requests.contains_key(&item.manga.id)
+            {
+                continue;
+            }
+
+            if let Some(file_name) = item.manga.img_url.clone() {
+                let request = search_manga_cover(
+                    file_name,
+                    item.manga.id.clone(),
+                    CoverAspect::Natural,
+                    &mut self.cover_tasks,
+                    self.local_event_tx.clone(),
+                );
+                self.cover_requests.insert(item.manga.id.clone(), request);
+            }
+        }
+    }
+
+    /// Ids and cover file names of the results whose cover was requested and did not load
+    fn failed_covers(&self) -> Vec<(String, String)> {
+        self.mangas_found_list
+            .widget
+            .mangas
+            .iter()
+            .filter(|item| item.image_state.is_none())
+            .filter(|item| !self.pending_covers.contains(&item.manga.id))
+            .filter(|item| {
+                self.cover_requests
+                    .get(&item.manga.id)
+                    .is_none_or(AbortHandle::is_finished)
+            })
+            .filter_map(|item| Some((item.manga.id.clone(), item.manga.img_url.clone()?)))
+            .collect()
+    }
+
+    /// Request again every cover which failed, their progress is shown like the one of the first
+    /// request
+    fn retry_failed_covers(&mut self) {
+        if picker().is_none() {
+            return;
+        }
+
+        let failed_covers = self.failed_covers();
+
+        if failed_covers.is_empty() {
+            self.global_event_tx
+                .send(Events::ShowNotification(
+                    "No covers failed to load".to_string(),
+                ))
+                .ok();
+            return;
+        }
+
+        if self.pending_covers.is_empty() {
+            self.total_covers = 0;
+        }
+        self.total_covers += failed_covers.len();
+
+        for (manga_id, file_name) in failed_covers {
+            self.pending_covers.insert(manga_id.clone());
+            let request = search_manga_cover(
+                file_name,
+                manga_id.clone(),
+                CoverAspect::Natural,
+                &mut self.cover_tasks,
+                self.local_event_tx.clone(),
+            );
+            self.cover_requests.insert(manga_id, request);
+        }
+    }
+
     fn load_cover(&mut self, maybe_cover: Option<Box<dyn StatefulProtocol>>, manga_id: String) {
+        self.pending_covers.remove(&manga_id);
+
         if let Some(image) = maybe_cover {
             if let Some(manga) = self
                 .mangas_found_list
@@ -616,7 +2176,14 @@ impl SearchPage {
 
     pub fn tick(&mut self) {
         self.loader_state.calc_next();
-        if let Ok(event) = self.local_event_rx.try_recv() {
+        let started = Instant::now();
+        for _ in 0..MAX_EVENTS_PER_TICK {
+            if started.elapsed() >= TICK_TIME_BUDGET {
+                break;
+            }
+            let Ok(event) = self.local_event_rx.try_recv() else {
+                break;
+            };
             match event {
                 SearchPageEvents::LoadMangasFound(response) => self.load_mangas_found(response),
                 SearchPageEvents::SearchCovers => {
@@ -625,7 +2192,68 @@ impl SearchPage {
                 SearchPageEvents::LoadCover(maybe_image, manga_id) => {
                     self.load_cover(maybe_image, manga_id)
                 }
+                SearchPageEvents::LoadSuggestions(suggestions) => {
+                    self.load_suggestions(suggestions)
+                }
+                SearchPageEvents::LoadDidYouMean(title) => {
+                    if self.state == PageState::NotFound {
+                        self.did_you_mean = Some(title);
+                    }
+                }
             }
         }
     }
 }
+
+fn description_matches(manga: &Data, search_term: &str) -> bool {
+    let description = manga
+        .attributes
+        .description
+        .as_ref()
+        .and_then(|description| {
+            preferred_description(
+                description,
+                MangaTuiConfig::get().description_language.as_deref(),
+            )
+        })
+        .map(|description| description.to_lowercase())
+        .unwrap_or_default();
+
+    !description.is_empty()
+        && search_term
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| description.contains(word))
+}
+
+/// Mangadex's search only considers titles, so each page shows the mangas found by title, the
+/// ones whose description also matches first, followed by the mangas of a larger batch fetched by
+/// the filters whose description matches the search term. The total stays the one mangadex
+/// reports for the title search
+async fn search_titles_and_descriptions(
+    search_term: &str,
+    page: u32,
+    filters: Filters,
+) -> Result<SearchMangaResponse, FetchError> {
+    let (by_title, for_description) = tokio::join!(
+        MangadexClient::global().search_mangas(search_term, page, filters.clone()),
+        MangadexClient::global().search_mangas_for_description_match(page, filters),
+    );
+
+    let mut response = by_title?;
+
+    response
+        .data
+        .sort_by_key(|manga| !description_matches(manga, search_term));
+
+    let matched_by_description: Vec<Data> = for_description?
+        .data
+        .into_iter()
+        .filter(|manga| description_matches(manga, search_term))
+        .filter(|manga| !response.data.iter().any(|found| found.id == manga.id))
+        .collect();
+
+    response.data.extend(matched_by_description);
+
+    Ok(response)
+}
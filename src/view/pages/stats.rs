@@ -0,0 +1,185 @@
+use crate::backend::database::{get_reading_stats, ReadingStats};
+use crate::backend::error_log::{write_to_error_log, ErrorType};
+use crate::backend::tui::Events;
+use crate::global::{ERROR_STYLE, INSTRUCTIONS_STYLE};
+use crate::view::widgets::Component;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+/// How many tags are listed as the most read
+const TOP_TAGS: u32 = 10;
+/// How many days are shown on the activity chart, including today
+const ACTIVITY_DAYS: i64 = 14;
+
+pub enum StatsActions {
+    Reload,
+}
+
+/// Summary of the reading history, it's built from the local database so it's available offline
+pub struct Stats {
+    stats: Option<ReadingStats>,
+    pub local_action_tx: UnboundedSender<StatsActions>,
+    pub local_action_rx: UnboundedReceiver<StatsActions>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        let (local_action_tx, local_action_rx) = mpsc::unbounded_channel::<StatsActions>();
+        Self {
+            stats: None,
+            local_action_tx,
+            local_action_rx,
+        }
+    }
+
+    pub fn load_stats(&mut self) {
+        match get_reading_stats(TOP_TAGS, ACTIVITY_DAYS) {
+            Ok(stats) => self.stats = Some(stats),
+            Err(e) => {
+                self.stats = None;
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+            }
+        }
+    }
+
+    fn handle_key_events(&mut self, key_event: KeyEvent) {
+        if let KeyCode::Char('r') = key_event.code {
+            self.local_action_tx.send(StatsActions::Reload).ok();
+        }
+    }
+
+    fn render_totals(stats: &ReadingStats, area: Rect, buf: &mut Buffer) {
+        Paragraph::new(vec![
+            Line::from(vec![
+                "Chapters read: ".into(),
+                stats.chapters_read.to_string().bold(),
+            ]),
+            Line::from(vec![
+                "Mangas started: ".into(),
+                stats.mangas_started.to_string().bold(),
+            ]),
+            Line::from(vec![
+                "Mangas completed: ".into(),
+                stats.mangas_completed.to_string().bold(),
+            ]),
+        ])
+        .block(Block::bordered().title("Totals"))
+        .render(area, buf);
+    }
+
+    fn render_top_tags(stats: &ReadingStats, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered().title("Most read tags");
+
+        if stats.top_tags.is_empty() {
+            Paragraph::new(
+                "No tags yet, they are saved when a chapter is read or the manga page of a manga \
+                 with chapters read is opened",
+            )
+            .wrap(Wrap { trim: true })
+            .block(block)
+            .render(area, buf);
+            return;
+        }
+
+        let tags = List::new(stats.top_tags.iter().map(|(name, chapters)| {
+            Line::from(vec![
+                format!("{name} ").into(),
+                format!("{chapters} chapters").dark_gray(),
+            ])
+        }))
+        .block(block);
+
+        Widget::render(tags, area, buf);
+    }
+
+    fn render_activity(stats: &ReadingStats, area: Rect, buf: &mut Buffer) {
+        // Only the month and the day fit below the bars
+        let days: Vec<(&str, u64)> = stats
+            .activity
+            .iter()
+            .map(|(day, chapters)| (day.get(5..).unwrap_or(day), *chapters as u64))
+            .collect();
+
+        let inner_width = area.width.saturating_sub(2);
+        let bar_width = (inner_width / ACTIVITY_DAYS as u16)
+            .saturating_sub(1)
+            .max(1);
+
+        BarChart::default()
+            .block(
+                Block::bordered().title(format!("Chapters read in the last {ACTIVITY_DAYS} days")),
+            )
+            .data(&days)
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(Color::Yellow))
+            .value_style(Style::default().fg(Color::Black).bg(Color::Yellow))
+            .render(area, buf);
+    }
+}
+
+impl Component for Stats {
+    type Actions = StatsActions;
+
+    fn render(&mut self, area: Rect, frame: &mut Frame<'_>) {
+        let buf = frame.buffer_mut();
+
+        let instructions = Line::from(vec![
+            "Reload ".into(),
+            Span::raw("<r>").style(*INSTRUCTIONS_STYLE),
+        ]);
+
+        let block = Block::bordered()
+            .title("Reading stats")
+            .title_bottom(instructions);
+
+        let stats = match self.stats.as_ref() {
+            Some(stats) if !stats.is_empty() => stats,
+            Some(_) => {
+                Paragraph::new("No reading history yet")
+                    .block(block)
+                    .render(area, buf);
+                return;
+            }
+            None => {
+                Paragraph::new(
+                    Span::raw("The reading history could not be loaded").style(*ERROR_STYLE),
+                )
+                .block(block)
+                .render(area, buf);
+                return;
+            }
+        };
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [top_area, activity_area] =
+            Layout::vertical([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(inner);
+
+        let [totals_area, tags_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(top_area);
+
+        Self::render_totals(stats, totals_area, buf);
+        Self::render_top_tags(stats, tags_area, buf);
+        Self::render_activity(stats, activity_area, buf);
+    }
+
+    fn handle_events(&mut self, events: Events) {
+        if let Events::Key(key_event) = events {
+            self.handle_key_events(key_event);
+        }
+    }
+
+    fn update(&mut self, action: Self::Actions) {
+        match action {
+            StatsActions::Reload => self.load_stats(),
+        }
+    }
+
+    fn clean_up(&mut self) {
+        self.stats = None;
+    }
+}
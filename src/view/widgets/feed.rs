@@ -1,12 +1,37 @@
+use crate::backend::database::LibraryChapter;
 use crate::backend::filter::Languages;
 use crate::backend::ChapterResponse;
-use crate::utils::display_dates_since_publication;
+use crate::config::MangaTuiConfig;
+use crate::utils::{display_date, parse_date, truncate_to_width};
 use ratatui::{prelude::*, widgets::*};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
 use tui_widget_list::PreRender;
 
+/// How the mangas of the feed are drawn, it's changed with `<l>` on the feed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedLayout {
+    /// A box for each manga with its recent chapters
+    #[default]
+    Detailed,
+    /// One line for each chapter, with the title of the manga on the first one
+    Compact,
+}
+
+impl FeedLayout {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Detailed => Self::Compact,
+            Self::Compact => Self::Detailed,
+        }
+    }
+}
+
 pub enum FeedTabs {
     History,
     PlantToRead,
+    Downloads,
 }
 
 #[derive(Clone)]
@@ -17,9 +42,9 @@ pub struct RecentChapters {
     pub readeable_at: String,
 }
 
-impl From<RecentChapters> for ListItem<'_> {
+impl From<RecentChapters> for Line<'_> {
     fn from(value: RecentChapters) -> Self {
-        let line = Line::from(vec![
+        Line::from(vec![
             format!("Ch. {} ", value.number).into(),
             value.title.bold(),
             " | ".into(),
@@ -28,9 +53,13 @@ impl From<RecentChapters> for ListItem<'_> {
             value.translated_language.as_human_readable().into(),
             " | ".into(),
             value.readeable_at.into(),
-        ]);
+        ])
+    }
+}
 
-        ListItem::new(line)
+impl From<RecentChapters> for ListItem<'_> {
+    fn from(value: RecentChapters) -> Self {
+        ListItem::new(Line::from(value))
     }
 }
 
@@ -38,29 +67,62 @@ impl From<RecentChapters> for ListItem<'_> {
 pub struct MangasRead {
     pub id: String,
     pub title: String,
+    pub is_pinned: bool,
     pub style: Style,
+    pub recent_chapters_title: &'static str,
     pub recent_chapters: Vec<RecentChapters>,
 }
 
+impl MangasRead {
+    fn display_title(&self) -> String {
+        if self.is_pinned {
+            format!("📌 {}", self.title)
+        } else {
+            self.title.clone()
+        }
+    }
+
+    fn render_compact(self, area: Rect, buf: &mut Buffer) {
+        let [title_area, recent_chapters_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(2)]).areas(area);
+
+        let title = truncate_to_width(
+            &self.display_title(),
+            title_area.width.saturating_sub(1) as usize,
+        );
+        Paragraph::new(title)
+            .style(self.style)
+            .render(title_area, buf);
+
+        let chapters: Vec<Line<'_>> = self.recent_chapters.into_iter().map(Line::from).collect();
+        Paragraph::new(chapters).render(recent_chapters_area, buf);
+    }
+}
+
 impl Widget for MangasRead {
     fn render(self, area: Rect, buf: &mut Buffer)
     where
         Self: Sized,
     {
+        if MangaTuiConfig::get().feed_layout == FeedLayout::Compact {
+            return self.render_compact(area, buf);
+        }
+
         let layout = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(2)]);
 
         let [title_area, recent_chapters_area] = layout.margin(1).areas(area);
 
         Block::bordered().style(self.style).render(area, buf);
 
-        Paragraph::new(self.title)
+        Paragraph::new(self.display_title())
             .block(Block::default().borders(Borders::RIGHT))
             .wrap(Wrap { trim: true })
             .render(title_area, buf);
 
         if !self.recent_chapters.is_empty() {
             Widget::render(
-                List::new(self.recent_chapters).block(Block::bordered().title("Latest chapters")),
+                List::new(self.recent_chapters)
+                    .block(Block::bordered().title(self.recent_chapters_title)),
                 recent_chapters_area.inner(Margin {
                     horizontal: 1,
                     vertical: 1,
@@ -76,7 +138,10 @@ impl PreRender for MangasRead {
         if context.is_selected {
             self.style = Style::default().fg(Color::Yellow);
         }
-        10
+        match MangaTuiConfig::get().feed_layout {
+            FeedLayout::Detailed => 10,
+            FeedLayout::Compact => self.recent_chapters.len().max(1) as u16,
+        }
     }
 }
 
@@ -120,23 +185,16 @@ impl HistoryWidget {
     pub fn set_chapter(&mut self, manga_id: String, response: ChapterResponse) {
         if let Some(manga) = self.mangas.iter_mut().find(|manga| manga.id == manga_id) {
             for chapter in response.data {
-                let today = chrono::offset::Local::now().date_naive();
-                let parse_date =
-                    chrono::DateTime::parse_from_rfc3339(&chapter.attributes.readable_at)
-                        .unwrap_or_default();
-
-                let difference = today - parse_date.date_naive();
-
-                let num_days = difference.num_days();
+                let readable_at = parse_date(&chapter.attributes.readable_at).unwrap_or_default();
 
                 let translated_language =
                     Languages::try_from_iso_code(&chapter.attributes.translated_language)
-                        .unwrap_or(*Languages::get_preferred_lang());
+                        .unwrap_or(Languages::get_preferred_lang());
 
                 let recent_chapter = RecentChapters {
                     title: chapter.attributes.title.unwrap_or("No title ".to_string()),
                     number: chapter.attributes.chapter.unwrap_or_default(),
-                    readeable_at: display_dates_since_publication(num_days),
+                    readeable_at: display_date(readable_at, chrono::Utc::now()),
                     translated_language,
                 };
                 manga.recent_chapters.push(recent_chapter);
@@ -144,6 +202,25 @@ impl HistoryWidget {
         }
     }
 
+    pub fn set_downloaded_chapters(&mut self, manga_id: String, chapters: Vec<LibraryChapter>) {
+        if let Some(manga) = self.mangas.iter_mut().find(|manga| manga.id == manga_id) {
+            for chapter in chapters {
+                let downloaded_at = parse_date(&chapter.downloaded_at).unwrap_or_default();
+
+                let translated_language = Languages::iter()
+                    .find(|lang| lang.as_human_readable() == chapter.lang)
+                    .unwrap_or(Languages::get_preferred_lang());
+
+                manga.recent_chapters.push(RecentChapters {
+                    title: chapter.title,
+                    number: chapter.number,
+                    readeable_at: display_date(downloaded_at, chrono::Utc::now()),
+                    translated_language,
+                });
+            }
+        }
+    }
+
     fn render_pagination_data(&mut self, area: Rect, buf: &mut Buffer) {
         let amount_pages = self.total_results as f64 / 5_f64;
         Paragraph::new(Line::from(vec![
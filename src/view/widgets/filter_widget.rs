@@ -166,6 +166,8 @@ impl<'a> StatefulWidgetFrame for FilterWidget<'a> {
                                 "Press".into(),
                                 " <l> ".bold().yellow(),
                                 "to filter tags".into(),
+                                " <x> ".bold().yellow(),
+                                "to always hide a tag".into(),
                             ])
                         };
 
@@ -1,4 +1,6 @@
 use crate::backend::authors::AuthorsResponse;
+use crate::backend::blocklist::{BlockedItem, BlockedKind, Blocklist};
+use crate::backend::error_log::{write_to_error_log, ErrorType};
 use crate::backend::fetch::MangadexClient;
 use crate::backend::filter::{
     Artist, Author, ContentRating, Filters, Languages, MagazineDemographic, PublicationStatus,
@@ -6,6 +8,7 @@ use crate::backend::filter::{
 };
 use crate::backend::tags::TagsResponse;
 use crate::backend::tui::Events;
+use crate::config::MangaTuiConfig;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::widgets::*;
 use std::marker::PhantomData;
@@ -72,6 +75,16 @@ pub struct FilterList<T> {
     _state: PhantomData<T>,
 }
 
+impl<T> Clone for FilterList<T> {
+    fn clone(&self) -> Self {
+        Self {
+            items: self.items.clone(),
+            state: self.state.clone(),
+            _state: PhantomData,
+        }
+    }
+}
+
 impl<T> FilterList<T> {
     pub fn toggle(&mut self) {
         if let Some(index) = self.state.selected() {
@@ -108,25 +121,15 @@ impl<T> FilterList<T> {
 
 impl Default for FilterList<ContentRatingState> {
     fn default() -> Self {
+        let selected_ratings = MangaTuiConfig::get().content_rating.clone();
+
         Self {
-            items: vec![
-                FilterListItem {
-                    is_selected: true,
-                    name: ContentRating::Safe.to_string(),
-                },
-                FilterListItem {
-                    is_selected: true,
-                    name: ContentRating::Suggestive.to_string(),
-                },
-                FilterListItem {
-                    is_selected: false,
-                    name: ContentRating::Erotic.to_string(),
-                },
-                FilterListItem {
-                    is_selected: false,
-                    name: ContentRating::Pornographic.to_string(),
-                },
-            ],
+            items: ContentRating::iter()
+                .map(|rating| FilterListItem {
+                    is_selected: selected_ratings.contains(&rating),
+                    name: rating.to_string(),
+                })
+                .collect(),
             state: ListState::default(),
             _state: PhantomData::<ContentRatingState>,
         }
@@ -231,6 +234,18 @@ pub struct FilterListDynamic<T> {
     _state: PhantomData<T>,
 }
 
+impl<T> Clone for FilterListDynamic<T> {
+    fn clone(&self) -> Self {
+        Self {
+            items: self.items.clone(),
+            state: self.state.clone(),
+            search_bar: self.search_bar.clone(),
+            _is_found: self._is_found,
+            _state: PhantomData,
+        }
+    }
+}
+
 impl FilterListDynamic<TagState> {
     pub fn toggle_tags(&mut self) {
         if self.is_search_bar_empty() {
@@ -254,6 +269,25 @@ impl FilterListDynamic<TagState> {
     }
 }
 
+impl FilterListDynamic<TagState> {
+    /// Remove the selected tag from the list, taking in account the tags may be filtered by the
+    /// search bar
+    fn take_selected_tag(&mut self) -> Option<ListItemId> {
+        let index = self.state.selected()?;
+        let search = self.search_bar.value().to_lowercase();
+        let items = self.items.as_mut()?;
+
+        let position = items
+            .iter()
+            .enumerate()
+            .filter(|(_, tag)| tag.name.to_lowercase().contains(&search))
+            .map(|(position, _)| position)
+            .nth(index)?;
+
+        Some(items.remove(position))
+    }
+}
+
 impl FilterListDynamic<AuthorState> {
     fn search_authors(&mut self, tx: UnboundedSender<FilterEvents>) {
         let name = self.get_name();
@@ -373,14 +407,33 @@ impl FilterState {
         }
     }
 
+    /// The same filters selected, with its own channel so both can be changed apart
+    pub fn duplicate(&self) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel::<FilterEvents>();
+        if self.tags.items.is_none() {
+            tx.send(FilterEvents::SearchTags).ok();
+        }
+        Self {
+            is_open: false,
+            id_filter: self.id_filter,
+            filters: self.filters.clone(),
+            content_rating: self.content_rating.clone(),
+            publication_status: self.publication_status.clone(),
+            sort_by_state: self.sort_by_state.clone(),
+            tags: self.tags.clone(),
+            magazine_demographic: self.magazine_demographic.clone(),
+            author_state: self.author_state.clone(),
+            artist_state: self.artist_state.clone(),
+            lang_state: self.lang_state.clone(),
+            is_typing: false,
+            tx,
+            rx,
+        }
+    }
+
     pub fn reset(&mut self) {
-        if self.tags.items.is_some() {
-            self.tags
-                .items
-                .as_mut()
-                .unwrap()
-                .iter_mut()
-                .for_each(|tag| tag.is_selected = false);
+        if let Some(tags) = self.tags.items.as_mut() {
+            tags.iter_mut().for_each(|tag| tag.is_selected = false);
             self.tags.search_bar.reset();
         }
 
@@ -460,6 +513,7 @@ impl FilterState {
                 KeyCode::BackTab => self.previous_filter(),
                 KeyCode::Char('s') => self.toggle_filter_list(),
                 KeyCode::Char('r') => self.reset(),
+                KeyCode::Char('x') => self.block_selected_tag(),
                 KeyCode::Char('l') | KeyCode::Right => self.toggle_focus_input(),
                 _ => {}
             }
@@ -645,10 +699,37 @@ impl FilterState {
         )
     }
 
+    /// Search the tags again, used when a tag is removed from the blocklist so it's listed again
+    pub fn reload_tags(&mut self) {
+        self.tx.send(FilterEvents::SearchTags).ok();
+    }
+
+    /// Hide the selected tag from every search, it can be restored from the blocklist
+    fn block_selected_tag(&mut self) {
+        if FILTERS.get(self.id_filter) != Some(&MangaFilters::Tags) {
+            return;
+        }
+
+        if let Some(tag) = self.tags.take_selected_tag() {
+            self.set_tags();
+            let block_operation = Blocklist::block(BlockedItem {
+                id: tag.id,
+                kind: BlockedKind::Tag,
+                name: tag.name,
+            });
+
+            if let Err(e) = block_operation {
+                write_to_error_log(ErrorType::FromError(Box::new(e)));
+            }
+        }
+    }
+
     pub fn set_tags_from_response(&mut self, tags_response: TagsResponse) {
+        let blocklist = Blocklist::get();
         let tags: Vec<ListItemId> = tags_response
             .data
             .into_iter()
+            .filter(|tag| !blocklist.blocked_tag_ids().any(|id| id == tag.id))
             .map(|data| ListItemId {
                 is_selected: false,
                 id: data.id,
@@ -687,6 +768,24 @@ impl FilterState {
         }
     }
 
+    /// Select the content ratings up to the one after the highest one selected, after
+    /// pornographic it goes back to only safe
+    pub fn cycle_content_rating(&mut self) {
+        let highest = self
+            .content_rating
+            .items
+            .iter()
+            .rposition(|item| item.is_selected);
+
+        let next = highest.map_or(0, |index| (index + 1) % self.content_rating.items.len());
+
+        for (index, item) in self.content_rating.items.iter_mut().enumerate() {
+            item.is_selected = index <= next;
+        }
+
+        self.set_content_rating();
+    }
+
     fn set_content_rating(&mut self) {
         self.filters.set_content_rating(
             self.content_rating
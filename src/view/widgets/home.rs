@@ -1,7 +1,9 @@
 use crate::backend::{Data, SearchMangaResponse};
 use crate::common::Manga;
-use crate::utils::{from_manga_response, set_status_style, set_tags_style};
-use crate::PICKER;
+use crate::picker;
+use crate::utils::{
+    from_manga_response, render_cover_frame, set_status_style, set_tag_group_style, set_tags_style,
+};
 use ratatui::{prelude::*, widgets::*};
 use ratatui_image::protocol::StatefulProtocol;
 use ratatui_image::{Resize, StatefulImage};
@@ -38,6 +40,7 @@ impl CarrouselItem {
     fn render_cover(&mut self, area: Rect, buf: &mut Buffer) {
         match self.cover_state {
             Some(ref mut image_state) => {
+                let area = render_cover_frame(area, buf);
                 let cover = StatefulImage::new(None).resize(Resize::Fit(None));
 
                 StatefulWidget::render(cover, area, buf, image_state)
@@ -64,12 +67,7 @@ impl CarrouselItem {
             .title_bottom(self.manga.author.name.clone())
             .render(area, buf);
 
-        let mut tags: Vec<Span<'_>> = self
-            .manga
-            .tags
-            .iter()
-            .map(|tag| set_tags_style(tag))
-            .collect();
+        let mut tags: Vec<Span<'_>> = self.manga.tags.iter().map(set_tag_group_style).collect();
 
         tags.push(set_status_style(&self.manga.status));
         tags.push(set_tags_style(&self.manga.content_rating));
@@ -95,7 +93,7 @@ impl CarrouselItem {
     }
 
     pub fn render_recently_added(&mut self, area: Rect, buf: &mut Buffer) {
-        if PICKER.is_some() {
+        if picker().is_some() {
             let layout = Layout::vertical([Constraint::Percentage(80), Constraint::Percentage(20)]);
             let [cover_area, title_area] = layout.areas(area);
             self.render_cover(cover_area, buf);
@@ -128,7 +126,7 @@ impl Widget for CarrouselItem {
 
         let [cover_area, details_area] = layout.areas(area);
 
-        if PICKER.is_some() {
+        if picker().is_some() {
             self.render_cover(cover_area, buf);
         }
         self.render_details(details_area, buf);
@@ -1,7 +1,8 @@
 use crate::backend::filter::Languages;
 use crate::backend::ChapterResponse;
 use crate::global::ERROR_STYLE;
-use crate::utils::display_dates_since_publication;
+use crate::utils::{display_date, parse_date};
+use crate::view::pages::manga::ChapterOrder;
 use ratatui::{prelude::*, widgets::*};
 use tui_widget_list::PreRender;
 
@@ -28,6 +29,13 @@ pub struct ChapterItem {
     pub state: ChapterItemState,
     pub download_loading_state: Option<f64>,
     pub translated_language: Languages,
+    /// Unix timestamp of when the chapter became readable
+    pub uploaded_at: i64,
+    /// Whether other chapters of the list have the same number, for example when several groups
+    /// translated it, then the group is shown next to the number
+    pub has_same_number: bool,
+    /// Part of the chapters selected with `<Space>` for the batch actions
+    pub is_selected: bool,
     style: Style,
 }
 
@@ -53,13 +61,22 @@ impl Widget for ChapterItem {
 
         let is_downloaded_icon = if self.is_downloaded { "📥" } else { " " };
 
+        let number = if self.has_same_number && !self.scanlator.is_empty() {
+            format!(" Ch. {} ({}) ", self.chapter_number, self.scanlator)
+        } else {
+            format!(" Ch. {} ", self.chapter_number)
+        };
+
+        let is_selected_icon = if self.is_selected { "✅ " } else { "" };
+
         Paragraph::new(Line::from(vec![
+            is_selected_icon.into(),
             is_read_icon.into(),
             " ".into(),
             is_downloaded_icon.into(),
             " ".into(),
             self.translated_language.as_emoji().into(),
-            format!(" Ch. {} ", self.chapter_number).into(),
+            number.into(),
             self.title.into(),
         ]))
         .wrap(Wrap { trim: true })
@@ -149,6 +166,7 @@ impl ChapterItem {
         readable_at: String,
         scanlator: String,
         translated_language: Languages,
+        uploaded_at: i64,
     ) -> Self {
         Self {
             id,
@@ -160,6 +178,9 @@ impl ChapterItem {
             is_downloaded: false,
             download_loading_state: None,
             translated_language,
+            uploaded_at,
+            has_same_number: false,
+            is_selected: false,
             style: Style::default(),
             state: ChapterItemState::Normal,
         }
@@ -188,7 +209,7 @@ impl ChaptersListWidget {
     pub fn from_response(response: &ChapterResponse) -> Self {
         let mut chapters: Vec<ChapterItem> = vec![];
 
-        let today = chrono::offset::Local::now().date_naive();
+        let now = chrono::Utc::now();
         for chapter in response.data.iter() {
             let id = chapter.id.clone();
             let title = chapter
@@ -205,12 +226,9 @@ impl ChaptersListWidget {
 
             let translated_language: Languages =
                 Languages::try_from_iso_code(&chapter.attributes.translated_language)
-                    .unwrap_or(*Languages::get_preferred_lang());
-
-            let parse_date = chrono::DateTime::parse_from_rfc3339(&chapter.attributes.readable_at)
-                .unwrap_or_default();
+                    .unwrap_or(Languages::get_preferred_lang());
 
-            let difference = today - parse_date.date_naive();
+            let published_at = parse_date(&chapter.attributes.readable_at).unwrap_or_default();
 
             let scanlator = chapter
                 .relationships
@@ -222,16 +240,104 @@ impl ChaptersListWidget {
                 id,
                 title,
                 chapter_number,
-                display_dates_since_publication(difference.num_days()),
+                display_date(published_at, now),
                 scanlator.unwrap_or_default(),
                 translated_language,
+                published_at.timestamp(),
             ))
         }
 
-        Self { chapters }
+        let mut widget = Self { chapters };
+        widget.mark_same_numbers();
+        widget
+    }
+
+    fn mark_same_numbers(&mut self) {
+        let numbers: Vec<String> = self
+            .chapters
+            .iter()
+            .map(|chapter| chapter.chapter_number.clone())
+            .collect();
+
+        for chapter in self.chapters.iter_mut() {
+            chapter.has_same_number = numbers
+                .iter()
+                .filter(|number| **number == chapter.chapter_number)
+                .count()
+                > 1;
+        }
+    }
+
+    /// The chapter with the same number as the one at `index` by the first of `preferred_groups`
+    /// which translated it, or the one at `index` if none of them did
+    pub fn preferred_chapter(&self, index: usize, preferred_groups: &[String]) -> usize {
+        let Some(chapter) = self.chapters.get(index) else {
+            return index;
+        };
+
+        self.chapters
+            .iter()
+            .enumerate()
+            .filter(|(_, other)| other.chapter_number == chapter.chapter_number)
+            .filter_map(|(other_index, other)| {
+                group_priority(&other.scanlator, preferred_groups)
+                    .map(|priority| (priority, other_index))
+            })
+            .min()
+            .map_or(index, |(_, other_index)| other_index)
+    }
+
+    /// The chapter to read after the one at `current` in reading order, the chapters with the same
+    /// number as the current one are skipped. When the next number has several chapters the one by
+    /// the first of `preferred_groups` is picked, then the one by the same group and otherwise the
+    /// latest one
+    pub fn next_chapter(
+        &self,
+        current: usize,
+        order: ChapterOrder,
+        preferred_groups: &[String],
+    ) -> Option<usize> {
+        let current_chapter = self.chapters.get(current)?;
+
+        let mut following: Box<dyn Iterator<Item = usize>> = match order {
+            ChapterOrder::Ascending => Box::new(current + 1..self.chapters.len()),
+            ChapterOrder::Descending => Box::new((0..current).rev()),
+        };
+
+        let next_number = &following
+            .find(|index| self.chapters[*index].chapter_number != current_chapter.chapter_number)
+            .map(|index| &self.chapters[index])?
+            .chapter_number;
+
+        let candidates = || {
+            self.chapters
+                .iter()
+                .enumerate()
+                .filter(|(_, chapter)| chapter.chapter_number == *next_number)
+        };
+
+        candidates()
+            .filter_map(|(index, chapter)| {
+                group_priority(&chapter.scanlator, preferred_groups)
+                    .map(|priority| (priority, index))
+            })
+            .min()
+            .map(|(_, index)| (index, &self.chapters[index]))
+            .or_else(|| {
+                candidates().find(|(_, chapter)| chapter.scanlator == current_chapter.scanlator)
+            })
+            .or_else(|| candidates().max_by_key(|(_, chapter)| chapter.uploaded_at))
+            .map(|(index, _)| index)
     }
 }
 
+/// Position of the group in `preferred_groups`, the names are compared ignoring the case
+fn group_priority(scanlator: &str, preferred_groups: &[String]) -> Option<usize> {
+    preferred_groups
+        .iter()
+        .position(|group| !scanlator.is_empty() && group.eq_ignore_ascii_case(scanlator))
+}
+
 impl StatefulWidget for ChaptersListWidget {
     type State = tui_widget_list::ListState;
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
@@ -239,3 +345,65 @@ impl StatefulWidget for ChaptersListWidget {
         StatefulWidget::render(chapters_list, area, buf, state);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn chapter(number: &str, scanlator: &str, uploaded_at: i64) -> ChapterItem {
+        ChapterItem::new(
+            format!("{number} {scanlator}"),
+            String::new(),
+            number.to_string(),
+            String::new(),
+            scanlator.to_string(),
+            Languages::English,
+            uploaded_at,
+        )
+    }
+
+    #[test]
+    fn next_chapter_skips_and_picks_between_chapters_with_the_same_number() {
+        let mut widget = ChaptersListWidget {
+            chapters: vec![
+                chapter("1", "group a", 10),
+                chapter("1", "group b", 20),
+                chapter("2", "group b", 30),
+                chapter("2", "group c", 40),
+                chapter("3", "group c", 50),
+            ],
+        };
+        widget.mark_same_numbers();
+
+        assert!(widget.chapters[0].has_same_number);
+        assert!(!widget.chapters[4].has_same_number);
+
+        // The same group is preferred, otherwise the latest upload
+        assert_eq!(
+            Some(2),
+            widget.next_chapter(1, ChapterOrder::Ascending, &[])
+        );
+        assert_eq!(
+            Some(3),
+            widget.next_chapter(0, ChapterOrder::Ascending, &[])
+        );
+        assert_eq!(None, widget.next_chapter(4, ChapterOrder::Ascending, &[]));
+        assert_eq!(
+            Some(1),
+            widget.next_chapter(2, ChapterOrder::Descending, &[])
+        );
+        assert_eq!(
+            Some(3),
+            widget.next_chapter(4, ChapterOrder::Descending, &[])
+        );
+
+        // A preferred group comes before the same group
+        let preferred = vec!["Group D".to_string(), "group c".to_string()];
+        assert_eq!(
+            Some(3),
+            widget.next_chapter(1, ChapterOrder::Ascending, &preferred)
+        );
+        assert_eq!(3, widget.preferred_chapter(2, &preferred));
+        assert_eq!(0, widget.preferred_chapter(0, &preferred));
+    }
+}
@@ -6,7 +6,7 @@ use tui_widget_list::PreRender;
 pub enum PageItemState {
     Loading,
     FinishedLoad,
-    _NotFound,
+    NotFound,
 }
 
 #[derive(Clone)]
@@ -15,6 +15,7 @@ pub struct PagesItem {
     pub state: PageItemState,
     pub loading_state: ThrobberState,
     pub style: Style,
+    pub is_bookmarked: bool,
 }
 
 impl Widget for PagesItem {
@@ -26,7 +27,9 @@ impl Widget for PagesItem {
         let [chapter_number_area, loader_area] = layout.areas(area);
 
         Block::default().style(self.style).render(area, buf);
-        let page = Paragraph::new(format!("Page {}", self.number)).wrap(Wrap { trim: true });
+        let bookmark = if self.is_bookmarked { " 🔖" } else { "" };
+        let page =
+            Paragraph::new(format!("Page {}{bookmark}", self.number)).wrap(Wrap { trim: true });
 
         if self.state == PageItemState::Loading {
             let loader = Throbber::default()
@@ -38,6 +41,10 @@ impl Widget for PagesItem {
             page.render(chapter_number_area, buf);
 
             StatefulWidget::render(loader, loader_area, buf, &mut self.loading_state);
+        } else if self.state == PageItemState::NotFound {
+            page.render(chapter_number_area, buf);
+
+            Paragraph::new("Failed".red()).render(loader_area, buf);
         } else {
             page.render(area, buf);
         }
@@ -60,6 +67,7 @@ impl PagesItem {
             state: PageItemState::Loading,
             loading_state: ThrobberState::default(),
             style: Style::default(),
+            is_bookmarked: false,
         }
     }
     pub fn on_tick(&mut self) {
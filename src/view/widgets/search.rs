@@ -1,29 +1,41 @@
 use crate::backend::Data;
-use crate::common::Manga;
-use crate::utils::{from_manga_response, set_status_style, set_tags_style};
-use crate::PICKER;
+use crate::common::{Manga, MangaTag};
+use crate::config::MangaTuiConfig;
+use crate::global::INSTRUCTIONS_STYLE;
+use crate::picker;
+use crate::utils::{
+    fit_tags, format_search_item_title, from_manga_response, more_tags_label, render_cover_frame,
+    set_status_style, set_tag_group_style, set_tags_style, title_match_rank, truncate_to_width,
+};
 use ratatui::{prelude::*, widgets::*};
 use ratatui_image::protocol::StatefulProtocol;
 use ratatui_image::{Resize, StatefulImage};
+use serde::{Deserialize, Serialize};
 use throbber_widgets_tui::{Throbber, ThrobberState};
 use tui_widget_list::PreRender;
+use unicode_width::UnicodeWidthStr;
 
 pub struct MangaPreview<'a> {
     title: &'a str,
     description: &'a str,
-    tags: &'a Vec<String>,
+    tags: &'a Vec<MangaTag>,
     content_rating: &'a str,
     status: &'a str,
+    description_percentage: u16,
     loader_state: ThrobberState,
+    /// How many lines the description is scrolled, it's clamped when rendering so it can't go
+    /// further than the last line
+    description_scroll: Option<&'a mut u16>,
 }
 
 impl<'a> MangaPreview<'a> {
     pub fn new(
         title: &'a str,
         description: &'a str,
-        tags: &'a Vec<String>,
+        tags: &'a Vec<MangaTag>,
         content_rating: &'a str,
         status: &'a str,
+        description_percentage: u16,
         loader_state: ThrobberState,
     ) -> Self {
         Self {
@@ -32,10 +44,17 @@ impl<'a> MangaPreview<'a> {
             tags,
             content_rating,
             status,
+            description_percentage,
             loader_state,
+            description_scroll: None,
         }
     }
 
+    pub fn description_scroll(mut self, description_scroll: &'a mut u16) -> Self {
+        self.description_scroll = Some(description_scroll);
+        self
+    }
+
     pub fn render_cover_and_details_area(
         &mut self,
         area: Rect,
@@ -47,9 +66,10 @@ impl<'a> MangaPreview<'a> {
 
         self.render_details(details_area, buf);
 
-        if PICKER.is_some() {
+        if picker().is_some() {
             match state {
                 Some(image_state) => {
+                    let cover_area = render_cover_frame(cover_area, buf);
                     let cover = StatefulImage::new(None).resize(Resize::Fit(None));
                     StatefulWidget::render(cover, cover_area, buf, image_state)
                 }
@@ -75,15 +95,37 @@ impl<'a> MangaPreview<'a> {
     }
 
     pub fn render_description_area(self, area: Rect, buf: &mut Buffer) {
-        Block::bordered().title(self.title).render(area, buf);
+        Block::bordered()
+            .title(self.title)
+            .title_bottom(Line::from(vec![
+                "More / less description ".into(),
+                Span::raw("<+>/<->").style(*INSTRUCTIONS_STYLE),
+                " Cover / text ".into(),
+                Span::raw("<e>").style(*INSTRUCTIONS_STYLE),
+                " Scroll description ".into(),
+                Span::raw("<K>/<J>").style(*INSTRUCTIONS_STYLE),
+            ]))
+            .render(area, buf);
 
         let inner = area.inner(layout::Margin {
             horizontal: 1,
             vertical: 2,
         });
 
+        let max_scroll =
+            wrapped_lines_count(self.description, inner.width).saturating_sub(inner.height);
+
+        let scroll = match self.description_scroll {
+            Some(scroll) => {
+                *scroll = (*scroll).min(max_scroll);
+                *scroll
+            }
+            None => 0,
+        };
+
         Paragraph::new(self.description)
             .wrap(Wrap { trim: true })
+            .scroll((scroll, 0))
             .render(inner, buf);
     }
 
@@ -91,7 +133,18 @@ impl<'a> MangaPreview<'a> {
         let layout = Layout::vertical([Constraint::Percentage(20), Constraint::Percentage(80)]);
         let [details_area, tags_area] = layout.areas(area);
 
-        let tags_list: Vec<Span<'_>> = self.tags.iter().map(|tag| set_tags_style(tag)).collect();
+        let (fitting_tags, hidden_tags) = fit_tags(
+            self.tags,
+            tags_area.width as usize,
+            tags_area.height as usize,
+        );
+
+        let mut tags_list: Vec<Span<'_>> =
+            fitting_tags.into_iter().map(set_tag_group_style).collect();
+
+        if hidden_tags > 0 {
+            tags_list.push(more_tags_label(hidden_tags).dark_gray());
+        }
 
         let content_rating = set_tags_style(self.content_rating);
 
@@ -105,23 +158,141 @@ impl<'a> MangaPreview<'a> {
     }
 }
 
+/// Estimate how many lines a text takes once it's wrapped by words to the given width
+fn wrapped_lines_count(text: &str, width: u16) -> u16 {
+    let width = width.max(1) as usize;
+
+    text.lines()
+        .map(|line| {
+            let mut lines = 1;
+            let mut current_width = 0;
+            for word in line.split_whitespace() {
+                let word_width = word.width();
+                let needed_width = if current_width == 0 {
+                    word_width
+                } else {
+                    current_width + 1 + word_width
+                };
+
+                if needed_width <= width {
+                    current_width = needed_width;
+                } else {
+                    if current_width > 0 {
+                        lines += 1;
+                    }
+                    // Words longer than the width are split across lines
+                    lines += (word_width - 1) / width;
+                    current_width = (word_width - 1) % width + 1;
+                }
+            }
+            lines
+        })
+        .sum::<usize>()
+        .min(u16::MAX as usize) as u16
+}
+
 impl<'a> StatefulWidget for MangaPreview<'a> {
     type State = Option<Box<dyn StatefulProtocol>>;
 
     fn render(mut self, area: ratatui::prelude::Rect, buf: &mut Buffer, state: &mut Self::State) {
-        let [cover_details_area, description_area] =
-            Layout::vertical([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(area);
+        let [cover_details_area, description_area] = Layout::vertical([
+            Constraint::Percentage(100 - self.description_percentage),
+            Constraint::Percentage(self.description_percentage),
+        ])
+        .areas(area);
 
         self.render_cover_and_details_area(cover_details_area, buf, state);
         self.render_description_area(description_area, buf);
     }
 }
 
+/// How the search results are grouped, it's changed with `<G>` on the search page
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultsGrouping {
+    /// In the order given by mangadex
+    #[default]
+    None,
+    /// Ongoing first, then completed, hiatus and cancelled
+    Status,
+    /// Most recent year first
+    Year,
+}
+
+impl ResultsGrouping {
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::Status,
+            Self::Status => Self::Year,
+            Self::Year => Self::None,
+        }
+    }
+
+    /// Mangas without data for the grouping go last
+    fn sort_key(self, manga: &Manga) -> (u8, i64) {
+        match self {
+            Self::None => (0, 0),
+            Self::Status => {
+                let rank = match manga.status.to_lowercase().as_str() {
+                    "ongoing" => 0,
+                    "completed" => 1,
+                    "hiatus" => 2,
+                    "cancelled" => 3,
+                    _ => 4,
+                };
+                (rank, 0)
+            }
+            Self::Year => match manga.year {
+                Some(year) => (0, -i64::from(year)),
+                None => (1, 0),
+            },
+        }
+    }
+
+    /// Header of the group the manga is in, `None` if the results are not grouped
+    pub fn label(self, manga: &Manga) -> Option<String> {
+        match self {
+            Self::None => None,
+            Self::Status => {
+                let mut status = manga.status.chars();
+                Some(match status.next() {
+                    Some(first) => first.to_uppercase().chain(status).collect(),
+                    None => "Unknown status".to_string(),
+                })
+            }
+            Self::Year => Some(
+                manga
+                    .year
+                    .map_or("Unknown year".to_string(), |year| year.to_string()),
+            ),
+        }
+    }
+}
+
+/// The borders plus one line for the title
+pub const MIN_MANGA_ITEM_HEIGHT: u16 = 3;
+pub const MAX_MANGA_ITEM_HEIGHT: u16 = 8;
+
 #[derive(Default, Clone)]
 pub struct MangaItem {
     pub manga: Manga,
     pub style: Style,
     pub image_state: Option<Box<dyn StatefulProtocol>>,
+    /// Rows used by the item on the list, taken from `search_item_height` when rendering
+    pub height: u16,
+    /// The title line built from `search_item_title`
+    pub title: String,
+    /// Whether a line is drawn below the item, it's part of the item but outside of its border so
+    /// it's never highlighted
+    pub separator: bool,
+    pub show_tags: bool,
+    /// Name of the group the item is the first of, drawn above it so it's never highlighted
+    pub header: Option<String>,
+    /// Style and color of the left edge marker used when the item is selected, taken from
+    /// `selection_style` when rendering
+    pub selected_style: Style,
+    pub selected_marker: Option<Color>,
+    is_selected: bool,
 }
 
 impl Widget for MangaItem {
@@ -129,7 +300,65 @@ impl Widget for MangaItem {
     where
         Self: Sized,
     {
-        Paragraph::new(self.manga.title)
+        let area = match self.header.as_ref() {
+            Some(header) => {
+                let [header_area, item_area] =
+                    Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+                Paragraph::new(header.as_str().bold())
+                    .style(*INSTRUCTIONS_STYLE)
+                    .render(header_area, buf);
+                item_area
+            }
+            None => area,
+        };
+
+        let area = if self.separator {
+            let [item_area, separator_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+            Paragraph::new("─".repeat(separator_area.width as usize))
+                .style(Style::default().fg(Color::DarkGray))
+                .render(separator_area, buf);
+            item_area
+        } else {
+            area
+        };
+
+        let marker_color = self.selected_marker.filter(|_| self.is_selected);
+        let area = if let Some(color) = marker_color {
+            let [marker_area, item_area] =
+                Layout::horizontal([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+            let marker: Vec<Line<'_>> = (0..marker_area.height).map(|_| "▌".into()).collect();
+            Paragraph::new(marker)
+                .style(Style::default().fg(color))
+                .render(marker_area, buf);
+            item_area
+        } else {
+            area
+        };
+
+        // The details are only visible when the height leaves room for them after the title
+        let details = Line::from(vec![
+            set_status_style(&self.manga.status),
+            " ".into(),
+            set_tags_style(&self.manga.content_rating),
+        ]);
+
+        // Long titles are cut instead of wrapped so the details stay on their line
+        let title = truncate_to_width(&self.title, area.width.saturating_sub(2) as usize);
+
+        let mut lines = vec![Line::from(title), details];
+
+        if self.show_tags {
+            lines.push(Line::from(
+                self.manga
+                    .tags
+                    .iter()
+                    .map(set_tag_group_style)
+                    .collect::<Vec<Span<'_>>>(),
+            ));
+        }
+
+        Paragraph::new(lines)
             .wrap(Wrap { trim: true })
             .block(Block::bordered().style(self.style))
             .style(self.style)
@@ -140,9 +369,10 @@ impl Widget for MangaItem {
 impl PreRender for MangaItem {
     fn pre_render(&mut self, context: &tui_widget_list::PreRenderContext) -> u16 {
         if context.is_selected {
-            self.style = Style::default().fg(Color::Yellow);
+            self.style = self.selected_style;
+            self.is_selected = true;
         }
-        4
+        self.height + u16::from(self.separator) + u16::from(self.header.is_some())
     }
 }
 
@@ -159,6 +389,14 @@ impl MangaItem {
             manga,
             image_state,
             style: Style::default(),
+            height: MIN_MANGA_ITEM_HEIGHT + 1,
+            title: String::new(),
+            separator: false,
+            show_tags: true,
+            header: None,
+            selected_style: Style::default().fg(Color::Yellow),
+            selected_marker: None,
+            is_selected: false,
         }
     }
 }
@@ -166,6 +404,12 @@ impl MangaItem {
 #[derive(Default, Clone)]
 pub struct ListMangasFoundWidget {
     pub mangas: Vec<MangaItem>,
+    grouping: ResultsGrouping,
+    /// Set while exact titles come first, the mangas whose title is closest to it go first in each
+    /// group
+    exact_title: Option<String>,
+    /// Ids of the mangas in the order they were found, it's restored when they are not grouped
+    found_order: Vec<String>,
 }
 
 impl ListMangasFoundWidget {
@@ -176,14 +420,103 @@ impl ListMangasFoundWidget {
             mangas.push(MangaItem::from(manga));
         }
 
-        Self { mangas }
+        let found_order = mangas.iter().map(|item| item.manga.id.clone()).collect();
+
+        Self {
+            mangas,
+            grouping: ResultsGrouping::None,
+            exact_title: None,
+            found_order,
+        }
+    }
+
+    pub fn group(&mut self, grouping: ResultsGrouping) {
+        self.grouping = grouping;
+        self.sort();
+    }
+
+    /// `None` puts the mangas back in the order they were found
+    pub fn put_exact_title_first(&mut self, title: Option<String>) {
+        self.exact_title = title;
+        self.sort();
+    }
+
+    /// The mangas of each group keep the order they were found in, unless their title is closer to
+    /// `exact_title`
+    fn sort(&mut self) {
+        let found_order = &self.found_order;
+        let grouping = self.grouping;
+        let exact_title = self.exact_title.as_deref();
+        self.mangas.sort_by_key(|item| {
+            let found_at = found_order.iter().position(|id| *id == item.manga.id);
+            let title_rank =
+                exact_title.map_or(0, |title| title_match_rank(&item.manga.title, title));
+            (grouping.sort_key(&item.manga), title_rank, found_at)
+        });
     }
 }
 
 impl StatefulWidgetRef for ListMangasFoundWidget {
     type State = tui_widget_list::ListState;
     fn render_ref(&self, area: ratatui::prelude::Rect, buf: &mut Buffer, state: &mut Self::State) {
-        let list = tui_widget_list::List::new(self.mangas.clone());
+        let config = MangaTuiConfig::get();
+        let height = config
+            .search_item_height
+            .clamp(MIN_MANGA_ITEM_HEIGHT, MAX_MANGA_ITEM_HEIGHT);
+
+        // Without the tags there is nothing to show after the title and the details, so the
+        // items don't take more rows than those need
+        let height = if config.search_item_tags {
+            height
+        } else {
+            height.min(MIN_MANGA_ITEM_HEIGHT + 1)
+        };
+
+        let mut previous_group = None;
+
+        let list = tui_widget_list::List::new(
+            self.mangas
+                .iter()
+                .cloned()
+                .map(|manga| {
+                    let group = self.grouping.label(&manga.manga);
+                    let header = group
+                        .clone()
+                        .filter(|group| previous_group.as_ref() != Some(group));
+                    previous_group = group;
+                    (manga, header)
+                })
+                .map(|(manga, header)| MangaItem {
+                    header,
+                    height,
+                    title: format_search_item_title(&config.search_item_title, &manga.manga),
+                    separator: config.search_item_separator,
+                    show_tags: config.search_item_tags,
+                    selected_style: config.selection_style.style(),
+                    selected_marker: config
+                        .selection_style
+                        .marker
+                        .then(|| config.selection_style.color()),
+                    ..manga
+                })
+                .collect(),
+        );
         StatefulWidget::render(list, area, buf, state);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn wrapped_lines_are_counted_with_the_display_width() {
+        assert_eq!(1, wrapped_lines_count("One Piece", 9));
+        assert_eq!(2, wrapped_lines_count("One Piece", 8));
+
+        // Each character takes two columns, so the word needs 10 of them
+        assert_eq!(1, wrapped_lines_count("ワンピース", 10));
+        assert_eq!(2, wrapped_lines_count("ワンピース", 6));
+        assert_eq!(2, wrapped_lines_count("ワンピース One", 10));
+    }
+}